@@ -0,0 +1,330 @@
+//! A deterministic, manually-driven executor and virtual clock for tests.
+//!
+//! The executor installed by [`Executor::init_testing`] never makes progress
+//! on its own: spawned tasks are queued until the test explicitly drives them
+//! with [`run_until_stalled`], [`block_on`], or [`advance`]. This means a test
+//! can observe the exact state of the system between “ticks”, instead of
+//! awaiting [`Executor::tick`] and hoping all the work has been done.
+//!
+//! Tasks and timers are stored per thread, so independent tests running in
+//! parallel on different threads do not interfere with one another. Tasks
+//! spawned with [`Executor::spawn`] from another thread are queued on that
+//! thread, and will only run if that thread drives the executor.
+//!
+//! ```
+//! use any_spawner::{testing, Executor};
+//! use std::{cell::Cell, rc::Rc, time::Duration};
+//!
+//! _ = Executor::init_testing();
+//!
+//! let done = Rc::new(Cell::new(false));
+//! Executor::spawn_local({
+//!     let done = Rc::clone(&done);
+//!     async move {
+//!         testing::sleep(Duration::from_secs(1)).await;
+//!         done.set(true);
+//!     }
+//! });
+//!
+//! // nothing has run yet
+//! assert_eq!(testing::pending_tasks(), 1);
+//!
+//! // the task runs until it hits the timer
+//! testing::run_until_stalled();
+//! assert!(!done.get());
+//! assert_eq!(testing::pending_timers(), 1);
+//!
+//! // advancing the virtual clock fires the timer and runs the task to completion
+//! testing::advance(Duration::from_secs(1));
+//! assert!(done.get());
+//! assert_eq!(testing::pending_tasks(), 0);
+//! ```
+
+use crate::{Executor, ExecutorError, ExecutorFns, PinnedLocalFuture};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, HashMap, VecDeque},
+    future::{Future, IntoFuture},
+    pin::{pin, Pin},
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    time::Duration,
+};
+
+type TaskId = u64;
+
+/// The queue of tasks that have been woken, and are ready to be polled.
+///
+/// This is shared with every [`Waker`] handed out by the executor, which must be `Send + Sync`.
+type ReadyQueue = Arc<Mutex<VecDeque<TaskId>>>;
+
+#[derive(Default)]
+struct TestRuntime {
+    next_id: Cell<TaskId>,
+    tasks: RefCell<HashMap<TaskId, PinnedLocalFuture<()>>>,
+    ready: ReadyQueue,
+    running: Cell<bool>,
+    clock: RefCell<VirtualClock>,
+}
+
+#[derive(Default)]
+struct VirtualClock {
+    now: Duration,
+    next_timer_id: u64,
+    timers: BTreeMap<(Duration, u64), Waker>,
+}
+
+thread_local! {
+    static RUNTIME: TestRuntime = TestRuntime::default();
+}
+
+struct TaskWaker {
+    id: TaskId,
+    ready: ReadyQueue,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let mut ready = self.ready.lock().unwrap_or_else(|e| e.into_inner());
+        if !ready.contains(&self.id) {
+            ready.push_back(self.id);
+        }
+    }
+}
+
+impl Executor {
+    /// Globally sets the deterministic test executor as the executor used to spawn tasks.
+    ///
+    /// Tasks spawned with this executor only make progress when they are driven by
+    /// [`testing::run_until_stalled`](run_until_stalled), [`testing::block_on`](block_on),
+    /// or [`testing::advance`](advance). [`Executor::poll_local`] runs the current thread's
+    /// tasks until they stall.
+    ///
+    /// Returns `Err(_)` if a global executor has already been set.
+    ///
+    /// Requires the `testing` feature to be activated on this crate.
+    #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+    pub fn init_testing() -> Result<(), ExecutorError> {
+        let executor_impl = ExecutorFns {
+            spawn: |fut| spawn_task(fut),
+            spawn_local: spawn_task,
+            poll_local: || {
+                run_until_stalled();
+            },
+        };
+        crate::EXECUTOR_FNS
+            .set(executor_impl)
+            .map_err(|_| ExecutorError::AlreadySet)
+    }
+}
+
+fn spawn_task(fut: PinnedLocalFuture<()>) {
+    RUNTIME.with(|rt| {
+        let id = rt.next_id.get();
+        rt.next_id.set(id + 1);
+        rt.tasks.borrow_mut().insert(id, fut);
+        rt.ready
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_back(id);
+    });
+}
+
+/// Polls every task on the current thread that is ready to make progress, until none are.
+///
+/// Returns the number of tasks that ran to completion.
+///
+/// Calling this from within a task that is being driven by the test executor does nothing.
+pub fn run_until_stalled() -> usize {
+    RUNTIME.with(|rt| {
+        if rt.running.replace(true) {
+            return 0;
+        }
+
+        let mut completed = 0;
+        loop {
+            let next = rt
+                .ready
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .pop_front();
+            let Some(id) = next else {
+                break;
+            };
+            // remove the task while it's being polled, so that it can spawn other tasks
+            let Some(mut task) = rt.tasks.borrow_mut().remove(&id) else {
+                continue;
+            };
+            let waker = Waker::from(Arc::new(TaskWaker {
+                id,
+                ready: Arc::clone(&rt.ready),
+            }));
+            let mut cx = Context::from_waker(&waker);
+            match task.as_mut().poll(&mut cx) {
+                Poll::Ready(()) => completed += 1,
+                Poll::Pending => {
+                    rt.tasks.borrow_mut().insert(id, task);
+                }
+            }
+        }
+
+        rt.running.set(false);
+        completed
+    })
+}
+
+/// Runs a future to completion on the current thread, driving the test executor whenever
+/// the future is not yet ready.
+///
+/// This does not advance the virtual clock.
+///
+/// # Panics
+/// Panics if the future is still pending once every spawned task has stalled, as it can
+/// never complete without outside intervention (such as calling [`advance`]).
+#[track_caller]
+pub fn block_on<T>(fut: impl IntoFuture<Output = T>) -> T {
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = pin!(fut.into_future());
+    loop {
+        if let Poll::Ready(value) = fut.as_mut().poll(&mut cx) {
+            return value;
+        }
+        let had_ready = RUNTIME.with(|rt| {
+            !rt.ready
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .is_empty()
+        });
+        if !had_ready {
+            panic!(
+                "testing::block_on stalled: the future is pending, but no \
+                 tasks are ready to run ({} pending tasks, {} pending timers)",
+                pending_tasks(),
+                pending_timers()
+            );
+        }
+        run_until_stalled();
+    }
+}
+
+/// Returns the number of tasks on the current thread that have been spawned but have not yet
+/// completed.
+pub fn pending_tasks() -> usize {
+    RUNTIME.with(|rt| rt.tasks.borrow().len())
+}
+
+/// Returns `true` if there are no tasks waiting to be polled on the current thread.
+pub fn is_stalled() -> bool {
+    RUNTIME.with(|rt| {
+        rt.ready
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    })
+}
+
+/// Returns the amount of virtual time that has elapsed on the current thread.
+pub fn now() -> Duration {
+    RUNTIME.with(|rt| rt.clock.borrow().now)
+}
+
+/// Returns the number of [`sleep`] timers on the current thread that have not yet fired.
+pub fn pending_timers() -> usize {
+    RUNTIME.with(|rt| rt.clock.borrow().timers.len())
+}
+
+/// Advances the virtual clock by the given duration.
+///
+/// Timers fire in deadline order. After each timer fires, the executor runs until it stalls,
+/// so that timers created while handling an earlier timer fire at the correct virtual time.
+pub fn advance(by: Duration) {
+    let target = now() + by;
+    run_until_stalled();
+    loop {
+        let next = RUNTIME.with(|rt| {
+            let mut clock = rt.clock.borrow_mut();
+            let first = clock.timers.first_key_value().map(|(key, _)| *key)?;
+            if first.0 > target {
+                return None;
+            }
+            let waker = clock.timers.remove(&first)?;
+            clock.now = first.0;
+            Some(waker)
+        });
+        let Some(waker) = next else {
+            break;
+        };
+        waker.wake();
+        run_until_stalled();
+    }
+    RUNTIME.with(|rt| rt.clock.borrow_mut().now = target);
+    run_until_stalled();
+}
+
+/// Returns a future that resolves once the virtual clock has advanced by `duration`.
+///
+/// The deadline is measured from the moment the future is first polled.
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        duration,
+        deadline: None,
+    }
+}
+
+/// A future that resolves once the virtual clock reaches its deadline. See [`sleep`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Sleep {
+    duration: Duration,
+    deadline: Option<(Duration, u64)>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        RUNTIME.with(|rt| {
+            let mut clock = rt.clock.borrow_mut();
+            let key = match self.deadline {
+                Some(key) => key,
+                None => {
+                    let id = clock.next_timer_id;
+                    clock.next_timer_id += 1;
+                    let key = (clock.now + self.duration, id);
+                    self.deadline = Some(key);
+                    key
+                }
+            };
+            if clock.now >= key.0 {
+                clock.timers.remove(&key);
+                Poll::Ready(())
+            } else {
+                clock.timers.insert(key, cx.waker().clone());
+                Poll::Pending
+            }
+        })
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(key) = self.deadline {
+            _ = RUNTIME.try_with(|rt| {
+                if let Ok(mut clock) = rt.clock.try_borrow_mut() {
+                    clock.timers.remove(&key);
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "testing")]
+
+use any_spawner::{testing, Executor};
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+#[test]
+fn test_testing_executor_is_manual() {
+    _ = Executor::init_testing();
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    Executor::spawn_local({
+        let log = Rc::clone(&log);
+        async move {
+            log.borrow_mut().push("first");
+            Executor::spawn_local({
+                let log = Rc::clone(&log);
+                async move {
+                    log.borrow_mut().push("nested");
+                }
+            });
+        }
+    });
+
+    // spawned tasks don't run until the executor is driven
+    assert!(log.borrow().is_empty());
+    assert_eq!(testing::pending_tasks(), 1);
+
+    assert_eq!(testing::run_until_stalled(), 2);
+    assert_eq!(*log.borrow(), ["first", "nested"]);
+    assert_eq!(testing::pending_tasks(), 0);
+    assert!(testing::is_stalled());
+}
+
+#[test]
+fn test_testing_executor_virtual_clock() {
+    _ = Executor::init_testing();
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let start = testing::now();
+
+    for (label, ms) in [("slow", 300), ("fast", 100), ("medium", 200)] {
+        let log = Rc::clone(&log);
+        Executor::spawn_local(async move {
+            testing::sleep(Duration::from_millis(ms)).await;
+            log.borrow_mut().push((label, testing::now() - start));
+        });
+    }
+
+    testing::run_until_stalled();
+    assert_eq!(testing::pending_tasks(), 3);
+    assert_eq!(testing::pending_timers(), 3);
+
+    testing::advance(Duration::from_millis(150));
+    assert_eq!(*log.borrow(), [("fast", Duration::from_millis(100))]);
+
+    testing::advance(Duration::from_millis(150));
+    assert_eq!(
+        *log.borrow(),
+        [
+            ("fast", Duration::from_millis(100)),
+            ("medium", Duration::from_millis(200)),
+            ("slow", Duration::from_millis(300)),
+        ]
+    );
+    assert_eq!(testing::pending_tasks(), 0);
+    assert_eq!(testing::pending_timers(), 0);
+    assert_eq!(testing::now() - start, Duration::from_millis(300));
+}
+
+#[test]
+fn test_testing_executor_block_on() {
+    _ = Executor::init_testing();
+
+    let (tx, rx) = futures::channel::oneshot::channel();
+    Executor::spawn(async move {
+        Executor::tick().await;
+        tx.send(42).unwrap();
+    });
+
+    assert_eq!(testing::block_on(rx), Ok(42));
+}
+
+#[test]
+#[should_panic(expected = "stalled")]
+fn test_testing_executor_block_on_stalls() {
+    _ = Executor::init_testing();
+
+    testing::block_on(testing::sleep(Duration::from_secs(1)));
+}
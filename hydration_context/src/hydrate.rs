@@ -3,19 +3,26 @@
 // as a result, we'll just allow deprecated for now
 #![allow(deprecated)]
 
-use super::{SerializedDataId, SharedContext};
+use super::{MemoKey, SerializedDataId, SharedContext};
 use crate::{PinnedFuture, PinnedStream};
 use core::fmt::Debug;
-use js_sys::Array;
+use futures::channel::mpsc;
+use js_sys::{Array, Object, Reflect};
+use or_poisoned::OrPoisoned;
 use std::{
+    any::Any,
+    collections::HashMap,
     fmt::Display,
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
-        LazyLock,
+        Arc, LazyLock, Mutex,
     },
 };
 use throw_error::{Error, ErrorId};
-use wasm_bindgen::{prelude::wasm_bindgen, JsCast};
+use wasm_bindgen::{
+    prelude::{wasm_bindgen, Closure},
+    JsCast, JsValue,
+};
 
 #[wasm_bindgen]
 extern "C" {
@@ -52,6 +59,13 @@ fn serialized_errors() -> Vec<(SerializedDataId, ErrorId, Error)> {
     })
 }
 
+/// Reads one of the globals used to stream chunks of data from the server, if it has been defined.
+fn stream_global(name: &str) -> Option<JsValue> {
+    Reflect::get(&js_sys::global(), &JsValue::from_str(name))
+        .ok()
+        .filter(|value| !value.is_undefined())
+}
+
 fn incomplete_chunks() -> Vec<SerializedDataId> {
     __INCOMPLETE_CHUNKS.with(|i| {
         i.iter()
@@ -83,6 +97,7 @@ pub struct HydrateSharedContext {
     during_hydration: AtomicBool,
     errors: LazyLock<Vec<(SerializedDataId, ErrorId, Error)>>,
     incomplete: LazyLock<Vec<SerializedDataId>>,
+    memo: Mutex<HashMap<MemoKey, Arc<dyn Any + Send + Sync>>>,
 }
 
 impl HydrateSharedContext {
@@ -94,6 +109,7 @@ impl HydrateSharedContext {
             during_hydration: AtomicBool::new(true),
             errors: LazyLock::new(serialized_errors),
             incomplete: LazyLock::new(incomplete_chunks),
+            memo: Default::default(),
         }
     }
 
@@ -108,6 +124,7 @@ impl HydrateSharedContext {
             during_hydration: AtomicBool::new(true),
             errors: LazyLock::new(serialized_errors),
             incomplete: LazyLock::new(incomplete_chunks),
+            memo: Default::default(),
         }
     }
 }
@@ -134,6 +151,75 @@ impl SharedContext for HydrateSharedContext {
         __RESOLVED_RESOURCES.with(|r| r.get(id.0 as u32).as_string())
     }
 
+    fn read_stream(
+        &self,
+        id: &SerializedDataId,
+    ) -> Option<PinnedStream<String>> {
+        let streamed = stream_global("__STREAMED_CHUNKS")?;
+        let complete = stream_global("__COMPLETE_STREAMS")?
+            .dyn_into::<Array>()
+            .ok()?;
+        let listeners = stream_global("__STREAM_LISTENERS")?;
+        let key = JsValue::from_f64(id.0 as f64);
+
+        let chunks = {
+            let key = key.clone();
+            move || {
+                Reflect::get(&streamed, &key)
+                    .ok()
+                    .and_then(|chunks| chunks.dyn_into::<Array>().ok())
+            }
+        };
+        let is_complete = {
+            let key = key.clone();
+            move || complete.includes(&key, 0)
+        };
+        let is_pending = stream_global("__PENDING_STREAMS")
+            .and_then(|pending| pending.dyn_into::<Array>().ok())
+            .is_some_and(|pending| pending.includes(&key, 0));
+        if chunks().is_none() && !is_complete() && !is_pending {
+            return None;
+        }
+
+        // sends any chunks that have arrived since the last call, and returns `true` once the
+        // server's stream has ended
+        let (tx, rx) = mpsc::unbounded();
+        let mut sent = 0;
+        let mut flush = move || {
+            if let Some(chunks) = chunks() {
+                for chunk in chunks.iter().skip(sent) {
+                    sent += 1;
+                    if let Some(chunk) = chunk.as_string() {
+                        _ = tx.unbounded_send(chunk);
+                    }
+                }
+            }
+            let done = is_complete();
+            if done {
+                tx.close_channel();
+            }
+            done
+        };
+
+        if !flush() {
+            let listener = Closure::<dyn FnMut()>::new({
+                let listeners = listeners.clone();
+                let key = key.clone();
+                move || {
+                    if flush() {
+                        _ = Reflect::delete_property(
+                            listeners.unchecked_ref::<Object>(),
+                            &key,
+                        );
+                    }
+                }
+            });
+            _ = Reflect::set(&listeners, &key, &listener.into_js_value());
+        }
+
+        Some(Box::pin(rx))
+    }
+
     fn await_data(&self, _id: &SerializedDataId) -> Option<String> {
         todo!()
     }
@@ -147,7 +233,8 @@ impl SharedContext for HydrateSharedContext {
     }
 
     fn hydration_complete(&self) {
-        self.during_hydration.store(false, Ordering::Relaxed)
+        self.during_hydration.store(false, Ordering::Relaxed);
+        self.memo.lock().or_poisoned().clear();
     }
 
     fn get_is_hydrating(&self) -> bool {
@@ -201,4 +288,12 @@ impl SharedContext for HydrateSharedContext {
     fn get_incomplete_chunk(&self, id: &SerializedDataId) -> bool {
         self.incomplete.iter().any(|entry| entry == id)
     }
+
+    fn memoized(&self, key: &MemoKey) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.memo.lock().or_poisoned().get(key).cloned()
+    }
+
+    fn memoize(&self, key: MemoKey, value: Arc<dyn Any + Send + Sync>) {
+        self.memo.lock().or_poisoned().insert(key, value);
+    }
 }
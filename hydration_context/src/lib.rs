@@ -26,9 +26,21 @@ use futures::Stream;
 pub use hydrate::*;
 use serde::{Deserialize, Serialize};
 pub use ssr::*;
-use std::{fmt::Debug, future::Future, pin::Pin};
+use std::{
+    any::{Any, TypeId},
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+};
 use throw_error::{Error, ErrorId};
 
+/// The key of a value in the request-scoped cache of a [`SharedContext`].
+///
+/// This is the [`TypeId`] of whatever identifies the kind of work being shared, along with a
+/// string that identifies its inputs.
+pub type MemoKey = (TypeId, String);
+
 /// Type alias for a boxed [`Future`].
 pub type PinnedFuture<T> = Pin<Box<dyn Future<Output = T> + Send + Sync>>;
 /// Type alias for a boxed [`Future`] that is `!Send`.
@@ -78,9 +90,39 @@ pub trait SharedContext: Debug {
     /// from the server to the client. This will be polled as part of the process of
     /// building the HTTP response, *not* when it is first created.
     ///
+    /// The data is sent as a string. Data in a binary format should be encoded as text (for
+    /// example, as base64) before it is written, and decoded after it is read.
+    ///
     /// In browser implementations, this should be a no-op.
     fn write_async(&self, id: SerializedDataId, fut: PinnedFuture<String>);
 
+    /// The given [`Stream`] yields a series of chunks of data that can be serialized from the
+    /// server to the client. Each chunk will be sent to the client as soon as it is ready,
+    /// as part of the process of building the HTTP response, and the client will be told
+    /// when the stream has ended.
+    ///
+    /// The first chunk can also be read with [`read_data`](Self::read_data), like data written
+    /// with [`write_async`](Self::write_async).
+    ///
+    /// In browser implementations, this should be a no-op.
+    fn write_stream(&self, id: SerializedDataId, stream: PinnedStream<String>) {
+        _ = (id, stream);
+    }
+
+    /// Returns a [`Stream`] of every chunk of data that the server has sent, or will send,
+    /// for a stream written with [`write_stream`](Self::write_stream). The stream ends when
+    /// the server's stream has ended.
+    ///
+    /// On the server and in client-side rendered implementations, and if the server did not
+    /// write a stream with this ID, this should return [`None`].
+    fn read_stream(
+        &self,
+        id: &SerializedDataId,
+    ) -> Option<PinnedStream<String>> {
+        _ = id;
+        None
+    }
+
     /// Reads the current value of some data from the shared context, if it has been
     /// sent from the server. This returns the serialized data as a `String` that should
     /// be deserialized.
@@ -165,4 +207,24 @@ pub trait SharedContext: Debug {
 
     /// Checks whether this chunk is being sent from the server before all its data have loaded.
     fn get_incomplete_chunk(&self, id: &SerializedDataId) -> bool;
+
+    /// Returns a value previously stored under `key` with [`memoize`](Self::memoize), if any.
+    ///
+    /// This is a request-scoped cache: on the server it lives exactly as long as the response
+    /// being rendered, and in the browser it lives as long as the initial hydration. It can be
+    /// used to share a single piece of async work between identical calls within one request.
+    ///
+    /// The default implementation never stores anything, and always returns [`None`].
+    fn memoized(&self, key: &MemoKey) -> Option<Arc<dyn Any + Send + Sync>> {
+        _ = key;
+        None
+    }
+
+    /// Stores a value under `key` in the request-scoped cache, so that it can be read back
+    /// with [`memoized`](Self::memoized).
+    ///
+    /// The default implementation is a no-op.
+    fn memoize(&self, key: MemoKey, value: Arc<dyn Any + Send + Sync>) {
+        _ = (key, value);
+    }
 }
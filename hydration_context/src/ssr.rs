@@ -1,4 +1,4 @@
-use super::{SerializedDataId, SharedContext};
+use super::{MemoKey, SerializedDataId, SharedContext};
 use crate::{PinnedFuture, PinnedStream};
 use futures::{
     future::join_all,
@@ -7,7 +7,8 @@ use futures::{
 };
 use or_poisoned::OrPoisoned;
 use std::{
-    collections::HashSet,
+    any::Any,
+    collections::{HashMap, HashSet},
     fmt::{Debug, Write},
     mem,
     pin::Pin,
@@ -20,8 +21,10 @@ use std::{
 use throw_error::{Error, ErrorId};
 
 type AsyncDataBuf = Arc<RwLock<Vec<(SerializedDataId, PinnedFuture<String>)>>>;
+type StreamDataBuf = Arc<RwLock<Vec<PendingStream>>>;
 type ErrorBuf = Arc<RwLock<Vec<(SerializedDataId, ErrorId, Error)>>>;
 type SealedErrors = Arc<RwLock<HashSet<SerializedDataId>>>;
+type MemoCache = Mutex<HashMap<MemoKey, Arc<dyn Any + Send + Sync>>>;
 
 #[derive(Default)]
 /// The shared context that should be used on the server side.
@@ -31,10 +34,12 @@ pub struct SsrSharedContext {
     is_hydrating: AtomicBool,
     sync_buf: RwLock<Vec<ResolvedData>>,
     async_buf: AsyncDataBuf,
+    stream_buf: StreamDataBuf,
     errors: ErrorBuf,
     sealed_error_boundaries: SealedErrors,
     deferred: Mutex<Vec<PinnedFuture<()>>>,
     incomplete: Arc<Mutex<Vec<SerializedDataId>>>,
+    memo: MemoCache,
 }
 
 impl SsrSharedContext {
@@ -111,6 +116,14 @@ impl SharedContext for SsrSharedContext {
         self.async_buf.write().or_poisoned().push((id, fut))
     }
 
+    fn write_stream(&self, id: SerializedDataId, stream: PinnedStream<String>) {
+        self.stream_buf.write().or_poisoned().push(PendingStream {
+            id,
+            started: false,
+            stream,
+        })
+    }
+
     fn read_data(&self, _id: &SerializedDataId) -> Option<String> {
         None
     }
@@ -205,11 +218,22 @@ impl SharedContext for SsrSharedContext {
         }
         initial_chunk.push_str("];");
 
+        // pending streams, and the chunks that will be pushed for them
+        initial_chunk.push_str("__PENDING_STREAMS=[");
+        for pending in self.stream_buf.read().or_poisoned().iter() {
+            _ = write!(&mut initial_chunk, "{},", pending.id.0);
+        }
+        initial_chunk.push_str("];");
+        initial_chunk.push_str(
+            "__STREAMED_CHUNKS={};__COMPLETE_STREAMS=[];__STREAM_LISTENERS={};",
+        );
+
         // resolvers
         initial_chunk.push_str("__RESOURCE_RESOLVERS=[];");
 
         let async_data = AsyncDataStream {
             async_buf: Arc::clone(&self.async_buf),
+            stream_buf: Arc::clone(&self.stream_buf),
             errors: Arc::clone(&self.errors),
             sealed_error_boundaries: Arc::clone(&self.sealed_error_boundaries),
         };
@@ -262,10 +286,25 @@ impl SharedContext for SsrSharedContext {
             .iter()
             .any(|entry| entry == id)
     }
+
+    fn memoized(&self, key: &MemoKey) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.memo.lock().or_poisoned().get(key).cloned()
+    }
+
+    fn memoize(&self, key: MemoKey, value: Arc<dyn Any + Send + Sync>) {
+        self.memo.lock().or_poisoned().insert(key, value);
+    }
+}
+
+struct PendingStream {
+    id: SerializedDataId,
+    started: bool,
+    stream: PinnedStream<String>,
 }
 
 struct AsyncDataStream {
     async_buf: AsyncDataBuf,
+    stream_buf: StreamDataBuf,
     errors: ErrorBuf,
     sealed_error_boundaries: SealedErrors,
 }
@@ -296,6 +335,52 @@ impl Stream for AsyncDataStream {
                 }
             }
         }
+        drop(async_buf);
+
+        let mut stream_buf = self.stream_buf.write().or_poisoned();
+        for mut pending in mem::take(&mut *stream_buf) {
+            let id = pending.id.0;
+            let mut changed = false;
+            let done = loop {
+                match pending.stream.as_mut().poll_next(cx) {
+                    Poll::Pending => break false,
+                    Poll::Ready(None) => break true,
+                    Poll::Ready(Some(chunk)) => {
+                        // see the error path below: Debug-format, then escape `<`
+                        let chunk =
+                            format!("{chunk:?}").replace('<', "\\u003c");
+                        // the first chunk is also the initial value used for hydration
+                        if !pending.started {
+                            pending.started = true;
+                            _ = write!(
+                                resolved,
+                                "__RESOLVED_RESOURCES[{id}] = {chunk};"
+                            );
+                        }
+                        _ = write!(
+                            resolved,
+                            "(__STREAMED_CHUNKS[{id}]=__STREAMED_CHUNKS[{id}]||[]).push({chunk});"
+                        );
+                        changed = true;
+                    }
+                }
+            };
+            if done {
+                _ = write!(resolved, "__COMPLETE_STREAMS.push({id});");
+            }
+            if changed || done {
+                _ = write!(
+                    resolved,
+                    "__STREAM_LISTENERS[{id}]&&__STREAM_LISTENERS[{id}]();"
+                );
+            }
+            if !done {
+                stream_buf.push(pending);
+            }
+        }
+        let streams_empty = stream_buf.is_empty();
+        drop(stream_buf);
+
         let sealed = self.sealed_error_boundaries.read().or_poisoned();
         for error in mem::take(&mut *self.errors.write().or_poisoned()) {
             if !sealed.contains(&error.0) {
@@ -311,7 +396,8 @@ impl Stream for AsyncDataStream {
             }
         }
 
-        if async_buf.is_empty() && resolved.is_empty() {
+        let async_empty = self.async_buf.read().or_poisoned().is_empty();
+        if async_empty && streams_empty && resolved.is_empty() {
             return Poll::Ready(None);
         }
         if resolved.is_empty() {
@@ -338,7 +424,7 @@ impl ResolvedData {
 mod tests {
     use super::*;
     use futures::{executor::block_on, StreamExt};
-    use std::fmt;
+    use std::{any::TypeId, fmt};
 
     #[derive(Debug)]
     struct CustomError(&'static str);
@@ -351,6 +437,50 @@ mod tests {
 
     impl std::error::Error for CustomError {}
 
+    #[test]
+    fn memoized_values_are_shared_within_a_request() {
+        let key = (TypeId::of::<usize>(), "user".to_string());
+        let ctx = SsrSharedContext::new();
+        assert!(ctx.memoized(&key).is_none());
+
+        ctx.memoize(key.clone(), Arc::new(42_usize));
+        let value = ctx.memoized(&key).expect("value was memoized");
+        assert_eq!(value.downcast_ref::<usize>(), Some(&42));
+
+        // the same string with a different type is a different key
+        assert!(ctx
+            .memoized(&(TypeId::of::<u32>(), "user".to_string()))
+            .is_none());
+
+        // a new request starts with an empty cache
+        assert!(SsrSharedContext::new().memoized(&key).is_none());
+    }
+
+    #[test]
+    fn streamed_chunks_are_pushed_then_completed() {
+        let ctx = SsrSharedContext::new();
+        ctx.write_stream(
+            SerializedDataId(0),
+            Box::pin(stream::iter([
+                "\"a\"".to_string(),
+                "\"<b>\"".to_string(),
+            ])),
+        );
+
+        let mut stream = ctx.pending_data().expect("pending_data on ssr");
+        let initial = block_on(stream.next()).expect("initial chunk");
+        assert!(initial.contains("__PENDING_STREAMS=[0,];"));
+
+        let rest = block_on(stream.collect::<Vec<_>>()).concat();
+        let first = rest.find("__RESOLVED_RESOURCES[0]").unwrap();
+        let pushed = rest.find("__STREAMED_CHUNKS[0]||[]).push(").unwrap();
+        let complete = rest.find("__COMPLETE_STREAMS.push(0);").unwrap();
+        assert!(first < pushed && pushed < complete);
+        assert_eq!(rest.matches("__RESOLVED_RESOURCES[0]").count(), 1);
+        assert_eq!(rest.matches(").push(").count(), 2);
+        assert!(!rest.contains('<'));
+    }
+
     /// An error message containing `</script>` must not be able to escape
     /// the surrounding <script> tag in the streamed initial chunk.
     #[test]
@@ -135,13 +135,21 @@ where
     // this means
     // a) the reactive owner for each row will not be cleared when the whole list updates
     // b) context provided in each row will not wipe out the others
+    let children = owned_rows(children);
+    move || keyed(each(), key.clone(), children.clone())
+}
+
+/// Wraps the `children` of a keyed list so that, as in [`For`], each row gets its own [`Owner`],
+/// which is a child of the current owner.
+pub(crate) fn owned_rows<T, N>(
+    children: impl Fn(T) -> N + Clone,
+) -> impl Fn(usize, T) -> (fn(usize), OwnedView<N>) + Clone {
     let parent = Owner::current().expect("no reactive owner");
-    let children = move |_, child| {
+    move |_, child| {
         let owner = parent.with(Owner::new);
         let view = owner.with(|| children(child));
-        (drop, OwnedView::new_with_owner(view, owner))
-    };
-    move || keyed(each(), key.clone(), children.clone())
+        (drop as fn(usize), OwnedView::new_with_owner(view, owner))
+    }
 }
 
 /// Iterates over children and displays them, keyed by the `key` function given.
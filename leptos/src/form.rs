@@ -73,6 +73,57 @@ use web_sys::{
 ///     Ok(())
 /// }
 /// ```
+///
+/// ## Validation
+/// The optional `validate` callback receives the arguments parsed from the form when it is
+/// submitted. If it returns `false`, the submission is blocked and the action is not dispatched.
+/// A type that implements `reactive_stores::Validate` can run the same rules
+/// again in the server function, where returning its errors with `?` converts them into a
+/// [`ServerFnError`].
+///
+/// ```rust
+/// # use leptos::prelude::*;
+/// use leptos::form::ActionForm;
+/// use reactive_stores::Validate;
+///
+/// fn required(value: &str) -> Result<(), &'static str> {
+///     if value.is_empty() {
+///         Err("required")
+///     } else {
+///         Ok(())
+///     }
+/// }
+///
+/// #[derive(Validate, serde::Serialize, serde::Deserialize, Debug, Clone)]
+/// struct Comment {
+///     #[validate(required)]
+///     author: String,
+///     #[validate(required)]
+///     body: String,
+/// }
+///
+/// #[component]
+/// fn CommentForm() -> impl IntoView {
+///     let submit = ServerAction::<PostComment>::new();
+///
+///     view! {
+///       <ActionForm
+///         action=submit
+///         validate=|input: PostComment| input.comment.validate().is_ok()
+///       >
+///         <input type="text" name="comment[author]"/>
+///         <textarea name="comment[body]"></textarea>
+///         <input type="submit"/>
+///       </ActionForm>
+///     }
+/// }
+///
+/// #[server]
+/// async fn post_comment(comment: Comment) -> Result<(), ServerFnError> {
+///     comment.validate()?;
+///     Ok(())
+/// }
+/// ```
 #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 #[component]
 pub fn ActionForm<ServFn, OutputProtocol>(
@@ -81,6 +132,10 @@ pub fn ActionForm<ServFn, OutputProtocol>(
     /// A [`NodeRef`] in which the `<form>` element should be stored.
     #[prop(optional)]
     node_ref: Option<NodeRef<Form>>,
+    /// Checks the arguments parsed from the form before the action is dispatched; the
+    /// submission is blocked if this returns `false`.
+    #[prop(optional, into)]
+    validate: Option<Callback<(ServFn,), bool>>,
     /// Component children; should include the HTML of the form elements.
     children: Children,
 ) -> impl IntoView
@@ -119,6 +174,11 @@ where
 
             match ServFn::from_event(&ev) {
                 Ok(new_input) => {
+                    if let Some(validate) = validate {
+                        if !validate.run((new_input.clone(),)) {
+                            return;
+                        }
+                    }
                     action.dispatch(new_input);
                 }
                 Err(err) => {
@@ -158,6 +218,10 @@ pub fn MultiActionForm<ServFn, OutputProtocol>(
     /// A [`NodeRef`] in which the `<form>` element should be stored.
     #[prop(optional)]
     node_ref: Option<NodeRef<Form>>,
+    /// Checks the arguments parsed from the form before the action is dispatched; the
+    /// submission is blocked if this returns `false`.
+    #[prop(optional, into)]
+    validate: Option<Callback<(ServFn,), bool>>,
     /// Component children; should include the HTML of the form elements.
     children: Children,
 ) -> impl IntoView
@@ -191,6 +255,11 @@ where
 
         match ServFn::from_event(&ev) {
             Ok(new_input) => {
+                if let Some(validate) = validate {
+                    if !validate.run((new_input.clone(),)) {
+                        return;
+                    }
+                }
                 action.dispatch(new_input);
             }
             Err(err) => {
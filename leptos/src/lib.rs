@@ -91,7 +91,14 @@
 //! - **`islands`** Activates “islands mode,” in which components are not made interactive on the
 //!   client unless they use the `#[island]` macro.
 //! - **`hydrate`** Hydration: use this to add interactivity to an SSRed Leptos app.
+//! - **`hydration-recovery`** If the server-rendered HTML does not match the view being hydrated
+//!   (for example, because a browser extension has modified the page), renders the mismatched
+//!   part of the view again on the client, rather than panicking.
+//! - **`a11y`** Checks views for common accessibility issues at compile time, reporting each one
+//!   as a warning.
 //! - **`nonce`** Adds support for nonces to be added as part of a Content Security Policy.
+//! - **`synced-store`** Adds `SyncedStore`, which keeps a store in sync with the server over a
+//!   WebSocket server function.
 //! - **`rkyv`** In SSR/hydrate mode, enables using [`rkyv`](https://docs.rs/rkyv/latest/rkyv/) to serialize resources.
 //! - **`tracing`** Adds support for [`tracing`](https://docs.rs/tracing/latest/tracing/).
 //! - **`trace-component-props`** Adds `tracing` support for component props.
@@ -168,10 +175,12 @@ pub mod prelude {
     mod export_types {
         #[cfg(feature = "nonce")]
         pub use crate::nonce::*;
+        #[cfg(feature = "synced-store")]
+        pub use crate::synced_store::*;
         pub use crate::{
             callback::*, children::*, component::*, control_flow::*, error::*,
-            form::*, hydration::*, into_view::*, mount::*, suspense::*,
-            text_prop::*,
+            form::*, hydration::*, into_view::*, mount::*, shared_signal::*,
+            suspense::*, text_prop::*,
         };
         pub use leptos_config::*;
         pub use leptos_dom::helpers::*;
@@ -226,6 +235,7 @@ pub mod error {
 pub mod control_flow {
     pub use crate::{
         animated_show::*, await_::*, for_loop::*, show::*, show_let::*,
+        transition_for::*, virtual_for::*,
     };
 }
 mod animated_show;
@@ -233,10 +243,15 @@ mod await_;
 mod for_loop;
 mod show;
 mod show_let;
+mod transition_for;
+mod virtual_for;
 
 /// A component that allows rendering a component somewhere else.
 pub mod portal;
 
+/// A component that renders its children into a shadow root.
+pub mod shadow_root;
+
 /// Components to enable server-side rendering and client-side hydration.
 pub mod hydration;
 
@@ -252,9 +267,18 @@ pub mod suspense {
 #[macro_use]
 mod suspense_component;
 
+/// Signals that are kept in sync across browser tabs and Web Workers.
+pub mod shared_signal;
+
+/// Stores that are kept in sync with the server over WebSocket server functions.
+#[cfg(feature = "synced-store")]
+pub mod synced_store;
+
 /// Types for reactive string properties for components.
 pub mod text_prop;
+
 mod transition;
+pub mod web_component;
 pub use leptos_macro::*;
 #[doc(inline)]
 pub use server_fn;
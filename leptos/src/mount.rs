@@ -212,6 +212,32 @@ where
     UnmountHandle { owner, mountable }
 }
 
+/// Runs the provided closure and mounts the result to the provided shadow root.
+///
+/// The view can use [`ShadowRootContext`](crate::shadow_root::ShadowRootContext) to find out
+/// that it is rendered in a shadow root.
+pub fn mount_to_shadow_root<F, N>(
+    root: web_sys::ShadowRoot,
+    f: F,
+) -> UnmountHandle<N::State>
+where
+    F: FnOnce() -> N + 'static,
+    N: IntoView,
+{
+    use crate::shadow_root::{ShadowRootContext, ShadowRootMode};
+    use reactive_graph::owner::provide_context;
+
+    let mode = if root.mode() == web_sys::ShadowRootMode::Closed {
+        ShadowRootMode::Closed
+    } else {
+        ShadowRootMode::Open
+    };
+    mount_to(wasm_bindgen::JsCast::unchecked_into(root), move || {
+        provide_context(ShadowRootContext { mode });
+        f()
+    })
+}
+
 /// Runs the provided closure and mounts the result to the provided element.
 pub fn mount_to_renderer<F, N>(
     parent: &tachys::renderer::types::Element,
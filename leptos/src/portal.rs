@@ -52,23 +52,29 @@ where
                     .expect("HTML element creation to work")
             };
 
-            let render_root = if use_shadow {
-                container
-                    .attach_shadow(&web_sys::ShadowRootInit::new(
-                        web_sys::ShadowRootMode::Open,
-                    ))
-                    .map(|root| root.unchecked_into())
-                    .unwrap_or(container.clone())
-            } else {
-                container.clone()
-            };
+            let shadow_root = use_shadow
+                .then(|| {
+                    container
+                        .attach_shadow(&web_sys::ShadowRootInit::new(
+                            web_sys::ShadowRootMode::Open,
+                        ))
+                        .ok()
+                })
+                .flatten();
 
             let _ = mount.append_child(&container);
+            let children = {
+                let children = Arc::clone(&children);
+                move || untrack(|| children())
+            };
             let handle = SendWrapper::new((
-                mount::mount_to(render_root.unchecked_into(), {
-                    let children = Arc::clone(&children);
-                    move || untrack(|| children())
-                }),
+                match shadow_root {
+                    Some(root) => mount::mount_to_shadow_root(root, children),
+                    None => mount::mount_to(
+                        container.clone().unchecked_into(),
+                        children,
+                    ),
+                },
                 mount.clone(),
                 container,
             ));
@@ -0,0 +1,63 @@
+use crate::{children::TypedChildren, component, IntoView};
+use reactive_graph::owner::{provide_context, Owner};
+pub use tachys::html::shadow_root::ShadowRootMode;
+use tachys::{html::shadow_root::shadow_root, reactive_graph::OwnedView};
+
+/// Provided as context to the children of a [`ShadowRoot`], or of a view mounted with
+/// [`mount_to_shadow_root`](crate::mount::mount_to_shadow_root).
+///
+/// Components that would otherwise add content to the document `<head>`, like the `<Style>`
+/// component of `leptos_meta`, can use this to render that content inside the shadow root
+/// instead, so that it only applies there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShadowRootContext {
+    /// The mode of the shadow root.
+    pub mode: ShadowRootMode,
+}
+
+/// Renders its children into a shadow root attached to the element that contains it,
+/// isolating them from the styles of the rest of the page.
+///
+/// During server-side rendering, this renders a
+/// [declarative shadow root](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/template#shadowrootmode),
+/// which is hydrated on the client.
+///
+/// ```rust
+/// use leptos::{prelude::*, shadow_root::ShadowRoot};
+///
+/// #[component]
+/// pub fn Card() -> impl IntoView {
+///     view! {
+///         <div class="card">
+///             <ShadowRoot>
+///                 // this style only applies inside the shadow root
+///                 <style>"p { color: purple; }"</style>
+///                 <p>"Styled"</p>
+///             </ShadowRoot>
+///         </div>
+///     }
+/// }
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+#[component]
+pub fn ShadowRoot<V>(
+    /// Whether the shadow root can be accessed with the `shadowRoot` property of its host
+    /// element. Defaults to [`ShadowRootMode::Open`].
+    #[prop(optional)]
+    mode: ShadowRootMode,
+    /// The children to render into the shadow root.
+    children: TypedChildren<V>,
+) -> impl IntoView
+where
+    V: IntoView + 'static,
+{
+    let owner = Owner::current()
+        .expect("no current reactive Owner found")
+        .child();
+    let children = children.into_inner();
+    let children = owner.with(|| {
+        provide_context(ShadowRootContext { mode });
+        children()
+    });
+    OwnedView::new_with_owner(shadow_root(mode, children), owner)
+}
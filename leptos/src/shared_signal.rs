@@ -0,0 +1,682 @@
+use crate::logging::debug_warn;
+use leptos_server::{
+    codee::{string::JsonSerdeCodec, Decoder, Encoder},
+    FromEncodedStr, IntoEncodedString,
+};
+use or_poisoned::OrPoisoned;
+use reactive_graph::{
+    signal::{
+        guards::{Plain, ReadGuard, UntrackedWriteGuard, WriteGuard},
+        ArcReadSignal, ArcRwSignal,
+    },
+    traits::{
+        DefinedAt, IsDisposed, Notify, ReadUntracked, Set, Track,
+        UntrackableGuard, WithUntracked, Write,
+    },
+};
+use send_wrapper::SendWrapper;
+use serde_json::{json, Value};
+use std::{
+    borrow::Borrow,
+    fmt::Debug,
+    marker::PhantomData,
+    panic::Location,
+    sync::{Arc, Mutex, Weak},
+};
+
+type MergeFn<T> = Arc<dyn Fn(&T, T) -> T + Send + Sync>;
+
+/// Decides what happens when a [`SharedSignal`] receives a value written in another context.
+#[derive(Default)]
+pub enum ConflictPolicy<T> {
+    /// Keeps whichever write happened last, ordering writes by a logical clock. Concurrent writes
+    /// are ordered consistently in every context, so they all settle on the same value.
+    #[default]
+    LastWriterWins,
+    /// Combines the current value with the value that was received.
+    ///
+    /// The function receives the current value and the incoming value, and returns the new value.
+    /// Merged values are not broadcast again, so the function should produce the same result in
+    /// every context.
+    Merge(MergeFn<T>),
+}
+
+impl<T> ConflictPolicy<T> {
+    /// Creates a [`ConflictPolicy::Merge`] policy.
+    pub fn merge(fun: impl Fn(&T, T) -> T + Send + Sync + 'static) -> Self {
+        Self::Merge(Arc::new(fun))
+    }
+}
+
+impl<T> Clone for ConflictPolicy<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::LastWriterWins => Self::LastWriterWins,
+            Self::Merge(fun) => Self::Merge(Arc::clone(fun)),
+        }
+    }
+}
+
+impl<T> Debug for ConflictPolicy<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LastWriterWins => write!(f, "LastWriterWins"),
+            Self::Merge(_) => write!(f, "Merge"),
+        }
+    }
+}
+
+/// A logical timestamp for a write: a Lamport clock, with the ID of the context that made the
+/// write used to break ties.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct Stamp {
+    clock: u64,
+    origin: u64,
+}
+
+/// What a context should do with a message it has received.
+#[derive(Debug, PartialEq, Eq)]
+enum Received {
+    Ignore,
+    Replace,
+    Merge,
+    Reply(Stamp),
+}
+
+/// Tracks the logical clock for one context's copy of a shared value.
+#[derive(Debug)]
+struct Replica {
+    origin: u64,
+    clock: u64,
+    // the stamp of the write the current value came from
+    current: Stamp,
+}
+
+impl Replica {
+    fn new(origin: u64) -> Self {
+        Self {
+            origin,
+            clock: 0,
+            current: Stamp::default(),
+        }
+    }
+
+    fn write(&mut self) -> Stamp {
+        self.clock += 1;
+        self.current = Stamp {
+            clock: self.clock,
+            origin: self.origin,
+        };
+        self.current
+    }
+
+    fn receive_value(&mut self, stamp: Stamp, merge: bool) -> Received {
+        self.clock = self.clock.max(stamp.clock);
+        if merge {
+            self.current = self.current.max(stamp);
+            Received::Merge
+        } else if stamp > self.current {
+            self.current = stamp;
+            Received::Replace
+        } else {
+            Received::Ignore
+        }
+    }
+
+    fn receive_sync(&self) -> Received {
+        // if nothing has been written yet, the initial value is as good as anyone else's
+        if self.current.clock == 0 {
+            Received::Ignore
+        } else {
+            Received::Reply(self.current)
+        }
+    }
+}
+
+/// A signal whose value is kept in sync across browser tabs and Web Workers on the same origin,
+/// using a [`BroadcastChannel`](https://developer.mozilla.org/en-US/docs/Web/API/BroadcastChannel).
+///
+/// Every `SharedSignal` created with the same `name` joins the same channel. When the value is
+/// updated, it is encoded with the codec `Ser` and sent to every other context, which applies it
+/// according to the signal's [`ConflictPolicy`]. A newly-created signal asks the other contexts
+/// for their current value, so a new tab starts with the value the others have settled on.
+///
+/// Updates made with untracked methods like [`update_untracked`] are not broadcast. Outside the
+/// browser (for example, during server-side rendering) this behaves like an ordinary
+/// [`ArcRwSignal`].
+///
+/// ```rust,no_run
+/// # use leptos::prelude::*;
+/// // every open tab sees the same theme
+/// let theme = SharedSignal::new("theme", String::from("light"));
+/// theme.set("dark".to_string());
+/// assert_eq!(theme.get(), "dark");
+/// ```
+///
+/// [`update_untracked`]: reactive_graph::traits::UpdateUntracked
+pub struct SharedSignal<T, Ser = JsonSerdeCodec> {
+    #[cfg(any(debug_assertions, leptos_debuginfo))]
+    defined_at: &'static Location<'static>,
+    value: ArcRwSignal<T>,
+    inner: Arc<SharedInner<T>>,
+    ser: PhantomData<fn() -> Ser>,
+}
+
+struct SharedInner<T> {
+    replica: Mutex<Replica>,
+    policy: ConflictPolicy<T>,
+    channel: Option<SendWrapper<Channel>>,
+}
+
+impl<T, Ser> Clone for SharedSignal<T, Ser> {
+    fn clone(&self) -> Self {
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: self.defined_at,
+            value: self.value.clone(),
+            inner: Arc::clone(&self.inner),
+            ser: PhantomData,
+        }
+    }
+}
+
+impl<T, Ser> Debug for SharedSignal<T, Ser> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedSignal")
+            .field("value", &self.value)
+            .field("policy", &self.inner.policy)
+            .finish()
+    }
+}
+
+impl<T> SharedSignal<T, JsonSerdeCodec>
+where
+    T: Send + Sync + 'static,
+    JsonSerdeCodec: Encoder<T> + Decoder<T>,
+    <JsonSerdeCodec as Encoder<T>>::Error: Debug,
+    <JsonSerdeCodec as Decoder<T>>::Error: Debug,
+    <<JsonSerdeCodec as Decoder<T>>::Encoded as FromEncodedStr>::DecodingError:
+        Debug,
+    <JsonSerdeCodec as Encoder<T>>::Encoded: IntoEncodedString,
+    <JsonSerdeCodec as Decoder<T>>::Encoded: FromEncodedStr,
+{
+    /// Creates a signal that is synchronized with every other `SharedSignal` with the same
+    /// `name`, encoding values as JSON and keeping the most recent write.
+    #[track_caller]
+    pub fn new(name: &str, value: T) -> Self {
+        Self::new_with_policy(name, value, ConflictPolicy::LastWriterWins)
+    }
+}
+
+impl<T, Ser> SharedSignal<T, Ser>
+where
+    T: Send + Sync + 'static,
+    Ser: Encoder<T> + Decoder<T> + 'static,
+    <Ser as Encoder<T>>::Error: Debug,
+    <Ser as Decoder<T>>::Error: Debug,
+    <<Ser as Decoder<T>>::Encoded as FromEncodedStr>::DecodingError: Debug,
+    <Ser as Encoder<T>>::Encoded: IntoEncodedString,
+    <Ser as Decoder<T>>::Encoded: FromEncodedStr,
+{
+    /// Creates a signal that is synchronized with every other `SharedSignal` with the same
+    /// `name`, encoding values with `Ser` and resolving conflicts with `policy`.
+    #[track_caller]
+    pub fn new_with_policy(
+        name: &str,
+        value: T,
+        policy: ConflictPolicy<T>,
+    ) -> Self {
+        let value = ArcRwSignal::new(value);
+        let inner = Arc::new_cyclic(|weak: &Weak<SharedInner<T>>| {
+            let channel = Channel::open(name, {
+                let weak = weak.clone();
+                let value = value.clone();
+                move |message| {
+                    if let Some(inner) = weak.upgrade() {
+                        inner.receive::<Ser>(&value, message);
+                    }
+                }
+            });
+            SharedInner {
+                replica: Mutex::new(Replica::new(random_id())),
+                policy,
+                channel: channel.map(SendWrapper::new),
+            }
+        });
+        inner.post(json!({
+            "origin": inner.replica.lock().or_poisoned().origin,
+            "sync": true,
+        }));
+
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+            value,
+            inner,
+            ser: PhantomData,
+        }
+    }
+
+    /// The conflict policy used to apply values received from other contexts.
+    pub fn policy(&self) -> &ConflictPolicy<T> {
+        &self.inner.policy
+    }
+
+    /// Returns a read-only signal that shares this signal's value.
+    pub fn read_only(&self) -> ArcReadSignal<T> {
+        self.value.read_only()
+    }
+}
+
+impl<T> SharedInner<T>
+where
+    T: Send + Sync + 'static,
+{
+    fn post(&self, message: Value) {
+        if let Some(channel) = &self.channel {
+            channel.post(&message.to_string());
+        }
+    }
+
+    fn broadcast<Ser>(&self, value: &ArcRwSignal<T>, stamp: Stamp)
+    where
+        Ser: Encoder<T>,
+        <Ser as Encoder<T>>::Error: Debug,
+        <Ser as Encoder<T>>::Encoded: IntoEncodedString,
+    {
+        if self.channel.is_none() {
+            return;
+        }
+        let encoded = value.try_with_untracked(|value| Ser::encode(value));
+        match encoded {
+            Some(Ok(encoded)) => self.post(json!({
+                "origin": stamp.origin,
+                "clock": stamp.clock,
+                "value": encoded.into_encoded_string(),
+            })),
+            Some(Err(e)) => {
+                debug_warn!("SharedSignal could not encode its value: {e:?}")
+            }
+            None => {}
+        }
+    }
+
+    fn receive<Ser>(&self, value: &ArcRwSignal<T>, message: String)
+    where
+        Ser: Encoder<T> + Decoder<T>,
+        <Ser as Encoder<T>>::Error: Debug,
+        <Ser as Decoder<T>>::Error: Debug,
+        <<Ser as Decoder<T>>::Encoded as FromEncodedStr>::DecodingError: Debug,
+        <Ser as Encoder<T>>::Encoded: IntoEncodedString,
+        <Ser as Decoder<T>>::Encoded: FromEncodedStr,
+    {
+        let Ok(message) = serde_json::from_str::<Value>(&message) else {
+            return;
+        };
+
+        if message.get("sync").is_some() {
+            let received = self.replica.lock().or_poisoned().receive_sync();
+            if let Received::Reply(stamp) = received {
+                self.broadcast::<Ser>(value, stamp);
+            }
+            return;
+        }
+
+        let (Some(origin), Some(clock), Some(encoded)) = (
+            message.get("origin").and_then(Value::as_u64),
+            message.get("clock").and_then(Value::as_u64),
+            message.get("value").and_then(Value::as_str),
+        ) else {
+            return;
+        };
+        let incoming =
+            match <Ser as Decoder<T>>::Encoded::from_encoded_str(encoded) {
+                Ok(encoded) => Ser::decode(encoded.borrow()),
+                Err(e) => {
+                    debug_warn!("SharedSignal could not decode a value: {e:?}");
+                    return;
+                }
+            };
+        let incoming = match incoming {
+            Ok(incoming) => incoming,
+            Err(e) => {
+                debug_warn!("SharedSignal could not decode a value: {e:?}");
+                return;
+            }
+        };
+
+        let received = self.replica.lock().or_poisoned().receive_value(
+            Stamp { clock, origin },
+            matches!(self.policy, ConflictPolicy::Merge(_)),
+        );
+        match (received, &self.policy) {
+            (Received::Replace, _) => value.set(incoming),
+            (Received::Merge, ConflictPolicy::Merge(merge)) => {
+                if let Some(merged) =
+                    value.try_with_untracked(|current| merge(current, incoming))
+                {
+                    value.set(merged);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<T, Ser> DefinedAt for SharedSignal<T, Ser> {
+    fn defined_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            Some(self.defined_at)
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            None
+        }
+    }
+}
+
+impl<T, Ser> IsDisposed for SharedSignal<T, Ser> {
+    fn is_disposed(&self) -> bool {
+        self.value.is_disposed()
+    }
+}
+
+impl<T, Ser> Track for SharedSignal<T, Ser> {
+    #[track_caller]
+    fn track(&self) {
+        self.value.track();
+    }
+}
+
+impl<T: 'static, Ser> ReadUntracked for SharedSignal<T, Ser> {
+    type Value = ReadGuard<T, Plain<T>>;
+
+    fn try_read_untracked(&self) -> Option<Self::Value> {
+        self.value.try_read_untracked()
+    }
+}
+
+impl<T, Ser> Notify for SharedSignal<T, Ser>
+where
+    T: Send + Sync + 'static,
+    Ser: Encoder<T>,
+    <Ser as Encoder<T>>::Error: Debug,
+    <Ser as Encoder<T>>::Encoded: IntoEncodedString,
+{
+    fn notify(&self) {
+        self.value.notify();
+        let stamp = self.inner.replica.lock().or_poisoned().write();
+        self.inner.broadcast::<Ser>(&self.value, stamp);
+    }
+}
+
+impl<T, Ser> Write for SharedSignal<T, Ser>
+where
+    T: Send + Sync + 'static,
+    Ser: Encoder<T>,
+    <Ser as Encoder<T>>::Error: Debug,
+    <Ser as Encoder<T>>::Encoded: IntoEncodedString,
+{
+    type Value = T;
+
+    fn try_write(&self) -> Option<impl UntrackableGuard<Target = Self::Value>> {
+        self.value
+            .try_write_untracked()
+            .map(|guard| WriteGuard::new(self.clone(), guard))
+    }
+
+    #[allow(refining_impl_trait)]
+    fn try_write_untracked(&self) -> Option<UntrackedWriteGuard<Self::Value>> {
+        self.value.try_write_untracked()
+    }
+}
+
+/// Tracks whether this context is the leader among all the contexts (tabs and workers) that are
+/// taking part in an election with the same name.
+///
+/// Exactly one context is the leader at a time. When the leader is closed or its
+/// `LeaderElection` is dropped, another context takes over. This is useful for work that should
+/// only be done once, like polling the server for updates that are then shared with
+/// [`SharedSignal`].
+///
+/// The election uses the [Web Locks API](https://developer.mozilla.org/en-US/docs/Web/API/Web_Locks_API).
+/// Outside the browser, or if the API is not available, every context is its own leader.
+#[derive(Clone, Debug)]
+pub struct LeaderElection {
+    is_leader: ArcRwSignal<bool>,
+    _lock: Option<Arc<LockRelease>>,
+}
+
+impl LeaderElection {
+    /// Joins the election with the given name.
+    pub fn new(name: &str) -> Self {
+        let is_leader = ArcRwSignal::new(false);
+        let lock = LockRelease::request(name, is_leader.clone());
+        if lock.is_none() {
+            is_leader.set(true);
+        }
+        Self {
+            is_leader,
+            _lock: lock.map(Arc::new),
+        }
+    }
+
+    /// A signal that is `true` while this context is the leader.
+    pub fn is_leader(&self) -> ArcReadSignal<bool> {
+        self.is_leader.read_only()
+    }
+}
+
+fn random_id() -> u64 {
+    #[cfg(target_family = "wasm")]
+    {
+        (js_sys::Math::random() * u64::MAX as f64) as u64
+    }
+    #[cfg(not(target_family = "wasm"))]
+    {
+        use std::hash::{BuildHasher, RandomState};
+        RandomState::new().hash_one(std::process::id())
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod platform {
+    use js_sys::{Function, Promise, Reflect};
+    use reactive_graph::{signal::ArcRwSignal, traits::Set};
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+    use web_sys::{BroadcastChannel, MessageEvent};
+
+    pub(super) struct Channel {
+        channel: BroadcastChannel,
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+    }
+
+    impl Channel {
+        pub(super) fn open(
+            name: &str,
+            mut on_message: impl FnMut(String) + 'static,
+        ) -> Option<Self> {
+            let channel =
+                BroadcastChannel::new(&format!("leptos-shared-signal:{name}"))
+                    .ok()?;
+            let on_message = Closure::new(move |event: MessageEvent| {
+                if let Some(data) = event.data().as_string() {
+                    on_message(data);
+                }
+            });
+            channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            Some(Self {
+                channel,
+                _on_message: on_message,
+            })
+        }
+
+        pub(super) fn post(&self, message: &str) {
+            _ = self.channel.post_message(&JsValue::from_str(message));
+        }
+    }
+
+    impl Drop for Channel {
+        fn drop(&mut self) {
+            self.channel.set_onmessage(None);
+            self.channel.close();
+        }
+    }
+
+    #[derive(Debug)]
+    pub(super) struct LockRelease(
+        pub(super) Option<send_wrapper::SendWrapper<Function>>,
+    );
+
+    impl LockRelease {
+        pub(super) fn request(
+            name: &str,
+            is_leader: ArcRwSignal<bool>,
+        ) -> Option<Self> {
+            let global = js_sys::global();
+            let navigator =
+                Reflect::get(&global, &JsValue::from_str("navigator")).ok()?;
+            let locks = Reflect::get(&navigator, &JsValue::from_str("locks"))
+                .ok()
+                .filter(|locks| !locks.is_undefined())?;
+            let request = Reflect::get(&locks, &JsValue::from_str("request"))
+                .ok()?
+                .dyn_into::<Function>()
+                .ok()?;
+
+            // the lock is held until this Promise resolves
+            let mut release = None;
+            let held = Promise::new(&mut |resolve, _| release = Some(resolve));
+            let on_acquired =
+                Closure::once_into_js(move |_lock: JsValue| -> JsValue {
+                    is_leader.set(true);
+                    held.into()
+                });
+            request
+                .call2(
+                    &locks,
+                    &JsValue::from_str(&format!("leptos-leader:{name}")),
+                    &on_acquired,
+                )
+                .ok()?;
+            Some(Self(release.map(send_wrapper::SendWrapper::new)))
+        }
+    }
+
+    impl Drop for LockRelease {
+        fn drop(&mut self) {
+            if let Some(release) = self.0.take() {
+                _ = release.call0(&JsValue::NULL);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod platform {
+    use reactive_graph::signal::ArcRwSignal;
+
+    pub(super) struct Channel;
+
+    impl Channel {
+        pub(super) fn open(
+            _name: &str,
+            _on_message: impl FnMut(String) + 'static,
+        ) -> Option<Self> {
+            None
+        }
+
+        pub(super) fn post(&self, _message: &str) {}
+    }
+
+    #[derive(Debug)]
+    pub(super) enum LockRelease {}
+
+    impl LockRelease {
+        pub(super) fn request(
+            _name: &str,
+            _is_leader: ArcRwSignal<bool>,
+        ) -> Option<Self> {
+            None
+        }
+    }
+}
+
+use platform::{Channel, LockRelease};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_writer_wins_orders_concurrent_writes() {
+        let mut a = Replica::new(1);
+        let mut b = Replica::new(2);
+
+        // both write concurrently: the tie is broken by origin in both contexts
+        let from_a = a.write();
+        let from_b = b.write();
+        assert_eq!(a.receive_value(from_b, false), Received::Replace);
+        assert_eq!(b.receive_value(from_a, false), Received::Ignore);
+        assert_eq!(a.current, b.current);
+
+        // a write made after seeing another one always wins
+        let later = a.write();
+        assert!(later.clock > from_b.clock);
+        assert_eq!(b.receive_value(later, false), Received::Replace);
+
+        // stale messages are ignored
+        assert_eq!(b.receive_value(from_a, false), Received::Ignore);
+    }
+
+    #[test]
+    fn new_context_syncs_from_existing_ones() {
+        let mut existing = Replica::new(1);
+        let mut new = Replica::new(2);
+        assert_eq!(new.receive_sync(), Received::Ignore);
+        assert_eq!(existing.receive_sync(), Received::Ignore);
+
+        let stamp = existing.write();
+        assert_eq!(existing.receive_sync(), Received::Reply(stamp));
+        assert_eq!(new.receive_value(stamp, false), Received::Replace);
+    }
+
+    #[test]
+    fn merge_applies_every_value() {
+        let mut a = Replica::new(1);
+        let mut b = Replica::new(2);
+        let stamp = b.write();
+        a.write();
+        a.write();
+        assert_eq!(a.receive_value(stamp, true), Received::Merge);
+        assert_eq!(a.clock, 2);
+    }
+
+    #[test]
+    fn behaves_like_a_signal_without_a_channel() {
+        use reactive_graph::{owner::Owner, traits::*};
+
+        Owner::new().with(|| {
+            let count = SharedSignal::new("count", 0);
+            let merged = SharedSignal::<Vec<i32>>::new_with_policy(
+                "merged",
+                vec![],
+                ConflictPolicy::merge(|current: &Vec<i32>, incoming| {
+                    let mut merged = current.clone();
+                    merged.extend(incoming);
+                    merged
+                }),
+            );
+            count.set(1);
+            count.update(|n| *n += 1);
+            assert_eq!(count.get(), 2);
+            assert_eq!(count.read_only().get(), 2);
+            merged.update(|values| values.push(1));
+            assert_eq!(merged.get(), vec![1]);
+
+            let leader = LeaderElection::new("leader");
+            assert!(leader.is_leader().get());
+        });
+    }
+}
@@ -0,0 +1,590 @@
+use crate::{logging::debug_warn, shared_signal::ConflictPolicy};
+use futures::{channel::mpsc, Future, StreamExt};
+use leptos_server::SharedValue;
+use or_poisoned::OrPoisoned;
+use reactive_graph::{
+    effect::Effect,
+    signal::{ArcReadSignal, ArcRwSignal},
+    traits::{
+        DefinedAt, IsDisposed, Notify, ReadUntracked, Set, Track,
+        UntrackableGuard, Write,
+    },
+};
+use reactive_stores::{
+    ArcStore, KeyMap, PatchField, PatchOperation, StoreField,
+    StoreFieldTrigger, StorePath, StorePathSegment,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use server_fn::{BoxedStream, ServerFnError};
+use std::{
+    fmt::Debug,
+    ops::DerefMut,
+    panic::Location,
+    sync::{Arc, Mutex},
+};
+
+/// The stream of patches exchanged between a [`SyncedStore`] and the server.
+///
+/// A WebSocket server function used to synchronize a store takes one of these as its input and
+/// returns one as its output.
+pub type PatchStream = BoxedStream<Vec<PatchOperation>, ServerFnError>;
+
+/// The state of the connection between a [`SyncedStore`] and the server.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SyncStatus {
+    /// The store has not connected to the server, either because it is connecting or because it
+    /// is not running in the browser.
+    #[default]
+    Connecting,
+    /// The store is connected, and is sending and receiving changes.
+    Connected,
+    /// The server closed the connection.
+    Closed,
+    /// The connection failed.
+    Failed(ServerFnError),
+}
+
+/// A [`Store`](reactive_stores::Store) that is kept in sync with the server over a
+/// [`Websocket`](server_fn::Websocket) server function.
+///
+/// The initial value is created on the server during server rendering, serialized, and used to
+/// hydrate the store in the browser, in the same way as a
+/// [`SharedValue`](leptos_server::SharedValue). Once the store is running in the browser, it
+/// connects to the server function: every local write is sent to the server as a set of
+/// field-level JSON Patch operations, and operations received from the server are applied so
+/// that only the fields they change are notified.
+///
+/// The server function should pass its input to a [`StoreSyncHub`], which holds the
+/// authoritative value and forwards changes to every other connected client.
+///
+/// `SyncedStore` implements [`StoreField`], so the field accessors generated by
+/// `#[derive(Store)]` can be used on it directly.
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "ssr")] {
+/// use leptos::{prelude::*, synced_store::*};
+/// use reactive_stores::{Patch, Store};
+/// use serde::{Deserialize, Serialize};
+/// use server_fn::{codec::JsonEncoding, Websocket};
+/// use std::sync::LazyLock;
+///
+/// #[derive(Clone, Default, Store, Patch, Serialize, Deserialize)]
+/// struct Dashboard {
+///     title: String,
+///     visitors: u32,
+/// }
+///
+/// static HUB: LazyLock<StoreSyncHub<Dashboard>> =
+///     LazyLock::new(|| StoreSyncHub::new(Dashboard::default()));
+///
+/// #[server(protocol = Websocket<JsonEncoding, JsonEncoding>)]
+/// async fn sync_dashboard(
+///     input: PatchStream,
+/// ) -> Result<PatchStream, ServerFnError> {
+///     Ok(HUB.connect(input))
+/// }
+///
+/// #[component]
+/// fn Dashboard() -> impl IntoView {
+///     let dashboard = SyncedStore::new(|| HUB.value(), sync_dashboard);
+///     view! { <h1>{move || dashboard.clone().title().get()}</h1> }
+/// }
+/// # }
+/// ```
+pub struct SyncedStore<T> {
+    #[cfg(any(debug_assertions, leptos_debuginfo))]
+    defined_at: &'static Location<'static>,
+    store: ArcStore<T>,
+    policy: ConflictPolicy<T>,
+    status: ArcRwSignal<SyncStatus>,
+}
+
+impl<T> Clone for SyncedStore<T> {
+    fn clone(&self) -> Self {
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: self.defined_at,
+            store: self.store.clone(),
+            policy: self.policy.clone(),
+            status: self.status.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for SyncedStore<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SyncedStore")
+            .field("store", &self.store)
+            .field("policy", &self.policy)
+            .field("status", &self.status)
+            .finish()
+    }
+}
+
+impl<T> SyncedStore<T>
+where
+    T: Serialize + DeserializeOwned + PatchField + Send + Sync + 'static,
+{
+    /// Creates a store that is synchronized with the server through `connect`, applying the
+    /// changes it receives as they arrive.
+    ///
+    /// On the server, `initial` is called and its value serialized. During hydration, the value
+    /// is deserialized instead.
+    #[track_caller]
+    pub fn new<Fut>(
+        initial: impl FnOnce() -> T,
+        connect: impl FnOnce(PatchStream) -> Fut + 'static,
+    ) -> Self
+    where
+        Fut: Future<Output = Result<PatchStream, ServerFnError>> + 'static,
+    {
+        Self::new_with_policy(initial, connect, ConflictPolicy::LastWriterWins)
+    }
+
+    /// Creates a store that is synchronized with the server through `connect`, resolving the
+    /// changes it receives with `policy`.
+    ///
+    /// With [`ConflictPolicy::Merge`], the function receives the current value and the value
+    /// with the remote changes applied. The merged value is not sent back to the server.
+    #[track_caller]
+    pub fn new_with_policy<Fut>(
+        initial: impl FnOnce() -> T,
+        connect: impl FnOnce(PatchStream) -> Fut + 'static,
+        policy: ConflictPolicy<T>,
+    ) -> Self
+    where
+        Fut: Future<Output = Result<PatchStream, ServerFnError>> + 'static,
+    {
+        let this = Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+            store: ArcStore::new(SharedValue::new(initial).into_inner()),
+            policy,
+            status: Default::default(),
+        };
+
+        // effects only run in the browser, so the server never opens a connection to itself.
+        // the connection is closed when the effect's owner is cleaned up
+        let mut connect = Some(connect);
+        Effect::new({
+            let this = this.clone();
+            move |_| {
+                let Some(connect) = connect.take() else {
+                    return;
+                };
+                let outgoing = this.store.changes().map(Ok).into();
+                let this = this.clone();
+                crate::task::spawn_local_scoped_with_cancellation(async move {
+                    match connect(outgoing).await {
+                        Ok(mut incoming) => {
+                            this.status.set(SyncStatus::Connected);
+                            while let Some(ops) = incoming.next().await {
+                                match ops {
+                                    Ok(ops) => this.receive(&ops),
+                                    Err(e) => {
+                                        this.status.set(SyncStatus::Failed(e));
+                                        return;
+                                    }
+                                }
+                            }
+                            this.status.set(SyncStatus::Closed);
+                        }
+                        Err(e) => this.status.set(SyncStatus::Failed(e)),
+                    }
+                });
+            }
+        });
+
+        this
+    }
+
+    fn receive(&self, ops: &[PatchOperation]) {
+        let applied = match &self.policy {
+            ConflictPolicy::LastWriterWins => self.store.apply_patch(ops),
+            ConflictPolicy::Merge(merge) => self
+                .store
+                .apply_patch_with(ops, |current, new| merge(current, new)),
+        };
+        if let Err(e) = applied {
+            debug_warn!("SyncedStore could not apply a patch: {e}");
+        }
+    }
+}
+
+impl<T> SyncedStore<T> {
+    /// The conflict policy used to apply changes received from the server.
+    pub fn policy(&self) -> &ConflictPolicy<T> {
+        &self.policy
+    }
+
+    /// The state of the connection to the server.
+    pub fn status(&self) -> ArcReadSignal<SyncStatus> {
+        self.status.read_only()
+    }
+
+    /// Returns the underlying store.
+    ///
+    /// Writes to the returned store are synchronized in the same way as writes to this one.
+    pub fn store(&self) -> ArcStore<T> {
+        self.store.clone()
+    }
+}
+
+impl<T> DefinedAt for SyncedStore<T> {
+    fn defined_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            Some(self.defined_at)
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            None
+        }
+    }
+}
+
+impl<T> IsDisposed for SyncedStore<T> {
+    #[inline(always)]
+    fn is_disposed(&self) -> bool {
+        false
+    }
+}
+
+impl<T> ReadUntracked for SyncedStore<T>
+where
+    T: 'static,
+{
+    type Value = <ArcStore<T> as ReadUntracked>::Value;
+
+    fn try_read_untracked(&self) -> Option<Self::Value> {
+        self.store.try_read_untracked()
+    }
+}
+
+impl<T> Write for SyncedStore<T>
+where
+    T: 'static,
+{
+    type Value = T;
+
+    fn try_write(&self) -> Option<impl UntrackableGuard<Target = Self::Value>> {
+        self.store.try_write()
+    }
+
+    fn try_write_untracked(
+        &self,
+    ) -> Option<impl DerefMut<Target = Self::Value>> {
+        self.store.try_write_untracked()
+    }
+}
+
+impl<T: 'static> Track for SyncedStore<T> {
+    fn track(&self) {
+        self.store.track();
+    }
+}
+
+impl<T: 'static> Notify for SyncedStore<T> {
+    fn notify(&self) {
+        self.store.notify();
+    }
+}
+
+impl<T> StoreField for SyncedStore<T>
+where
+    T: 'static,
+{
+    type Value = T;
+    type Reader = <ArcStore<T> as StoreField>::Reader;
+    type Writer = <ArcStore<T> as StoreField>::Writer;
+
+    #[track_caller]
+    fn get_trigger(&self, path: StorePath) -> StoreFieldTrigger {
+        self.store.get_trigger(path)
+    }
+
+    #[track_caller]
+    fn get_trigger_unkeyed(&self, path: StorePath) -> StoreFieldTrigger {
+        self.store.get_trigger_unkeyed(path)
+    }
+
+    #[track_caller]
+    fn path(&self) -> impl IntoIterator<Item = StorePathSegment> {
+        self.store.path().into_iter().collect::<Vec<_>>()
+    }
+
+    #[track_caller]
+    fn path_unkeyed(&self) -> impl IntoIterator<Item = StorePathSegment> {
+        self.store.path_unkeyed().into_iter().collect::<Vec<_>>()
+    }
+
+    #[track_caller]
+    fn reader(&self) -> Option<Self::Reader> {
+        self.store.reader()
+    }
+
+    #[track_caller]
+    fn writer(&self) -> Option<Self::Writer> {
+        self.store.writer()
+    }
+
+    #[track_caller]
+    fn keys(&self) -> Option<KeyMap> {
+        self.store.keys()
+    }
+}
+
+/// Holds the authoritative value of a [`SyncedStore`] on the server, and forwards the changes
+/// made by each connected client to every other client.
+///
+/// Changes are applied and forwarded one at a time, so every client receives them in the same
+/// order. If a client sends a change that can no longer be applied, it is sent the current value
+/// instead.
+pub struct StoreSyncHub<T> {
+    store: ArcStore<T>,
+    clients: Arc<Mutex<Clients>>,
+}
+
+#[derive(Default)]
+struct Clients {
+    next_id: u64,
+    senders: Vec<(u64, mpsc::UnboundedSender<Vec<PatchOperation>>)>,
+}
+
+impl<T> Clone for StoreSyncHub<T> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            clients: Arc::clone(&self.clients),
+        }
+    }
+}
+
+impl<T: Debug> Debug for StoreSyncHub<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoreSyncHub")
+            .field("store", &self.store)
+            .field("clients", &self.clients.lock().or_poisoned().senders.len())
+            .finish()
+    }
+}
+
+impl<T> StoreSyncHub<T>
+where
+    T: Serialize + DeserializeOwned + PatchField + Send + Sync + 'static,
+{
+    /// Creates a hub with the initial authoritative value.
+    pub fn new(value: T) -> Self {
+        Self {
+            store: ArcStore::new(value),
+            clients: Default::default(),
+        }
+    }
+
+    /// Returns a copy of the current authoritative value.
+    ///
+    /// This is typically used as the initial value of a [`SyncedStore`].
+    pub fn value(&self) -> T
+    where
+        T: Clone,
+    {
+        self.store.read_untracked().clone()
+    }
+
+    /// Connects a client, returning the stream of changes that should be sent to it.
+    ///
+    /// This is intended to be called with the input of a
+    /// [`Websocket`](server_fn::Websocket) server function, and its result returned from it.
+    pub fn connect(&self, mut input: PatchStream) -> PatchStream {
+        let (tx, rx) = mpsc::unbounded();
+        let id = {
+            let mut clients = self.clients.lock().or_poisoned();
+            // the client starts from the current value, which may have changed since it rendered
+            if let Some(ops) = self.replace_with_current() {
+                _ = tx.unbounded_send(ops);
+            }
+            let id = clients.next_id;
+            clients.next_id += 1;
+            clients.senders.push((id, tx));
+            id
+        };
+
+        let hub = self.clone();
+        crate::task::spawn(async move {
+            while let Some(ops) = input.next().await {
+                match ops {
+                    Ok(ops) => hub.receive(Some(id), &ops),
+                    Err(e) => {
+                        debug_warn!("StoreSyncHub received an error: {e}")
+                    }
+                }
+            }
+            hub.clients
+                .lock()
+                .or_poisoned()
+                .senders
+                .retain(|(client, _)| *client != id);
+        });
+
+        rx.map(Ok).into()
+    }
+
+    /// Applies a change made on the server, and sends it to every connected client.
+    pub fn apply_patch(
+        &self,
+        ops: &[PatchOperation],
+    ) -> Result<(), reactive_stores::JsonPatchError> {
+        let clients = self.clients.lock().or_poisoned();
+        self.store.apply_patch(ops)?;
+        Self::broadcast(&clients, None, ops);
+        Ok(())
+    }
+
+    fn receive(&self, origin: Option<u64>, ops: &[PatchOperation]) {
+        // holding the lock while applying the change keeps changes in the same order everywhere
+        let clients = self.clients.lock().or_poisoned();
+        match self.store.apply_patch(ops) {
+            Ok(()) => Self::broadcast(&clients, origin, ops),
+            Err(e) => {
+                debug_warn!("StoreSyncHub could not apply a patch: {e}");
+                if let (Some(ops), Some((_, sender))) = (
+                    self.replace_with_current(),
+                    clients.senders.iter().find(|(id, _)| Some(*id) == origin),
+                ) {
+                    _ = sender.unbounded_send(ops);
+                }
+            }
+        }
+    }
+
+    /// A change that replaces a client's whole value with the current authoritative value.
+    fn replace_with_current(&self) -> Option<Vec<PatchOperation>> {
+        let value = serde_json::to_value(&*self.store.read_untracked()).ok()?;
+        Some(vec![PatchOperation::Replace {
+            path: String::new(),
+            value,
+        }])
+    }
+
+    fn broadcast(
+        clients: &Clients,
+        origin: Option<u64>,
+        ops: &[PatchOperation],
+    ) {
+        for (id, sender) in &clients.senders {
+            if Some(*id) != origin {
+                _ = sender.unbounded_send(ops.to_vec());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reactive_graph::{owner::Owner, traits::Get};
+    use reactive_stores::{Patch, Store};
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(
+        Debug, Clone, Default, PartialEq, Store, Patch, Serialize, Deserialize,
+    )]
+    struct Dashboard {
+        title: String,
+        visitors: u32,
+    }
+
+    fn replace(path: &str, value: serde_json::Value) -> Vec<PatchOperation> {
+        vec![PatchOperation::Replace {
+            path: path.into(),
+            value,
+        }]
+    }
+
+    #[tokio::test]
+    async fn hub_forwards_changes_to_other_clients() {
+        _ = any_spawner::Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+
+        let hub = StoreSyncHub::new(Dashboard::default());
+        let (a_tx, a_input) = mpsc::unbounded();
+        let (b_tx, b_input) = mpsc::unbounded::<Result<_, ServerFnError>>();
+        let mut a = hub.connect(a_input.into());
+        let mut b = hub.connect(b_input.into());
+
+        // each client starts from the current value
+        let initial = replace("", json!({ "title": "", "visitors": 0 }));
+        assert_eq!(a.next().await.unwrap().unwrap(), initial);
+        assert_eq!(b.next().await.unwrap().unwrap(), initial);
+
+        a_tx.unbounded_send(Ok(replace("/visitors", json!(3))))
+            .unwrap();
+        assert_eq!(
+            b.next().await.unwrap().unwrap(),
+            replace("/visitors", json!(3))
+        );
+        assert_eq!(hub.value().visitors, 3);
+
+        hub.apply_patch(&replace("/title", json!("Sales"))).unwrap();
+        assert_eq!(
+            a.next().await.unwrap().unwrap(),
+            replace("/title", json!("Sales"))
+        );
+        assert_eq!(
+            b.next().await.unwrap().unwrap(),
+            replace("/title", json!("Sales"))
+        );
+
+        // a change that cannot be applied resyncs the client that sent it
+        a_tx.unbounded_send(Ok(replace("/missing", json!(1))))
+            .unwrap();
+        assert_eq!(
+            a.next().await.unwrap().unwrap(),
+            replace("", json!({ "title": "Sales", "visitors": 3 }))
+        );
+        drop(b_tx);
+    }
+
+    #[tokio::test]
+    async fn received_changes_are_resolved_with_policy() {
+        _ = any_spawner::Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+
+        let last_writer = SyncedStore::new(
+            || Dashboard {
+                title: "Local".into(),
+                visitors: 1,
+            },
+            |_| async { Err(ServerFnError::Request("offline".into())) },
+        );
+        last_writer.receive(&replace("/visitors", json!(5)));
+        assert_eq!(last_writer.clone().visitors().get(), 5);
+
+        let merged = SyncedStore::new_with_policy(
+            || Dashboard {
+                title: "Local".into(),
+                visitors: 10,
+            },
+            |_| async { Err(ServerFnError::Request("offline".into())) },
+            ConflictPolicy::merge(|current: &Dashboard, new: Dashboard| {
+                Dashboard {
+                    visitors: current.visitors.max(new.visitors),
+                    ..new
+                }
+            }),
+        );
+        merged.receive(&replace("/visitors", json!(5)));
+        merged.receive(&replace("/title", json!("Remote")));
+        assert_eq!(
+            merged.store().read_untracked().clone(),
+            Dashboard {
+                title: "Remote".into(),
+                visitors: 10,
+            }
+        );
+        assert_eq!(merged.status().get(), SyncStatus::Connecting);
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{for_loop::owned_rows, into_view::IntoView};
+use leptos_dom::helpers::{set_timeout, window};
+use leptos_macro::component;
+use or_poisoned::OrPoisoned;
+use std::{
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tachys::view::keyed::{keyed, ListAnimation, SerializableKey};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+
+/// Iterates over children and displays them, keyed by the `key` function given, animating items
+/// as they are added, removed, or reordered.
+///
+/// This works like [`For`](crate::control_flow::For), but applies CSS classes to the elements of
+/// each item:
+/// - `enter_class` is added to new items, and removed once its animation ends. It should define
+///   a keyframe `animation`: a `transition` would have no earlier style to start from, because
+///   the class is already present when the item is first rendered.
+/// - `leave_class` is added to removed items, which are only removed from the DOM once its
+///   animation or transition ends.
+/// - `move_class` is added to items that move to a new position, which are animated from their
+///   old position using the [FLIP](https://aerotwist.com/blog/flip-your-animations/) technique.
+///   It should define a `transition` for `transform`.
+///
+/// The durations of the animations are read from the elements' computed styles.
+///
+/// ```
+/// # use leptos::prelude::*;
+/// # use leptos::control_flow::TransitionFor;
+/// #[component]
+/// fn Todos() -> impl IntoView {
+///   let todos = RwSignal::new(vec![(0, "Write docs"), (1, "Ship it")]);
+///
+///   view! {
+///     <ul>
+///       <TransitionFor
+///         each=move || todos.get()
+///         key=|todo| todo.0
+///         enter_class="fade-in"
+///         leave_class="fade-out"
+///         move_class="slide"
+///         let((_, text))
+///       >
+///         <li>{text}</li>
+///       </TransitionFor>
+///     </ul>
+///   }
+/// }
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+#[component]
+pub fn TransitionFor<IF, I, T, EF, N, KF, K>(
+    /// Items over which the component should iterate.
+    each: IF,
+    /// A key function that will be applied to each item.
+    key: KF,
+    /// A function that takes the item, and returns the view that will be displayed for each item.
+    children: EF,
+    /// Optional CSS class to apply to items as they are added, which should define a keyframe
+    /// `animation`.
+    #[prop(optional)]
+    enter_class: &'static str,
+    /// Optional CSS class to apply to items as they are removed.
+    #[prop(optional)]
+    leave_class: &'static str,
+    /// Optional CSS class to apply to items while they move to a new position.
+    #[prop(optional)]
+    move_class: &'static str,
+) -> impl IntoView
+where
+    IF: Fn() -> I + Send + 'static,
+    I: IntoIterator<Item = T> + Send + 'static,
+    EF: Fn(T) -> N + Send + Clone + 'static,
+    N: IntoView + 'static,
+    KF: Fn(&T) -> K + Send + Clone + 'static,
+    K: Eq + Hash + SerializableKey + 'static,
+    T: Send + 'static,
+{
+    let animation: Arc<dyn ListAnimation> = Arc::new(ClassAnimation {
+        enter_class,
+        leave_class,
+        move_class,
+        first_positions: Mutex::new(Vec::new()),
+    });
+
+    let children = owned_rows(children);
+    move || {
+        keyed(each(), key.clone(), children.clone())
+            .with_animation(Arc::clone(&animation))
+    }
+}
+
+/// Animates the items of a [`TransitionFor`] by applying CSS classes.
+struct ClassAnimation {
+    enter_class: &'static str,
+    leave_class: &'static str,
+    move_class: &'static str,
+    /// The positions of moving elements before the DOM was updated.
+    first_positions: Mutex<Vec<(f64, f64)>>,
+}
+
+impl ListAnimation for ClassAnimation {
+    fn enter(&self, elements: &[Element]) {
+        if self.enter_class.is_empty() {
+            return;
+        }
+        // new elements are first rendered with the class, so only an `animation` plays here
+        for element in elements {
+            add_classes(element, self.enter_class);
+        }
+        let enter_class = self.enter_class;
+        let elements = elements.to_vec();
+        after_animations(&elements.clone(), move || {
+            for element in &elements {
+                remove_classes(element, enter_class);
+            }
+        });
+    }
+
+    fn leave(&self, elements: &[Element], done: Box<dyn FnOnce()>) {
+        for element in elements {
+            add_classes(element, self.leave_class);
+        }
+        after_animations(elements, done);
+    }
+
+    fn before_move(&self, elements: &[Element]) {
+        if self.move_class.is_empty() {
+            return;
+        }
+        *self.first_positions.lock().or_poisoned() = elements
+            .iter()
+            .map(|element| {
+                let rect = element.get_bounding_client_rect();
+                (rect.left(), rect.top())
+            })
+            .collect();
+    }
+
+    fn after_move(&self, elements: &[Element]) {
+        if self.move_class.is_empty() {
+            return;
+        }
+        let first =
+            std::mem::take(&mut *self.first_positions.lock().or_poisoned());
+        let mut moved = Vec::new();
+        for (element, (left, top)) in elements.iter().zip(first) {
+            let Some(html) = element.dyn_ref::<HtmlElement>() else {
+                continue;
+            };
+            let rect = element.get_bounding_client_rect();
+            let (dx, dy) = (left - rect.left(), top - rect.top());
+            if dx == 0.0 && dy == 0.0 {
+                continue;
+            }
+
+            // invert: put the element back in its old position, without a transition
+            let style = html.style();
+            _ = style.set_property("transition", "none");
+            _ = style.set_property(
+                "transform",
+                &format!("translate({dx}px, {dy}px)"),
+            );
+            // force a reflow, so the old position is rendered before transitioning
+            _ = element.get_bounding_client_rect();
+
+            // play: transition to the new position
+            _ = style.remove_property("transition");
+            add_classes(element, self.move_class);
+            _ = style.remove_property("transform");
+            moved.push(element.clone());
+        }
+        let move_class = self.move_class;
+        after_animations(&moved.clone(), move || {
+            for element in &moved {
+                remove_classes(element, move_class);
+            }
+        });
+    }
+}
+
+fn add_classes(element: &Element, classes: &str) {
+    for class in classes.split_whitespace() {
+        _ = element.class_list().add_1(class);
+    }
+}
+
+fn remove_classes(element: &Element, classes: &str) {
+    for class in classes.split_whitespace() {
+        _ = element.class_list().remove_1(class);
+    }
+}
+
+/// Calls `fun` once the longest CSS animation or transition of any of the elements has finished.
+fn after_animations(elements: &[Element], fun: impl FnOnce() + 'static) {
+    let duration = elements
+        .iter()
+        .filter_map(|element| window().get_computed_style(element).ok()?)
+        .map(|style| {
+            let animation = longest_time(
+                &style
+                    .get_property_value("animation-duration")
+                    .unwrap_or_default(),
+                &style
+                    .get_property_value("animation-delay")
+                    .unwrap_or_default(),
+            );
+            let transition = longest_time(
+                &style
+                    .get_property_value("transition-duration")
+                    .unwrap_or_default(),
+                &style
+                    .get_property_value("transition-delay")
+                    .unwrap_or_default(),
+            );
+            animation.max(transition)
+        })
+        .max()
+        .unwrap_or_default();
+    if duration.is_zero() {
+        fun();
+    } else {
+        set_timeout(fun, duration);
+    }
+}
+
+/// Returns the longest total time of a list of CSS durations and their matching delays.
+fn longest_time(durations: &str, delays: &str) -> Duration {
+    let delays = delays.split(',').map(css_time).collect::<Vec<_>>();
+    durations
+        .split(',')
+        .map(css_time)
+        .enumerate()
+        .map(|(i, duration)| {
+            // as in CSS, the list of delays is repeated if it is shorter
+            let delay = if delays.is_empty() {
+                Duration::ZERO
+            } else {
+                delays[i % delays.len()]
+            };
+            duration + delay
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+/// Parses a CSS `<time>`, like `0.3s` or `300ms`.
+fn css_time(time: &str) -> Duration {
+    let time = time.trim();
+    let millis = if let Some(ms) = time.strip_suffix("ms") {
+        ms.parse::<f64>().ok()
+    } else {
+        time.strip_suffix('s')
+            .and_then(|s| s.parse::<f64>().ok())
+            .map(|s| s * 1000.0)
+    };
+    millis
+        .filter(|ms| ms.is_finite() && *ms > 0.0)
+        .map(|ms| Duration::from_secs_f64(ms / 1000.0))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{css_time, longest_time};
+    use std::time::Duration;
+
+    #[test]
+    fn parses_css_times() {
+        assert_eq!(css_time("0.3s"), Duration::from_millis(300));
+        assert_eq!(css_time(" 150ms"), Duration::from_millis(150));
+        assert_eq!(css_time("0s"), Duration::ZERO);
+        assert_eq!(css_time("-1s"), Duration::ZERO);
+        assert_eq!(css_time("auto"), Duration::ZERO);
+    }
+
+    #[test]
+    fn finds_longest_time_with_delays() {
+        assert_eq!(
+            longest_time("0.2s, 100ms", "0s, 0.5s"),
+            Duration::from_millis(600)
+        );
+        assert_eq!(
+            longest_time("0.2s, 100ms, 50ms", "1s"),
+            Duration::from_millis(1200)
+        );
+        assert_eq!(longest_time("0s", "0s"), Duration::ZERO);
+    }
+}
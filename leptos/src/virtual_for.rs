@@ -0,0 +1,455 @@
+use crate::{for_loop::owned_rows, into_view::IntoView};
+use leptos_macro::{component, view};
+use reactive_graph::{
+    computed::Memo,
+    effect::Effect,
+    signal::RwSignal,
+    traits::{Get, GetUntracked, Set, Track, Update, With, WithUntracked},
+};
+use rustc_hash::FxHashMap;
+use std::{hash::Hash, ops::Range};
+use tachys::{
+    html::element::Div,
+    prelude::*,
+    reactive_graph::node_ref::NodeRef,
+    view::keyed::{keyed, SerializableKey},
+};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{Element, HtmlDivElement, ResizeObserver, ResizeObserverEntry};
+
+/// How the rows of a [`VirtualFor`] are sized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowHeight {
+    /// Every row has the same height, in pixels.
+    Fixed(f64),
+    /// Rows are measured with a `ResizeObserver` once they have been rendered. Rows that have not
+    /// been rendered yet are assumed to have the estimated height, in pixels.
+    ///
+    /// Measured heights belong to the item with the row's key, so they move with the item when
+    /// the items are reordered, and are forgotten when it is removed.
+    ///
+    /// Each row should render exactly one element, which is the element that will be measured.
+    Measured(f64),
+}
+
+impl From<f64> for RowHeight {
+    fn from(value: f64) -> Self {
+        RowHeight::Fixed(value)
+    }
+}
+
+/// Iterates over children and displays them, keyed by the `key` function given, but only renders
+/// the rows that are visible in its scroll container.
+///
+/// This is useful for lists or tables with many thousands of rows, which would be slow to render
+/// in full with [`For`](crate::control_flow::For). The list is rendered into a `<div>` that
+/// scrolls vertically; give it a fixed height with the `class` prop. Only the rows that are
+/// currently visible in it, plus `overscan` rows on either side, are rendered. Rows that remain
+/// visible while scrolling keep their DOM nodes and reactive state.
+///
+/// During server rendering, and on the first render in the browser, the first `initial_rows` rows
+/// are rendered, with space reserved for the rest.
+///
+/// ```
+/// # use leptos::prelude::*;
+/// # use leptos::control_flow::VirtualFor;
+/// #[component]
+/// fn Products() -> impl IntoView {
+///   let products = RwSignal::new((0..50_000).collect::<Vec<usize>>());
+///
+///   view! {
+///     <VirtualFor
+///       each=move || products.get()
+///       key=|id| *id
+///       row_height=32.0
+///       class="product-list"
+///       let(id)
+///     >
+///       <div class="product">"Product #" {id}</div>
+///     </VirtualFor>
+///   }
+/// }
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+#[component]
+pub fn VirtualFor<IF, I, T, EF, N, KF, K>(
+    /// Items over which the component should iterate.
+    each: IF,
+    /// A key function that will be applied to each item.
+    key: KF,
+    /// A function that takes the item, and returns the view that will be displayed for each item.
+    children: EF,
+    /// The height of each row. A number is treated as a fixed height in pixels.
+    #[prop(into)]
+    row_height: RowHeight,
+    /// The number of rows to render above and below the visible rows.
+    #[prop(default = 3)]
+    overscan: usize,
+    /// The number of rows to render before the scroll container has been measured, including
+    /// during server rendering.
+    #[prop(default = 20)]
+    initial_rows: usize,
+    /// Optional CSS class for the scroll container.
+    #[prop(optional)]
+    class: &'static str,
+) -> impl IntoView
+where
+    IF: Fn() -> I + Send + Sync + 'static,
+    I: IntoIterator<Item = T> + Send + 'static,
+    EF: Fn(T) -> N + Send + Clone + 'static,
+    N: IntoView + 'static,
+    KF: Fn(&T) -> K + Send + Clone + 'static,
+    K: Eq + Hash + SerializableKey + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    let (estimate, measured) = match row_height {
+        RowHeight::Fixed(height) => (height, false),
+        RowHeight::Measured(height) => (height, true),
+    };
+    let items = Memo::new_owning(move |_| {
+        (each().into_iter().collect::<Vec<_>>(), true)
+    });
+    let layout = RwSignal::new(RowLayout::new(estimate));
+    if measured {
+        // when the items change, each measured height moves to wherever its item is now
+        let key = key.clone();
+        Effect::watch(
+            move || items.track(),
+            move |_, _, _| {
+                let keys = items.with_untracked(|items| {
+                    items.iter().map(&key).collect::<Vec<_>>()
+                });
+                layout.maybe_update(|layout| layout.set_keys(keys));
+            },
+            false,
+        );
+    }
+    let scroll_top = RwSignal::new(0.0);
+    let viewport = RwSignal::new(None::<f64>);
+
+    let window = Memo::new(move |_| {
+        let len = items.with(Vec::len);
+        match viewport.get() {
+            Some(height) => layout.with(|layout| {
+                layout.window(len, scroll_top.get(), height, overscan)
+            }),
+            None => 0..initial_rows.min(len),
+        }
+    });
+
+    // rows outside the window are replaced by padding, so the scrollbar reflects the whole list
+    let padding = move || {
+        let window = window.get();
+        let len = items.with(Vec::len);
+        layout.with(|layout| {
+            let before = layout.offset(window.start);
+            let after = layout.offset(len) - layout.offset(window.end);
+            format!("padding-top:{before}px;padding-bottom:{after}px")
+        })
+    };
+
+    let children = owned_rows(children);
+    let rows = {
+        let key = key.clone();
+        move || {
+            let window = window.get();
+            let visible = items.with(|items| items[window].to_vec());
+            keyed(visible, key.clone(), children.clone())
+        }
+    };
+
+    let container = NodeRef::<Div>::new();
+    let rows_container = NodeRef::<Div>::new();
+
+    // measures the scroll container, and the rendered rows if they are not of a fixed height
+    Effect::new(move |observer: Option<Option<RowObserver>>| {
+        window.track();
+        let observer = observer.flatten().or_else(|| {
+            let key = key.clone();
+            RowObserver::new(
+                container.get()?,
+                rows_container.get()?,
+                window,
+                move |index| {
+                    items.with_untracked(|items| items.get(index).map(&key))
+                },
+                viewport,
+                layout,
+                measured,
+            )
+        });
+        if let Some(observer) = &observer {
+            observer.observe_rows();
+        }
+        observer
+    });
+
+    view! {
+        <div
+            node_ref=container
+            class=class
+            style="overflow-y:auto"
+            on:scroll=move |_| {
+                if let Some(container) = container.get_untracked() {
+                    scroll_top.set(f64::from(container.scroll_top()));
+                }
+            }
+        >
+            <div node_ref=rows_container style=padding>
+                {rows}
+            </div>
+        </div>
+    }
+}
+
+/// Observes the size of a [`VirtualFor`]'s scroll container and rows.
+struct RowObserver {
+    observer: ResizeObserver,
+    container: HtmlDivElement,
+    rows: HtmlDivElement,
+    measured: bool,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl RowObserver {
+    fn new<K>(
+        container: HtmlDivElement,
+        rows: HtmlDivElement,
+        window: Memo<Range<usize>>,
+        key_at: impl Fn(usize) -> Option<K> + 'static,
+        viewport: RwSignal<Option<f64>>,
+        layout: RwSignal<RowLayout<K>>,
+        measured: bool,
+    ) -> Option<Self>
+    where
+        K: Eq + Hash + Send + Sync + 'static,
+    {
+        let callback = Closure::new({
+            let container = container.clone();
+            let rows = rows.clone();
+            move |entries: js_sys::Array| {
+                let start = window.get_untracked().start;
+                let children = rows.children();
+                let mut heights = Vec::new();
+                for entry in entries.iter() {
+                    let target =
+                        entry.unchecked_into::<ResizeObserverEntry>().target();
+                    if &target == AsRef::<Element>::as_ref(&container) {
+                        viewport
+                            .set(Some(f64::from(container.client_height())));
+                    } else if let Some(position) = (0..children.length())
+                        .find(|i| children.item(*i).as_ref() == Some(&target))
+                    {
+                        let index = start + position as usize;
+                        if let Some(key) = key_at(index) {
+                            heights.push((
+                                index,
+                                key,
+                                target.get_bounding_client_rect().height(),
+                            ));
+                        }
+                    }
+                }
+                if !heights.is_empty() {
+                    layout.maybe_update(|layout| {
+                        let mut changed = false;
+                        for (index, key, height) in heights {
+                            changed |= layout.set_height(index, key, height);
+                        }
+                        changed
+                    });
+                }
+            }
+        });
+        let observer =
+            ResizeObserver::new(callback.as_ref().unchecked_ref()).ok()?;
+        observer.observe(&container);
+        Some(Self {
+            observer,
+            container,
+            rows,
+            measured,
+            _callback: callback,
+        })
+    }
+
+    /// Starts observing the rows that are currently rendered, and stops observing any that have
+    /// been removed.
+    fn observe_rows(&self) {
+        if !self.measured {
+            return;
+        }
+        self.observer.disconnect();
+        self.observer.observe(&self.container);
+        let children = self.rows.children();
+        for i in 0..children.length() {
+            if let Some(row) = children.item(i) {
+                self.observer.observe(&row);
+            }
+        }
+    }
+}
+
+impl Drop for RowObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+/// The vertical positions of the rows in a [`VirtualFor`].
+#[derive(Debug)]
+struct RowLayout<K> {
+    /// The height assumed for rows that have not been measured.
+    estimate: f64,
+    /// The measured height of each item, by its key.
+    heights: FxHashMap<K, f64>,
+    /// The distance from the top of the list to each row, up to and including the end of the
+    /// last measured row.
+    offsets: Vec<f64>,
+}
+
+impl<K: Eq + Hash> RowLayout<K> {
+    fn new(estimate: f64) -> Self {
+        Self {
+            estimate: estimate.max(1.0),
+            heights: FxHashMap::default(),
+            offsets: vec![0.0],
+        }
+    }
+
+    /// The distance from the top of the list to the row at `index`.
+    fn offset(&self, index: usize) -> f64 {
+        match self.offsets.get(index) {
+            Some(offset) => *offset,
+            None => {
+                let measured = self.offsets.len() - 1;
+                self.offsets[measured]
+                    + (index - measured) as f64 * self.estimate
+            }
+        }
+    }
+
+    /// The index of the row at the given distance from the top of the list.
+    fn index_at(&self, offset: f64) -> usize {
+        let measured = self.offsets.len() - 1;
+        let measured_end = self.offsets[measured];
+        if offset >= measured_end {
+            measured.saturating_add(
+                ((offset - measured_end) / self.estimate) as usize,
+            )
+        } else {
+            self.offsets
+                .partition_point(|row| *row <= offset)
+                .saturating_sub(1)
+        }
+    }
+
+    /// Sets the measured height of the row at `index`, which shows the item with the given key,
+    /// returning `true` if it has changed.
+    fn set_height(&mut self, index: usize, key: K, height: f64) -> bool {
+        self.heights.insert(key, height);
+        while self.offsets.len() < index + 2 {
+            let last = self.offsets[self.offsets.len() - 1];
+            self.offsets.push(last + self.estimate);
+        }
+        let delta = height - (self.offsets[index + 1] - self.offsets[index]);
+        if delta == 0.0 {
+            return false;
+        }
+        for offset in &mut self.offsets[index + 1..] {
+            *offset += delta;
+        }
+        true
+    }
+
+    /// Positions the rows for the items with the given keys, in order, using the heights that
+    /// were measured for those items. Returns `true` if any row has moved.
+    ///
+    /// The heights of items that are no longer in the list are forgotten.
+    fn set_keys(&mut self, keys: impl IntoIterator<Item = K>) -> bool {
+        let mut heights = FxHashMap::default();
+        let mut offsets = vec![0.0];
+        // the number of rows up to and including the last measured row
+        let mut measured = 0;
+        for (index, key) in keys.into_iter().enumerate() {
+            let height = self.heights.remove(&key);
+            offsets.push(offsets[index] + height.unwrap_or(self.estimate));
+            if let Some(height) = height {
+                heights.insert(key, height);
+                measured = index + 1;
+            }
+        }
+        offsets.truncate(measured + 1);
+        self.heights = heights;
+        let changed = offsets != self.offsets;
+        self.offsets = offsets;
+        changed
+    }
+
+    /// The range of rows that should be rendered to fill the viewport.
+    fn window(
+        &self,
+        len: usize,
+        scroll_top: f64,
+        viewport: f64,
+        overscan: usize,
+    ) -> Range<usize> {
+        let start = self.index_at(scroll_top.max(0.0));
+        let end = self
+            .index_at(scroll_top.max(0.0) + viewport)
+            .saturating_add(1 + overscan);
+        start.saturating_sub(overscan).min(len)..end.min(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RowLayout;
+
+    #[test]
+    fn fixed_rows_are_positioned_by_index() {
+        let layout = RowLayout::<usize>::new(20.0);
+        assert_eq!(layout.offset(50_000), 1_000_000.0);
+        assert_eq!(layout.index_at(105.0), 5);
+        assert_eq!(layout.window(50_000, 1000.0, 200.0, 2), 48..63);
+        assert_eq!(layout.window(50_000, 0.0, 200.0, 2), 0..13);
+        assert_eq!(layout.window(10, 1000.0, 200.0, 2), 10..10);
+    }
+
+    #[test]
+    fn measured_rows_shift_following_rows() {
+        let mut layout = RowLayout::new(20.0);
+        assert!(layout.set_height(1, 1, 50.0));
+        assert!(!layout.set_height(1, 1, 50.0));
+        assert_eq!(layout.offset(1), 20.0);
+        assert_eq!(layout.offset(2), 70.0);
+        assert_eq!(layout.offset(10), 230.0);
+        assert_eq!(layout.index_at(69.0), 1);
+        assert_eq!(layout.index_at(70.0), 2);
+        assert_eq!(layout.index_at(95.0), 3);
+
+        assert!(layout.set_height(0, 0, 10.0));
+        assert_eq!(layout.offset(2), 60.0);
+        assert_eq!(layout.window(100, 15.0, 40.0, 0), 1..2);
+    }
+
+    #[test]
+    fn measured_heights_move_with_their_items() {
+        let mut layout = RowLayout::new(20.0);
+        layout.set_height(1, "b", 50.0);
+        assert_eq!(layout.offset(3), 90.0);
+
+        // an item is added before the measured one, which moves down a row
+        assert!(layout.set_keys(["x", "a", "b", "c"]));
+        assert_eq!(layout.offset(2), 40.0);
+        assert_eq!(layout.offset(3), 90.0);
+        assert_eq!(layout.offset(4), 110.0);
+        assert!(!layout.set_keys(["x", "a", "b", "c"]));
+
+        // the measured item is removed, so every row has the estimated height again
+        assert!(layout.set_keys(["x", "a", "c"]));
+        assert_eq!(layout.offset(3), 60.0);
+        assert!(!layout.set_keys(["x", "b", "c"]));
+        assert_eq!(layout.offset(2), 40.0);
+    }
+}
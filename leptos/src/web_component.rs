@@ -0,0 +1,507 @@
+//! Registers components as standard [Web Components](https://developer.mozilla.org/en-US/docs/Web/API/Web_components),
+//! so they can be used from plain HTML or from applications built with other frameworks.
+//!
+//! Add the [`#[custom_element]`](macro@crate::custom_element) attribute above `#[component]`,
+//! then call [`define`] with the component’s props type and a tag name when your app starts:
+//!
+//! ```rust
+//! use leptos::{prelude::*, web_component::define};
+//!
+//! /// A counter that can be used from any framework.
+//! #[custom_element]
+//! #[component]
+//! fn Counter(
+//!     /// Set with the `label` attribute or the `label` property.
+//!     label: String,
+//!     /// Set with the `initial-value` attribute or the `initialValue` property.
+//!     #[prop(optional)]
+//!     initial_value: i32,
+//!     /// Dispatches a `CustomEvent` named `change`, with the new count as its `detail`.
+//!     #[prop(optional)]
+//!     on_change: Option<Callback<i32>>,
+//! ) -> impl IntoView {
+//!     let count = RwSignal::new(initial_value);
+//!     let increment = move |_| {
+//!         *count.write() += 1;
+//!         if let Some(on_change) = on_change {
+//!             on_change.run(count.get());
+//!         }
+//!     };
+//!     view! { <button on:click=increment>{label} ": " {count}</button> }
+//! }
+//!
+//! # if false {
+//! define::<CounterProps>("my-counter").unwrap();
+//! # }
+//! ```
+//!
+//! ```html
+//! <my-counter label="Clicks" initial-value="3"></my-counter>
+//! <script>
+//!   document.querySelector("my-counter")
+//!     .addEventListener("change", (ev) => console.log(ev.detail));
+//! </script>
+//! ```
+//!
+//! Each element renders its component into its own shadow root when it is connected to the
+//! document, and unmounts it when it is disconnected. Props are mapped as follows:
+//! - ordinary props are set from an attribute (the prop name in `kebab-case`) or a JavaScript
+//!   property (the prop name in `camelCase`), converted with [`FromElementValue`]. Changing one of
+//!   them renders the component again with the new props.
+//! - props of type [`Signal<T>`] are set in the same way, but are updated in place without
+//!   rendering the component again, so they are the best choice for values that change often.
+//! - [`Callback`] and [`UnsyncCallback`] props dispatch a [`CustomEvent`](web_sys::CustomEvent)
+//!   from the element, named after the prop without its `on_` prefix, in `kebab-case`. The value
+//!   the callback is called with is its `detail`.
+//! - a `children` prop renders a `<slot>`, which displays the element’s own children.
+
+use crate::{
+    callback::{Callback, UnsyncCallback},
+    mount::mount_to_shadow_root,
+};
+use reactive_graph::{
+    signal::ArcRwSignal,
+    traits::{Set, With},
+    wrappers::read::Signal,
+};
+use send_wrapper::SendWrapper;
+use std::{any::Any, cell::RefCell, collections::HashMap, sync::Arc};
+use tachys::view::any_view::AnyView;
+use wasm_bindgen::{closure::Closure, prelude::wasm_bindgen, JsValue};
+use web_sys::{HtmlElement, ShadowRoot, ShadowRootMode};
+
+/// A component that can be registered as a custom element with [`define`].
+///
+/// This is implemented for the props type of a component by the
+/// [`#[custom_element]`](macro@crate::custom_element) macro.
+pub trait CustomElement: 'static {
+    /// The names of the attributes the element observes, one for each prop that can be set.
+    const ATTRIBUTES: &'static [&'static str];
+
+    /// The names of the JavaScript properties of the element, in the same order as
+    /// [`ATTRIBUTES`](Self::ATTRIBUTES).
+    const PROPERTIES: &'static [&'static str];
+
+    /// Renders the component, reading its props from the `host` element.
+    fn render(host: ElementHost) -> AnyView;
+}
+
+/// Registers the component `C` as a custom element named `tag`, which renders into an open
+/// shadow root.
+///
+/// Returns an error if the name is not a valid custom element name, or has already been
+/// defined. On the server, this does nothing.
+pub fn define<C: CustomElement>(tag: &str) -> Result<(), JsValue> {
+    define_with_mode::<C>(tag, ShadowRootMode::Open)
+}
+
+/// Registers the component `C` as a custom element named `tag`, which renders into a shadow
+/// root with the given `mode`.
+///
+/// A [`Closed`](ShadowRootMode::Closed) shadow root cannot be accessed from outside the element
+/// through its `shadowRoot` property.
+///
+/// Returns an error if the name is not a valid custom element name, or has already been
+/// defined. On the server, this does nothing.
+pub fn define_with_mode<C: CustomElement>(
+    tag: &str,
+    mode: ShadowRootMode,
+) -> Result<(), JsValue> {
+    if !cfg!(target_arch = "wasm32") {
+        return Ok(());
+    }
+
+    let connected =
+        Closure::<dyn Fn(u32, HtmlElement, ShadowRoot, JsValue)>::new(
+            |id, element: HtmlElement, root: ShadowRoot, properties| {
+                let values = C::ATTRIBUTES
+                    .iter()
+                    .zip(C::PROPERTIES)
+                    .map(|(attribute, property)| {
+                        let value = js_sys::Reflect::get(
+                            &properties,
+                            &JsValue::from_str(property),
+                        )
+                        .ok()
+                        .and_then(present)
+                        .or_else(|| {
+                            element
+                                .get_attribute(attribute)
+                                .and_then(|value| present(value.into()))
+                        });
+                        ArcRwSignal::new(value)
+                    })
+                    .collect::<Arc<[_]>>();
+                let host = ElementHost {
+                    element: SendWrapper::new(element),
+                    values: Arc::clone(&values),
+                };
+                let handle =
+                    mount_to_shadow_root(root, move || C::render(host));
+                INSTANCES.with_borrow_mut(|instances| {
+                    instances.insert(
+                        id,
+                        Instance {
+                            values,
+                            _handle: Box::new(handle),
+                        },
+                    )
+                });
+            },
+        );
+    let disconnected = Closure::<dyn Fn(u32)>::new(|id| {
+        let instance =
+            INSTANCES.with_borrow_mut(|instances| instances.remove(&id));
+        drop(instance);
+    });
+    let changed = Closure::<dyn Fn(u32, String, JsValue, bool)>::new(
+        |id, name: String, value, is_property| {
+            let names = if is_property {
+                C::PROPERTIES
+            } else {
+                C::ATTRIBUTES
+            };
+            let Some(index) = names.iter().position(|n| *n == name) else {
+                return;
+            };
+            let signal = INSTANCES.with_borrow(|instances| {
+                instances
+                    .get(&id)
+                    .map(|instance| instance.values[index].clone())
+            });
+            if let Some(signal) = signal {
+                signal.set(present(value));
+            }
+        },
+    );
+
+    define_custom_element(
+        tag,
+        &C::ATTRIBUTES
+            .iter()
+            .map(|name| JsValue::from_str(name))
+            .collect(),
+        &C::PROPERTIES
+            .iter()
+            .map(|name| JsValue::from_str(name))
+            .collect(),
+        if mode == ShadowRootMode::Closed {
+            "closed"
+        } else {
+            "open"
+        },
+        &connected.into_js_value(),
+        &disconnected.into_js_value(),
+        &changed.into_js_value(),
+    )
+}
+
+type ElementValue = Option<SendWrapper<JsValue>>;
+
+struct Instance {
+    values: Arc<[ArcRwSignal<ElementValue>]>,
+    _handle: Box<dyn Any>,
+}
+
+thread_local! {
+    static INSTANCES: RefCell<HashMap<u32, Instance>> = Default::default();
+}
+
+fn present(value: JsValue) -> ElementValue {
+    (!value.is_null() && !value.is_undefined()).then(|| SendWrapper::new(value))
+}
+
+#[wasm_bindgen(inline_js = r#"
+let nextId = 0;
+
+export function defineCustomElement(tag, attributes, properties, mode, connected, disconnected, changed) {
+    const state = new WeakMap();
+
+    class LeptosCustomElement extends HTMLElement {
+        static get observedAttributes() {
+            return attributes;
+        }
+
+        constructor() {
+            super();
+            state.set(this, {
+                id: nextId++,
+                root: this.attachShadow({ mode }),
+                properties: {},
+            });
+            // properties set before the element was upgraded shadow the accessors
+            for (const name of properties) {
+                if (Object.prototype.hasOwnProperty.call(this, name)) {
+                    const value = this[name];
+                    delete this[name];
+                    this[name] = value;
+                }
+            }
+        }
+
+        connectedCallback() {
+            const { id, root, properties } = state.get(this);
+            connected(id, this, root, properties);
+        }
+
+        disconnectedCallback() {
+            disconnected(state.get(this).id);
+        }
+
+        attributeChangedCallback(name, _, value) {
+            changed(state.get(this).id, name, value, false);
+        }
+    }
+
+    for (const name of properties) {
+        Object.defineProperty(LeptosCustomElement.prototype, name, {
+            get() {
+                return state.get(this).properties[name];
+            },
+            set(value) {
+                const s = state.get(this);
+                s.properties[name] = value;
+                changed(s.id, name, value, true);
+            },
+            configurable: true,
+            enumerable: true,
+        });
+    }
+
+    customElements.define(tag, LeptosCustomElement);
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = defineCustomElement, catch)]
+    fn define_custom_element(
+        tag: &str,
+        attributes: &js_sys::Array,
+        properties: &js_sys::Array,
+        mode: &str,
+        connected: &JsValue,
+        disconnected: &JsValue,
+        changed: &JsValue,
+    ) -> Result<(), JsValue>;
+}
+
+/// The custom element a component has been rendered into, which provides the values of its
+/// props.
+///
+/// This is used by the code generated by [`#[custom_element]`](macro@crate::custom_element).
+#[derive(Clone)]
+pub struct ElementHost {
+    element: SendWrapper<HtmlElement>,
+    values: Arc<[ArcRwSignal<ElementValue>]>,
+}
+
+impl ElementHost {
+    /// The custom element.
+    pub fn element(&self) -> &HtmlElement {
+        &self.element
+    }
+
+    /// Reactively reads the value of the prop at `index`, converting it to `T`.
+    pub fn get<T: FromElementValue>(&self, index: usize) -> T {
+        self.values[index].with(|value| T::from_element_value(value.as_deref()))
+    }
+
+    /// Reactively reads the value of the prop at `index`, converting it to `T`, or returns
+    /// `None` if neither its attribute nor its property has been set.
+    pub fn try_get<T: FromElementValue>(&self, index: usize) -> Option<T> {
+        self.values[index].with(|value| {
+            value
+                .as_deref()
+                .map(|value| T::from_element_value(Some(value)))
+        })
+    }
+
+    /// Creates a signal that converts the value of the prop at `index` to `T` whenever it changes.
+    pub fn signal<T>(&self, index: usize) -> Signal<T>
+    where
+        T: FromElementValue + Send + Sync + 'static,
+    {
+        let value = self.values[index].clone();
+        Signal::derive(move || {
+            value.with(|value| T::from_element_value(value.as_deref()))
+        })
+    }
+
+    /// Creates an [`EventDispatcher`] for events named `event`.
+    pub fn event_dispatcher(&self, event: &'static str) -> EventDispatcher {
+        EventDispatcher {
+            element: self.element.clone(),
+            event,
+        }
+    }
+}
+
+/// Dispatches [`CustomEvent`](web_sys::CustomEvent)s from a custom element.
+///
+/// The events bubble, and cross the boundary of the element’s shadow root.
+#[derive(Clone)]
+pub struct EventDispatcher {
+    element: SendWrapper<HtmlElement>,
+    event: &'static str,
+}
+
+impl EventDispatcher {
+    /// Dispatches an event, with `detail` as its `detail`.
+    pub fn dispatch(&self, detail: impl IntoEventDetail) {
+        let init = web_sys::CustomEventInit::new();
+        init.set_bubbles(true);
+        init.set_composed(true);
+        init.set_detail(&detail.into_event_detail());
+        if let Ok(event) =
+            web_sys::CustomEvent::new_with_event_init_dict(self.event, &init)
+        {
+            _ = self.element.dispatch_event(&event);
+        }
+    }
+}
+
+/// Converts the value of an attribute or a JavaScript property into the value of a prop.
+///
+/// The value is `None` if neither the attribute nor the property is set. Attributes are always
+/// strings; properties can be any JavaScript value.
+pub trait FromElementValue: Sized {
+    /// Converts the value.
+    fn from_element_value(value: Option<&JsValue>) -> Self;
+}
+
+impl FromElementValue for String {
+    fn from_element_value(value: Option<&JsValue>) -> Self {
+        value
+            .and_then(|value| {
+                value
+                    .as_string()
+                    .or_else(|| value.as_f64().map(|n| n.to_string()))
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// An attribute is `true` if it is present with any value other than `"false"`. A property is
+/// `true` if it is truthy.
+impl FromElementValue for bool {
+    fn from_element_value(value: Option<&JsValue>) -> Self {
+        value.is_some_and(|value| {
+            value
+                .as_string()
+                .map(|value| value != "false")
+                .unwrap_or_else(|| value.is_truthy())
+        })
+    }
+}
+
+macro_rules! number_from_element_value {
+    ($($ty:ty),*) => {
+        $(
+            /// Values that are not numbers, and cannot be parsed as numbers, are `0`.
+            impl FromElementValue for $ty {
+                fn from_element_value(value: Option<&JsValue>) -> Self {
+                    value
+                        .and_then(|value| {
+                            value.as_f64().or_else(|| {
+                                value.as_string()?.trim().parse().ok()
+                            })
+                        })
+                        .map(|n| n as $ty)
+                        .unwrap_or_default()
+                }
+            }
+        )*
+    };
+}
+
+number_from_element_value!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
+impl<T: FromElementValue> FromElementValue for Option<T> {
+    fn from_element_value(value: Option<&JsValue>) -> Self {
+        value.map(|value| T::from_element_value(Some(value)))
+    }
+}
+
+impl FromElementValue for JsValue {
+    fn from_element_value(value: Option<&JsValue>) -> Self {
+        value.cloned().unwrap_or(JsValue::UNDEFINED)
+    }
+}
+
+/// Converts the value a callback prop is called with into the `detail` of the event it dispatches.
+pub trait IntoEventDetail {
+    /// Converts the value.
+    fn into_event_detail(self) -> JsValue;
+}
+
+impl IntoEventDetail for () {
+    fn into_event_detail(self) -> JsValue {
+        JsValue::UNDEFINED
+    }
+}
+
+impl IntoEventDetail for String {
+    fn into_event_detail(self) -> JsValue {
+        JsValue::from(self)
+    }
+}
+
+impl IntoEventDetail for bool {
+    fn into_event_detail(self) -> JsValue {
+        JsValue::from(self)
+    }
+}
+
+macro_rules! number_into_event_detail {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoEventDetail for $ty {
+                fn into_event_detail(self) -> JsValue {
+                    JsValue::from_f64(self as f64)
+                }
+            }
+        )*
+    };
+}
+
+number_into_event_detail!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
+impl<T: IntoEventDetail> IntoEventDetail for Option<T> {
+    fn into_event_detail(self) -> JsValue {
+        self.map(IntoEventDetail::into_event_detail)
+            .unwrap_or(JsValue::NULL)
+    }
+}
+
+impl IntoEventDetail for JsValue {
+    fn into_event_detail(self) -> JsValue {
+        self
+    }
+}
+
+/// Creates a callback prop that dispatches events from a custom element.
+pub trait FromEventDispatcher {
+    /// Creates the callback.
+    fn from_event_dispatcher(dispatcher: EventDispatcher) -> Self;
+}
+
+impl<T> FromEventDispatcher for Callback<T>
+where
+    T: IntoEventDetail + 'static,
+{
+    fn from_event_dispatcher(dispatcher: EventDispatcher) -> Self {
+        Callback::new(move |detail| dispatcher.dispatch(detail))
+    }
+}
+
+impl<T> FromEventDispatcher for UnsyncCallback<T>
+where
+    T: IntoEventDetail + 'static,
+{
+    fn from_event_dispatcher(dispatcher: EventDispatcher) -> Self {
+        UnsyncCallback::new(move |detail| dispatcher.dispatch(detail))
+    }
+}
@@ -208,6 +208,8 @@ fn ssr_option() {
 
 #[cfg(feature = "ssr")]
 #[test]
+// the textarea has no label, which the `a11y` lints warn about
+#[allow(deprecated)]
 fn ssr_textarea_escapes_static_content() {
     use leptos::prelude::*;
 
@@ -225,6 +227,8 @@ fn ssr_textarea_escapes_static_content() {
 
 #[cfg(feature = "ssr")]
 #[test]
+// the textarea has no label, which the `a11y` lints warn about
+#[allow(deprecated)]
 fn ssr_textarea_escapes_dynamic_content() {
     use leptos::prelude::*;
 
@@ -241,3 +245,68 @@ fn ssr_textarea_escapes_dynamic_content() {
          </textarea>"
     );
 }
+
+#[cfg(feature = "ssr")]
+#[test]
+fn shadow_root_renders_declarative_template() {
+    use leptos::{
+        prelude::*,
+        shadow_root::{ShadowRoot, ShadowRootMode},
+    };
+
+    let owner = Owner::new();
+    owner.set();
+    let rendered = view! {
+        <div>
+            <ShadowRoot mode=ShadowRootMode::Closed>
+                <p>"inside"</p>
+            </ShadowRoot>
+            <p>"outside"</p>
+        </div>
+    };
+
+    assert_eq!(
+        rendered.to_html(),
+        "<div><template shadowrootmode=\"closed\"><p>inside</p></template>\
+         <p>outside</p></div>"
+    );
+}
+
+#[cfg(feature = "ssr")]
+#[test]
+fn virtual_for_renders_initial_window() {
+    use leptos::{control_flow::VirtualFor, prelude::*};
+
+    let owner = Owner::new();
+    owner.set();
+    let rendered = view! {
+        <VirtualFor each=|| 0..1000 key=|i| *i row_height=10.0 initial_rows=3 let(i)>
+            <p>{i}</p>
+        </VirtualFor>
+    };
+
+    assert_eq!(
+        rendered.to_html(),
+        "<div class=\"\" style=\"overflow-y:auto;\"><div \
+         style=\"padding-top:0px;padding-bottom:9970px;\"> <p>0</p> \
+         <p>1</p> <p>2</p><!></div></div>"
+    );
+}
+
+#[cfg(all(feature = "ssr", feature = "sanitize"))]
+#[test]
+fn safe_inner_html_sanitizes_markup() {
+    use leptos::prelude::*;
+
+    let owner = Owner::new();
+    owner.set();
+    let markdown = RwSignal::new(String::from(
+        "<h1>Title</h1><img src=\"x.png\" onerror=\"steal()\">",
+    ));
+    let rendered = view! { <article safe_inner_html=move || markdown.get()/> };
+
+    assert_eq!(
+        rendered.to_html(),
+        "<article><h1>Title</h1><img src=\"x.png\"></article>"
+    );
+}
@@ -114,6 +114,42 @@ fn maybe_modify_return_type(ret: &mut ReturnType) {
     }
 }
 
+/// Whether the component returns an opaque view type, which can be wrapped in another view
+/// without changing its return type.
+fn returns_view(ret: &ReturnType) -> bool {
+    let ReturnType::Type(_, ty) = ret else {
+        return false;
+    };
+    let Type::ImplTrait(TypeImplTrait { bounds, .. }) = ty.as_ref() else {
+        return false;
+    };
+    let is_trait = |bound: &syn::TypeParamBound, names: &[&str]| match bound {
+        syn::TypeParamBound::Trait(trait_bound) => {
+            trait_bound.path.segments.last().is_some_and(|segment| {
+                names.iter().any(|name| segment.ident == name)
+            })
+        }
+        _ => false,
+    };
+    bounds
+        .iter()
+        .any(|bound| is_trait(bound, &["IntoView", "RenderHtml"]))
+        && bounds.iter().all(|bound| {
+            !matches!(bound, syn::TypeParamBound::Trait(_))
+                || is_trait(
+                    bound,
+                    &[
+                        "IntoView",
+                        "RenderHtml",
+                        "Render",
+                        "Clone",
+                        "Send",
+                        "Sync",
+                    ],
+                )
+        })
+}
+
 // implemented manually because Vec::drain_filter is nightly only
 // follows std recommended parallel
 pub fn drain_filter<T>(
@@ -359,6 +395,23 @@ impl ToTokens for Model {
             }
         };
 
+        // record the component's name while hydrating it, for hydration error messages. the view
+        // is always wrapped, and tachys only keeps the name under its own debug cfg
+        let component = if !*is_transparent && returns_view(ret) {
+            let component_name = name.to_string();
+            quote! {
+                {
+                    let __view = #component;
+                    ::leptos::tachys::hydration::ComponentView::new(
+                        #component_name,
+                        __view
+                    )
+                }
+            }
+        } else {
+            component
+        };
+
         // add island wrapper if island
         let component = if is_island {
             let hydrate_fn_name = hydrate_fn_name.as_ref().unwrap();
@@ -736,15 +789,15 @@ impl ToTokens for DummyModel {
     }
 }
 
-struct Prop {
-    docs: Docs,
-    prop_opts: PropOpt,
-    name: PatIdent,
-    ty: Type,
+pub struct Prop {
+    pub docs: Docs,
+    pub prop_opts: PropOpt,
+    pub name: PatIdent,
+    pub ty: Type,
 }
 
 impl Prop {
-    fn new(arg: FnArg) -> Self {
+    pub fn new(arg: FnArg) -> Self {
         let typed = if let FnArg::Typed(ty) = arg {
             ty
         } else {
@@ -999,20 +1052,20 @@ impl ToTokens for UnknownAttrs {
 
 #[derive(Clone, Debug, FromAttr)]
 #[attribute(ident = prop)]
-struct PropOpt {
+pub struct PropOpt {
     #[attribute(conflicts = [optional_no_strip, strip_option, marker])]
-    optional: bool,
+    pub optional: bool,
     #[attribute(conflicts = [optional, strip_option, marker])]
-    optional_no_strip: bool,
+    pub optional_no_strip: bool,
     #[attribute(conflicts = [optional, optional_no_strip, marker])]
-    strip_option: bool,
+    pub strip_option: bool,
     #[attribute(example = "5 * 10")]
-    default: Option<syn::Expr>,
-    into: bool,
-    attrs: bool,
-    name: Option<String>,
+    pub default: Option<syn::Expr>,
+    pub into: bool,
+    pub attrs: bool,
+    pub name: Option<String>,
     #[attribute(conflicts = [optional, optional_no_strip, strip_option, default, into, attrs, name])]
-    marker: bool,
+    pub marker: bool,
 }
 
 struct TypedBuilderOpts<'a> {
@@ -1446,3 +1499,38 @@ fn convert_impl_trait_to_generic(sig: &mut Signature) {
         }));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::returns_view;
+    use syn::{parse_quote, ReturnType};
+
+    #[test]
+    fn opaque_views_are_wrapped() {
+        let views: [ReturnType; 4] = [
+            parse_quote!(-> impl IntoView),
+            parse_quote!(-> impl leptos::IntoView + Send + 'static),
+            parse_quote!(-> impl RenderHtml + Clone),
+            parse_quote!(-> impl Render + ::leptos::tachys::view::RenderHtml),
+        ];
+        for ret in views {
+            assert!(returns_view(&ret), "{}", quote::quote!(#ret));
+        }
+    }
+
+    #[test]
+    fn other_return_types_are_not_wrapped() {
+        let others: [ReturnType; 6] = [
+            ReturnType::Default,
+            parse_quote!(-> AnyView),
+            parse_quote!(-> Vec<AnyView>),
+            parse_quote!(-> impl Render),
+            parse_quote!(-> impl Fn() -> AnyView),
+            // wrapping the view would hide the other traits it implements
+            parse_quote!(-> impl IntoView + Debug),
+        ];
+        for ret in others {
+            assert!(!returns_view(&ret), "{}", quote::quote!(#ret));
+        }
+    }
+}
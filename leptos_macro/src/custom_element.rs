@@ -0,0 +1,206 @@
+use crate::component::{
+    convert_from_snake_case, is_option, unwrap_option, Prop,
+};
+use convert_case::{Case, Casing};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use proc_macro_error2::abort;
+use quote::{format_ident, quote};
+use syn::{
+    AngleBracketedGenericArguments, FnArg, GenericArgument, ItemFn,
+    PathArguments, Type, TypePath,
+};
+
+/// How a prop of the component gets its value.
+enum PropKind<'a> {
+    /// Converted from an attribute or property, rendering the component again when it changes.
+    Value,
+    /// A signal derived from an attribute or property.
+    Signal(&'a Type),
+    /// A callback that dispatches a `CustomEvent`.
+    Event(String),
+    /// A `<slot>` for the element's children.
+    Children,
+}
+
+/// Returns the last segment of a type path, and its only generic argument, if any.
+fn last_segment(ty: &Type) -> Option<(String, Option<&Type>)> {
+    let Type::Path(TypePath { path, .. }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    let arg = match &segment.arguments {
+        PathArguments::AngleBracketed(AngleBracketedGenericArguments {
+            args,
+            ..
+        }) => match args.first() {
+            Some(GenericArgument::Type(ty)) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    };
+    Some((segment.ident.to_string(), arg))
+}
+
+pub fn custom_element_impl(
+    args: proc_macro::TokenStream,
+    s: TokenStream,
+) -> TokenStream {
+    if !args.is_empty() {
+        abort!(
+            Span::call_site(),
+            "no arguments are supported";
+            help = "the tag name is given when the element is defined, \
+                    with `leptos::web_component::define`"
+        );
+    }
+
+    let item = syn::parse::<ItemFn>(s.clone()).unwrap_or_else(|e| {
+        abort!(e.span(), "`custom_element` can only be used on a component")
+    });
+
+    if !item.attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "component")
+    }) {
+        abort!(
+            item.sig.ident,
+            "`#[custom_element]` must be placed above `#[component]`"
+        );
+    }
+    if !item.sig.generics.params.is_empty() {
+        abort!(
+            item.sig.generics,
+            "components with generic props cannot be custom elements"
+        );
+    }
+
+    let name = convert_from_snake_case(&item.sig.ident);
+    let props_name = format_ident!("{name}Props");
+
+    let mut attributes = Vec::new();
+    let mut properties = Vec::new();
+    let mut required_props = Vec::new();
+    let mut optional_props = Vec::new();
+
+    for arg in item.sig.inputs.iter().cloned() {
+        if let FnArg::Receiver(_) = arg {
+            continue;
+        }
+        let Prop {
+            prop_opts,
+            name,
+            ty,
+            ..
+        } = Prop::new(arg);
+        if prop_opts.marker || prop_opts.attrs {
+            continue;
+        }
+
+        let ident = name.ident;
+        let prop_name = ident.to_string();
+        let prop_name = prop_name.trim_start_matches("r#");
+        let optional = prop_opts.optional
+            || prop_opts.optional_no_strip
+            || prop_opts.default.is_some();
+        let strip_option =
+            prop_opts.strip_option || prop_opts.optional && is_option(&ty);
+        let inner = if is_option(&ty) {
+            unwrap_option(&ty)
+        } else {
+            ty.clone()
+        };
+
+        let kind = match last_segment(&inner) {
+            _ if prop_name == "children" => PropKind::Children,
+            Some((segment, _))
+                if segment == "Callback" || segment == "UnsyncCallback" =>
+            {
+                let event = prop_name.strip_prefix("on_").unwrap_or(prop_name);
+                PropKind::Event(event.to_case(Case::Kebab))
+            }
+            Some((segment, Some(value))) if segment == "Signal" => {
+                PropKind::Signal(value)
+            }
+            _ => PropKind::Value,
+        };
+
+        let index = attributes.len();
+        let inner_value = match kind {
+            PropKind::Value => {
+                attributes.push(prop_name.to_case(Case::Kebab));
+                properties.push(prop_name.to_case(Case::Camel));
+                if optional {
+                    optional_props.push(quote! {
+                        if let Some(value) = host.try_get::<#ty>(#index) {
+                            props.#ident = value;
+                        }
+                    });
+                } else {
+                    let setter_ty = if strip_option { &inner } else { &ty };
+                    required_props.push(quote! {
+                        .#ident(host.get::<#setter_ty>(#index))
+                    });
+                }
+                continue;
+            }
+            PropKind::Signal(value) => {
+                attributes.push(prop_name.to_case(Case::Kebab));
+                properties.push(prop_name.to_case(Case::Camel));
+                quote! { host.signal::<#value>(#index) }
+            }
+            PropKind::Event(event) => quote! {
+                <#inner as ::leptos::web_component::FromEventDispatcher>::from_event_dispatcher(
+                    host.event_dispatcher(#event)
+                )
+            },
+            PropKind::Children => quote! {
+                ::leptos::children::ToChildren::to_children(
+                    || ::leptos::tachys::html::element::slot()
+                )
+            },
+        };
+        let value = if is_option(&ty) {
+            quote! { Some(#inner_value) }
+        } else {
+            inner_value.clone()
+        };
+
+        if optional {
+            optional_props.push(quote! {
+                props.#ident = #value;
+            });
+        } else if strip_option {
+            required_props.push(quote! { .#ident(#inner_value) });
+        } else {
+            required_props.push(quote! { .#ident(#value) });
+        }
+    }
+
+    let s = proc_macro2::TokenStream::from(s);
+    quote! {
+        #s
+
+        impl ::leptos::web_component::CustomElement for #props_name {
+            const ATTRIBUTES: &'static [&'static str] = &[#(#attributes),*];
+            const PROPERTIES: &'static [&'static str] = &[#(#properties),*];
+
+            #[allow(unused_variables)]
+            fn render(
+                host: ::leptos::web_component::ElementHost,
+            ) -> ::leptos::tachys::view::any_view::AnyView {
+                ::leptos::tachys::view::any_view::IntoAny::into_any(move || {
+                    #[allow(unused_mut)]
+                    let mut props = ::leptos::component::component_props_builder(&#name)
+                        #(#required_props)*
+                        .build();
+                    #(#optional_props)*
+                    ::leptos::component::component_view(&#name, props)
+                })
+            }
+        }
+    }
+    .into()
+}
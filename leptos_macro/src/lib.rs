@@ -23,6 +23,7 @@ mod params;
 mod view;
 use crate::component::unmodified_fn_name_from_fn_name;
 mod component;
+mod custom_element;
 mod lazy;
 mod memo;
 mod slice;
@@ -243,6 +244,12 @@ mod slot;
 /// # }
 /// ```
 ///
+/// 12. With the `a11y` feature, the view is checked at compile time for common accessibility
+///     issues: images without `alt` text, form controls without a label, click or key
+///     handlers on elements that cannot be focused, unknown ARIA attributes or invalid
+///     literal ARIA values, and headings that skip a level. Each issue is reported as a
+///     deprecation warning on the offending element or attribute.
+///
 /// Here’s a simple example that shows off several of these features, put together
 /// ```rust
 /// # use leptos::prelude::*;
@@ -327,6 +334,11 @@ fn view_macro_impl(tokens: TokenStream, template: bool) -> TokenStream {
     let parser = rstml::Parser::new(config);
     let (mut nodes, errors) = parser.parse_recoverable(tokens).split_vec();
     let errors = errors.into_iter().map(|e| e.emit_as_expr_tokens());
+    let a11y = if cfg!(feature = "a11y") {
+        view::a11y::lints(&nodes)
+    } else {
+        quote! {}
+    };
     let nodes_output = view::render_view(
         &mut nodes,
         global_class.as_ref(),
@@ -342,6 +354,7 @@ fn view_macro_impl(tokens: TokenStream, template: bool) -> TokenStream {
             #[allow(unused_braces)]
             {
                 #(#errors;)*
+                #a11y
                 #nodes_output
             }
         }
@@ -723,6 +736,51 @@ fn component_macro(
     }.into()
 }
 
+/// Allows a component to be registered as a standard
+/// [custom element](https://developer.mozilla.org/en-US/docs/Web/API/Web_components/Using_custom_elements),
+/// so it can be used from plain HTML or from applications built with other frameworks.
+///
+/// This must be placed above the [`component`](macro@component) macro. It implements
+/// `leptos::web_component::CustomElement` for the component’s props type, which can then be
+/// registered with a tag name using `leptos::web_component::define`.
+///
+/// Each prop can be set with an attribute (its name in `kebab-case`) or a JavaScript property
+/// (its name in `camelCase`), except:
+/// - `Callback` and `UnsyncCallback` props, which dispatch a `CustomEvent` named after the prop
+///   without its `on_` prefix, in `kebab-case`.
+/// - a `children` prop, which renders a `<slot>` for the element’s children.
+///
+/// Props that are not signals or callbacks render the component again when they change.
+/// See the `leptos::web_component` module for details.
+///
+/// ```rust
+/// # use leptos::prelude::*;
+/// #[custom_element]
+/// #[component]
+/// fn UserBadge(
+///     /// Set with `<user-badge name="...">`, or `badge.name = "..."`.
+///     name: Signal<String>,
+///     /// Dispatches a `select` event, whose `detail` is the name.
+///     on_select: Callback<String>,
+/// ) -> impl IntoView {
+///     view! { <button on:click=move |_| on_select.run(name.get())>{name}</button> }
+/// }
+///
+/// # if false {
+/// leptos::web_component::define::<UserBadgeProps>("user-badge").unwrap();
+/// # }
+/// ```
+///
+/// Components with generic props cannot be custom elements.
+#[proc_macro_error2::proc_macro_error]
+#[proc_macro_attribute]
+pub fn custom_element(
+    args: proc_macro::TokenStream,
+    s: TokenStream,
+) -> TokenStream {
+    custom_element::custom_element_impl(args, s)
+}
+
 /// Annotates a struct so that it can be used with your Component as a `slot`.
 ///
 /// The `#[slot]` macro allows you to annotate plain Rust struct as component slots and use them
@@ -0,0 +1,384 @@
+//! Opt-in accessibility lints for the `view` macro, enabled with the `a11y` feature.
+//!
+//! Proc macros cannot emit warnings on stable Rust, so each lint is emitted as the use of a
+//! call to a deprecated function, spanned to the offending element or attribute.
+
+use leptos_hot_reload::parsing::{is_component_node, value_to_string};
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+use rstml::node::{CustomNode, Node, NodeAttribute, NodeElement};
+use std::collections::HashSet;
+use syn::spanned::Spanned;
+
+/// The ARIA attributes supported by `tachys::html::attribute::aria`.
+const ARIA_ATTRIBUTES: &[&str] = &[
+    "aria-activedescendant",
+    "aria-atomic",
+    "aria-autocomplete",
+    "aria-busy",
+    "aria-checked",
+    "aria-colcount",
+    "aria-colindex",
+    "aria-colspan",
+    "aria-controls",
+    "aria-current",
+    "aria-describedby",
+    "aria-description",
+    "aria-details",
+    "aria-disabled",
+    "aria-dropeffect",
+    "aria-errormessage",
+    "aria-expanded",
+    "aria-flowto",
+    "aria-grabbed",
+    "aria-haspopup",
+    "aria-hidden",
+    "aria-invalid",
+    "aria-keyshortcuts",
+    "aria-label",
+    "aria-labelledby",
+    "aria-live",
+    "aria-modal",
+    "aria-multiline",
+    "aria-multiselectable",
+    "aria-orientation",
+    "aria-owns",
+    "aria-placeholder",
+    "aria-posinset",
+    "aria-pressed",
+    "aria-readonly",
+    "aria-relevant",
+    "aria-required",
+    "aria-roledescription",
+    "aria-rowcount",
+    "aria-rowindex",
+    "aria-rowspan",
+    "aria-selected",
+    "aria-setsize",
+    "aria-sort",
+    "aria-valuemax",
+    "aria-valuemin",
+    "aria-valuenow",
+    "aria-valuetext",
+];
+
+/// Elements that can receive focus and keyboard input without a `role` or `tabindex`.
+const INTERACTIVE_ELEMENTS: &[&str] = &[
+    "a", "area", "audio", "button", "details", "embed", "iframe", "input",
+    "label", "object", "option", "select", "summary", "textarea", "video",
+];
+
+/// Events that a user would need a pointer or keyboard to trigger.
+const INTERACTIVE_EVENTS: &[&str] = &[
+    "click",
+    "dblclick",
+    "keydown",
+    "keypress",
+    "keyup",
+    "mousedown",
+    "mouseup",
+    "pointerdown",
+    "pointerup",
+];
+
+/// The allowed values of an ARIA attribute with a literal value.
+enum AriaValue {
+    /// One of a fixed list of tokens.
+    Token(&'static [&'static str]),
+    /// A space-separated list of tokens.
+    TokenList(&'static [&'static str]),
+    /// An integer.
+    Integer,
+    /// A number.
+    Number,
+}
+
+fn aria_value(name: &str) -> Option<AriaValue> {
+    const BOOLEAN: &[&str] = &["true", "false"];
+    const OPTIONAL_BOOLEAN: &[&str] = &["true", "false", "undefined"];
+    const TRISTATE: &[&str] = &["true", "false", "mixed", "undefined"];
+    Some(match name {
+        "aria-atomic"
+        | "aria-busy"
+        | "aria-disabled"
+        | "aria-modal"
+        | "aria-multiline"
+        | "aria-multiselectable"
+        | "aria-readonly"
+        | "aria-required" => AriaValue::Token(BOOLEAN),
+        "aria-expanded" | "aria-grabbed" | "aria-hidden" | "aria-selected" => {
+            AriaValue::Token(OPTIONAL_BOOLEAN)
+        }
+        "aria-checked" | "aria-pressed" => AriaValue::Token(TRISTATE),
+        "aria-autocomplete" => {
+            AriaValue::Token(&["inline", "list", "both", "none"])
+        }
+        "aria-current" => AriaValue::Token(&[
+            "page", "step", "location", "date", "time", "true", "false",
+        ]),
+        "aria-haspopup" => AriaValue::Token(&[
+            "false", "true", "menu", "listbox", "tree", "grid", "dialog",
+        ]),
+        "aria-invalid" => {
+            AriaValue::Token(&["grammar", "false", "spelling", "true"])
+        }
+        "aria-live" => AriaValue::Token(&["assertive", "off", "polite"]),
+        "aria-orientation" => {
+            AriaValue::Token(&["horizontal", "vertical", "undefined"])
+        }
+        "aria-sort" => {
+            AriaValue::Token(&["ascending", "descending", "none", "other"])
+        }
+        "aria-relevant" => {
+            AriaValue::TokenList(&["additions", "all", "removals", "text"])
+        }
+        "aria-dropeffect" => AriaValue::TokenList(&[
+            "copy", "execute", "link", "move", "none", "popup",
+        ]),
+        "aria-colcount" | "aria-colindex" | "aria-colspan"
+        | "aria-posinset" | "aria-rowcount" | "aria-rowindex"
+        | "aria-rowspan" | "aria-setsize" => AriaValue::Integer,
+        "aria-valuemax" | "aria-valuemin" | "aria-valuenow" => {
+            AriaValue::Number
+        }
+        _ => return None,
+    })
+}
+
+/// Checks the nodes of a `view` for common accessibility issues, returning tokens that emit a
+/// warning for each one.
+pub(crate) fn lints(nodes: &[Node<impl CustomNode>]) -> TokenStream {
+    let mut lints = Lints::default();
+    lints.visit(nodes, false);
+    lints.into_tokens()
+}
+
+#[derive(Default)]
+struct Lints {
+    warnings: Vec<(Span, String)>,
+    /// The `for` attributes of every `<label>`.
+    label_targets: HashSet<String>,
+    /// Form controls that are not inside a `<label>`, by their literal `id`.
+    unlabelled_controls: Vec<(Span, String, String)>,
+    /// The level of the last heading.
+    last_heading: Option<(u8, String)>,
+}
+
+impl Lints {
+    fn warn(&mut self, span: Span, message: String) {
+        self.warnings.push((span, message));
+    }
+
+    fn visit(&mut self, nodes: &[Node<impl CustomNode>], in_label: bool) {
+        for node in nodes {
+            match node {
+                Node::Element(el) => self.visit_element(el, in_label),
+                Node::Fragment(fragment) => {
+                    self.visit(&fragment.children, in_label)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn visit_element(
+        &mut self,
+        el: &NodeElement<impl CustomNode>,
+        in_label: bool,
+    ) {
+        if is_component_node(el) {
+            self.visit(&el.children, in_label);
+            return;
+        }
+
+        let tag = el.name().to_string();
+        let span = el.name().span();
+        let mut attrs = Vec::new();
+        let mut has_spread = false;
+        for attr in el.attributes() {
+            match attr {
+                NodeAttribute::Attribute(attr) => attrs.push((
+                    attr.key.to_string(),
+                    attr.key.span(),
+                    attr.value(),
+                )),
+                NodeAttribute::Block(_) => has_spread = true,
+            }
+        }
+        let has = |name: &str| attrs.iter().any(|(key, ..)| key == name);
+        let literal = |name: &str| {
+            attrs
+                .iter()
+                .find(|(key, ..)| key == name)
+                .and_then(|(_, _, value)| value.and_then(value_to_string))
+        };
+
+        // images
+        if tag == "img" && !has_spread && !has("alt") {
+            self.warn(
+                span,
+                "`<img>` should have an `alt` attribute describing the image, \
+                 or `alt=\"\"` if it is decorative"
+                    .into(),
+            );
+        }
+
+        // handlers on non-interactive elements
+        if !INTERACTIVE_ELEMENTS.contains(&tag.as_str())
+            && !has_spread
+            && !has("role")
+            && !has("tabindex")
+        {
+            for (key, key_span, _) in &attrs {
+                let event = key
+                    .strip_prefix("on:")
+                    .and_then(|event| event.split(':').next());
+                if let Some(event) =
+                    event.filter(|event| INTERACTIVE_EVENTS.contains(event))
+                {
+                    self.warn(
+                        *key_span,
+                        format!(
+                            "`<{tag}>` has an `on:{event}` handler but cannot \
+                             be focused or used with a keyboard; add a `role` \
+                             and `tabindex`, or use an interactive element \
+                             like `<button>`"
+                        ),
+                    );
+                }
+            }
+        }
+
+        // ARIA attributes
+        for (key, key_span, value) in &attrs {
+            if !key.starts_with("aria-") {
+                continue;
+            }
+            if !ARIA_ATTRIBUTES.contains(&key.as_str()) {
+                self.warn(
+                    *key_span,
+                    format!("`{key}` is not an ARIA attribute"),
+                );
+                continue;
+            }
+            let Some(expr) = value else { continue };
+            if let (Some(allowed), Some(value)) =
+                (aria_value(key), value_to_string(expr))
+            {
+                if let Some(expected) = invalid_aria_value(&allowed, &value) {
+                    self.warn(
+                        expr.span(),
+                        format!(
+                            "`{value}` is not a valid value for `{key}`, which \
+                             should be {expected}"
+                        ),
+                    );
+                }
+            }
+        }
+
+        // form controls
+        if tag == "label" {
+            if let Some(target) = literal("for") {
+                self.label_targets.insert(target);
+            }
+        }
+        let is_control = match tag.as_str() {
+            "select" | "textarea" => true,
+            "input" => !matches!(
+                literal("type").as_deref(),
+                Some("hidden" | "submit" | "reset" | "button" | "image")
+            ),
+            _ => false,
+        };
+        if is_control
+            && !in_label
+            && !has_spread
+            && !has("aria-label")
+            && !has("aria-labelledby")
+        {
+            if !has("id") {
+                self.warn(span, unlabelled_message(&tag));
+            } else if let Some(id) = literal("id") {
+                // a control with a dynamic id may be labelled elsewhere
+                self.unlabelled_controls.push((span, tag.clone(), id));
+            }
+        }
+
+        // headings
+        if let Some(level) = tag
+            .strip_prefix('h')
+            .and_then(|level| level.parse::<u8>().ok())
+            .filter(|level| (1..=6).contains(level))
+        {
+            if let Some((last, last_tag)) = &self.last_heading {
+                if level > last + 1 {
+                    self.warn(
+                        span,
+                        format!(
+                            "`<{tag}>` follows `<{last_tag}>`; heading levels \
+                             should only increase by one"
+                        ),
+                    );
+                }
+            }
+            self.last_heading = Some((level, tag.clone()));
+        }
+
+        self.visit(&el.children, in_label || tag == "label");
+    }
+
+    fn into_tokens(mut self) -> TokenStream {
+        for (span, tag, id) in std::mem::take(&mut self.unlabelled_controls) {
+            if !self.label_targets.contains(&id) {
+                self.warn(span, unlabelled_message(&tag));
+            }
+        }
+        self.warnings
+            .into_iter()
+            .map(|(span, message)| {
+                quote_spanned! {span=>
+                    {
+                        #[deprecated(note = #message)]
+                        fn accessibility() {}
+                        accessibility();
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+fn unlabelled_message(tag: &str) -> String {
+    format!(
+        "`<{tag}>` has no label; wrap it in a `<label>`, give it an `id` that \
+         matches a `<label for=...>`, or add an `aria-label`"
+    )
+}
+
+/// Returns a description of the expected values, if `value` is not one of them.
+fn invalid_aria_value(allowed: &AriaValue, value: &str) -> Option<String> {
+    let one_of = |tokens: &[&str]| {
+        tokens
+            .iter()
+            .map(|token| format!("`{token}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match allowed {
+        AriaValue::Token(tokens) => (!tokens.contains(&value))
+            .then(|| format!("one of {}", one_of(tokens))),
+        AriaValue::TokenList(tokens) => value
+            .split_whitespace()
+            .any(|token| !tokens.contains(&token))
+            .then(|| format!("a list of {}", one_of(tokens))),
+        AriaValue::Integer => value
+            .parse::<i64>()
+            .is_err()
+            .then(|| "an integer".to_string()),
+        AriaValue::Number => value
+            .parse::<f64>()
+            .is_err()
+            .then(|| "a number".to_string()),
+    }
+}
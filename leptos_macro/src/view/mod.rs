@@ -1,3 +1,4 @@
+pub(crate) mod a11y;
 mod component_builder;
 mod slot_helper;
 mod utils;
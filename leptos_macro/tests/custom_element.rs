@@ -0,0 +1,47 @@
+use leptos::{prelude::*, web_component::CustomElement};
+
+#[custom_element]
+#[component]
+fn Widget(
+    label: String,
+    #[prop(into)] count: Signal<i32>,
+    #[prop(optional)] step: Option<f64>,
+    #[prop(optional_no_strip)] disabled: Option<bool>,
+    #[prop(default = 5)] max_value: u8,
+    #[prop(strip_option)] title: Option<String>,
+    on_select: UnsyncCallback<String>,
+    #[prop(optional)] on_value_change: Option<Callback<i32>>,
+    children: ChildrenFn,
+) -> impl IntoView {
+    _ = label;
+    _ = count;
+    _ = step;
+    _ = disabled;
+    _ = max_value;
+    _ = title;
+    _ = on_select;
+    _ = on_value_change;
+    view! { <div>{children()}</div> }
+}
+
+#[custom_element]
+#[component]
+fn NoProps() -> impl IntoView {}
+
+#[test]
+fn observes_settable_props() {
+    assert_eq!(
+        WidgetProps::ATTRIBUTES,
+        &["label", "count", "step", "disabled", "max-value", "title"]
+    );
+    assert_eq!(
+        WidgetProps::PROPERTIES,
+        &["label", "count", "step", "disabled", "maxValue", "title"]
+    );
+    assert!(NoPropsProps::ATTRIBUTES.is_empty());
+}
+
+#[test]
+fn define_is_a_no_op_on_the_server() {
+    assert!(leptos::web_component::define::<WidgetProps>("x-widget").is_ok());
+}
@@ -7,4 +7,6 @@ fn ui() {
     #[cfg(all(feature = "nightly", rustc_nightly))]
     t.compile_fail("tests/ui/component_absolute.rs");
     t.compile_fail("tests/ui/server.rs");
+    #[cfg(feature = "a11y")]
+    t.compile_fail("tests/ui/a11y.rs");
 }
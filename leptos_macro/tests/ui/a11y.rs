@@ -0,0 +1,34 @@
+#![deny(deprecated)]
+
+use leptos::prelude::*;
+
+#[component]
+fn Labelled() -> impl IntoView {
+    view! {
+        <h1>"Profile"</h1>
+        <h2>"Details"</h2>
+        <img src="avatar.png" alt="" />
+        <label>"Name" <input type="text" /></label>
+        <label for="email">"Email"</label>
+        <input id="email" type="email" />
+        <input type="submit" />
+        <button on:click=|_| ()>"Save"</button>
+        <div role="button" tabindex="0" on:click=|_| ()>"Cancel"</div>
+        <div aria-hidden="true" aria-colcount="3"></div>
+    }
+}
+
+#[component]
+fn Unlabelled() -> impl IntoView {
+    view! {
+        <h1>"Profile"</h1>
+        <h3>"Details"</h3>
+        <img src="avatar.png" />
+        <input type="text" />
+        <input id="email" type="email" />
+        <div on:click=|_| ()>"Cancel"</div>
+        <div aria-expanded="yes" aria-colcount="three"></div>
+    }
+}
+
+fn main() {}
@@ -0,0 +1,63 @@
+use codee::{Decoder, Encoder};
+use serde::{de::DeserializeOwned, Serialize};
+use std::io;
+
+/// A binary codec that encodes values as [CBOR](https://cbor.io/) using
+/// [`ciborium`](https://docs.rs/ciborium).
+///
+/// Like the other binary codecs, its output is sent from the server to the client as a
+/// base64-encoded string.
+pub struct CborCodec;
+
+impl<T: Serialize> Encoder<T> for CborCodec {
+    type Error = ciborium::ser::Error<io::Error>;
+    type Encoded = Vec<u8>;
+
+    fn encode(val: &T) -> Result<Self::Encoded, Self::Error> {
+        let mut buf = Vec::new();
+        ciborium::into_writer(val, &mut buf)?;
+        Ok(buf)
+    }
+}
+
+impl<T: DeserializeOwned> Decoder<T> for CborCodec {
+    type Error = ciborium::de::Error<io::Error>;
+    type Encoded = [u8];
+
+    fn decode(val: &Self::Encoded) -> Result<T, Self::Error> {
+        ciborium::from_reader(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CborCodec;
+    use crate::{FromEncodedStr, IntoEncodedString};
+    use codee::{Decoder, Encoder};
+    use std::borrow::Borrow;
+
+    #[test]
+    fn round_trips_through_an_encoded_string() {
+        let value = (String::from("leptos"), vec![1u32, 2, 3], None::<f64>);
+
+        let encoded = CborCodec::encode(&value).unwrap().into_encoded_string();
+        // the bytes are sent as base64, which is safe to embed in the page
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/'));
+
+        let bytes = <[u8]>::from_encoded_str(&encoded).unwrap();
+        let decoded: (String, Vec<u32>, Option<f64>) =
+            CborCodec::decode(bytes.borrow()).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn invalid_data_is_an_error() {
+        assert!(<[u8]>::from_encoded_str("not base64!").is_err());
+
+        let bytes = <[u8]>::from_encoded_str("/w").unwrap();
+        let decoded: Result<String, _> = CborCodec::decode(bytes.borrow());
+        assert!(decoded.is_err());
+    }
+}
@@ -6,6 +6,11 @@
 mod action;
 pub use action::*;
 use std::borrow::Borrow;
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "cbor")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+pub use cbor::CborCodec;
 mod local_resource;
 pub use local_resource::*;
 mod multi_action;
@@ -14,7 +19,11 @@ mod once_resource;
 pub use once_resource::*;
 mod resource;
 pub use resource::*;
+mod resource_options;
+pub use resource_options::{ResourceOptions, Retry};
 mod shared;
+mod stream_resource;
+pub use stream_resource::*;
 
 use base64::{engine::general_purpose::STANDARD_NO_PAD, DecodeError, Engine};
 /// Re-export of the `codee` crate.
@@ -1,4 +1,4 @@
-use crate::{FromEncodedStr, IntoEncodedString};
+use crate::{FromEncodedStr, IntoEncodedString, ResourceOptions};
 #[cfg(feature = "rkyv")]
 use codee::binary::RkyvCodec;
 #[cfg(feature = "serde-wasm-bindgen")]
@@ -11,7 +11,7 @@ use codee::{
     string::{FromToStringCodec, JsonSerdeCodec},
     Decoder, Encoder,
 };
-use core::{fmt::Debug, marker::PhantomData};
+use core::{any::TypeId, fmt::Debug, marker::PhantomData};
 use futures::Future;
 use hydration_context::{SerializedDataId, SharedContext};
 use reactive_graph::{
@@ -19,11 +19,12 @@ use reactive_graph::{
         ArcAsyncDerived, ArcMemo, AsyncDerived, AsyncDerivedFuture,
         AsyncDerivedRefFuture,
     },
-    graph::{Source, ToAnySubscriber},
+    graph::{untrack, Source, ToAnySubscriber},
     owner::Owner,
     prelude::*,
     signal::{ArcRwSignal, RwSignal},
 };
+use serde::Serialize;
 use std::{
     future::{pending, IntoFuture},
     ops::{Deref, DerefMut},
@@ -202,7 +203,7 @@ pub fn in_resource_source_signal() -> bool {
 
 /// Set a static to true whilst running the given function.
 /// [`is_in_effect_scope`] will return true whilst the function is running.
-fn run_in_resource_source_signal<T>(fun: impl FnOnce() -> T) -> T {
+pub(crate) fn run_in_resource_source_signal<T>(fun: impl FnOnce() -> T) -> T {
     #[cfg(debug_assertions)]
     {
         // For the theoretical nested case, set back to initial value rather than false:
@@ -277,7 +278,9 @@ where
     /// a `Future` whose value will be serialized from the server to the client. If you are on
     /// the client, the initial value will be deserialized without re-running that async task.
     ///
-    /// If `blocking` is `true`, this is a blocking resource.
+    /// The `options` can be a `bool`, which sets whether this is a blocking resource, or a
+    /// [`ResourceOptions`], which can also set up retrying, polling, and refetching on focus or
+    /// reconnect.
     ///
     /// Blocking resources prevent any of the HTTP response from being sent until they have loaded.
     /// This is useful if you need their data to set HTML document metadata or information that
@@ -286,8 +289,7 @@ where
     pub fn new_with_options<S, Fut>(
         source: impl Fn() -> S + Send + Sync + 'static,
         fetcher: impl Fn(S) -> Fut + Send + Sync + 'static,
-        #[allow(unused)] // this is used with `feature = "ssr"`
-        blocking: bool,
+        options: impl Into<ResourceOptions<T>>,
     ) -> ArcResource<T, Ser>
     where
         S: PartialEq + Clone + Send + Sync + 'static,
@@ -300,6 +302,116 @@ where
             .map(|sc| sc.next_id())
             .unwrap_or_default();
 
+        Self::new_with_id(source, fetcher, options.into(), shared_context, id)
+    }
+
+    /// Creates a new resource with the encoding `Ser`, sharing its work with any identical
+    /// resource that has already been created while rendering the current request.
+    ///
+    /// Two resources are identical if they were created with the same `fetcher` (i.e., the same
+    /// function, or the same closure from the same place in the source code) and their `source`
+    /// functions serialize to the same value when the resource is created. On the server, the
+    /// second resource reuses the first one: the `fetcher` only runs once, and its value is only
+    /// serialized to the client once. While hydrating, the client finds the same serialized
+    /// value for both resources.
+    ///
+    /// Only the type of the `fetcher` is compared, not any variables it captures, so its result
+    /// must depend only on the value of `source`. Two resources created by the same closure
+    /// with different captured variables would otherwise share whichever result was loaded
+    /// first; pass those variables through `source` instead.
+    ///
+    /// This is useful for data like the current user, which may be loaded by several components
+    /// on the same page that do not otherwise share state. Outside of server rendering and
+    /// hydration (for example, in a client-side navigation), this behaves exactly like
+    /// [`ArcResource::new_with_options`].
+    ///
+    /// The `options` are the same as for [`ArcResource::new_with_options`].
+    ///
+    /// Note that while rendering on the server, identical resources are the same resource, so
+    /// calling [`refetch`](Self::refetch) on one of them refetches all of them, and only the
+    /// `options` of the first one apply (except that any of them can make it blocking).
+    #[track_caller]
+    pub fn new_deduped_with_options<S, F, Fut>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: F,
+        options: impl Into<ResourceOptions<T>>,
+    ) -> ArcResource<T, Ser>
+    where
+        S: Serialize + PartialEq + Clone + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+        Ser: Send + Sync + 'static,
+        F: Fn(S) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let options = options.into();
+        let shared_context = Owner::current_shared_context();
+        let key = shared_context.as_ref().and_then(|_| {
+            let value = untrack(|| run_in_resource_source_signal(&source));
+            let value = serde_json::to_string(&value).ok()?;
+            Some((TypeId::of::<(F, T, Ser)>(), value))
+        });
+
+        if let (Some(sc), Some(key)) = (&shared_context, key) {
+            if !sc.is_browser() {
+                // always take an ID, so that IDs stay in step with the client
+                let id = sc.next_id();
+                let existing = sc.memoized(&key).and_then(|existing| {
+                    existing.downcast_ref::<ArcResource<T, Ser>>().cloned()
+                });
+                if let Some(existing) = existing {
+                    if options.blocking {
+                        sc.defer_stream(Box::pin(existing.data.ready()));
+                    }
+                    return existing;
+                }
+                let resource = Self::new_with_id(
+                    source,
+                    fetcher,
+                    options,
+                    shared_context.clone(),
+                    id,
+                );
+                sc.memoize(key, Arc::new(resource.clone()));
+                return resource;
+            } else if sc.during_hydration() {
+                // the server only serialized the first of any identical resources,
+                // so every later one reads its initial value from that same ID
+                let id = sc.next_id();
+                let id = match sc.memoized(&key).and_then(|existing| {
+                    existing.downcast_ref::<SerializedDataId>().cloned()
+                }) {
+                    Some(first) => first,
+                    None => {
+                        sc.memoize(key, Arc::new(id.clone()));
+                        id
+                    }
+                };
+                return Self::new_with_id(
+                    source,
+                    fetcher,
+                    options,
+                    shared_context.clone(),
+                    id,
+                );
+            }
+        }
+
+        Self::new_with_options(source, fetcher, options)
+    }
+
+    #[track_caller]
+    fn new_with_id<S, Fut>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: impl Fn(S) -> Fut + Send + Sync + 'static,
+        options: ResourceOptions<T>,
+        shared_context: Option<Arc<dyn SharedContext + Send + Sync>>,
+        id: SerializedDataId,
+    ) -> ArcResource<T, Ser>
+    where
+        S: PartialEq + Clone + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
         let initial = initial_value::<T, Ser>(&id, shared_context.as_ref());
         let is_ready = initial.is_some();
 
@@ -308,16 +420,29 @@ where
             let refetch = refetch.clone();
             move |_| (refetch.get(), run_in_resource_source_signal(&source))
         });
+        let fetcher = Arc::new(fetcher);
         let fun = {
             let source = source.clone();
+            let retry = options.retry.clone();
             move || {
                 let (_, source) = source.get();
+                let retry = retry
+                    .clone()
+                    .map(|retry| (retry, Arc::clone(&fetcher), source.clone()));
                 let fut = fetcher(source);
                 async move {
                     if IS_SUPPRESSING_RESOURCE_LOAD.load(Ordering::Relaxed) {
                         pending().await
                     } else {
-                        fut.await
+                        let value = fut.await;
+                        match retry {
+                            Some((retry, fetcher, source)) => {
+                                retry
+                                    .run(value, || fetcher(source.clone()))
+                                    .await
+                            }
+                            None => value,
+                        }
                     }
                 }
             }
@@ -331,12 +456,17 @@ where
             source.add_subscriber(data.to_any_subscriber());
         }
 
+        // polling and refetching on focus or reconnect only make sense in the browser
+        if shared_context.as_ref().is_none_or(|sc| sc.is_browser()) {
+            options.watch(&refetch);
+        }
+
         #[cfg(feature = "ssr")]
         if let Some(shared_context) = shared_context {
             let value = data.clone();
             let ready_fut = data.ready();
 
-            if blocking {
+            if options.blocking {
                 shared_context.defer_stream(Box::pin(data.ready()));
             }
 
@@ -398,37 +528,45 @@ where
 {
     #[cfg(feature = "hydration")]
     {
-        use std::borrow::Borrow;
-
         let shared_context = Owner::current_shared_context();
         if let Some(shared_context) = shared_context {
             let value = shared_context.read_data(id);
             if let Some(value) = value {
-                let encoded =
-                    match <Ser as Decoder<T>>::Encoded::from_encoded_str(&value)
-                    {
-                        Ok(value) => value,
-                        Err(e) => {
-                            #[cfg(feature = "tracing")]
-                            tracing::error!("couldn't deserialize: {e:?}");
-                            return None;
-                        }
-                    };
-                let encoded = encoded.borrow();
-                match Ser::decode(encoded) {
-                    Ok(value) => return Some(value),
-                    #[allow(unused)]
-                    Err(e) => {
-                        #[cfg(feature = "tracing")]
-                        tracing::error!("couldn't deserialize: {e:?}");
-                    }
-                }
+                return decode_value::<T, Ser>(&value);
             }
         }
     }
     None
 }
 
+/// Decodes a value that has been serialized from the server with the encoding `Ser`.
+pub(crate) fn decode_value<T, Ser>(value: &str) -> Option<T>
+where
+    Ser: Decoder<T>,
+    <Ser as Decoder<T>>::Error: Debug,
+    <<Ser as Decoder<T>>::Encoded as FromEncodedStr>::DecodingError: Debug,
+    <Ser as Decoder<T>>::Encoded: FromEncodedStr,
+{
+    use std::borrow::Borrow;
+
+    let encoded = match <Ser as Decoder<T>>::Encoded::from_encoded_str(value) {
+        Ok(value) => value,
+        #[allow(unused)]
+        Err(e) => {
+            #[cfg(feature = "tracing")]
+            tracing::error!("couldn't deserialize: {e:?}");
+            return None;
+        }
+    };
+    let encoded = encoded.borrow();
+    Ser::decode(encoded)
+        .inspect_err(|_e| {
+            #[cfg(feature = "tracing")]
+            tracing::error!("couldn't deserialize: {_e:?}");
+        })
+        .ok()
+}
+
 impl<T, E, Ser> ArcResource<Result<T, E>, Ser>
 where
     Ser: Encoder<Result<T, E>> + Decoder<Result<T, E>>,
@@ -511,6 +649,68 @@ where
     {
         ArcResource::new_with_options(source, fetcher, true)
     }
+
+    /// Creates a new resource with the encoding [`JsonSerdeCodec`], sharing its work with any
+    /// identical resource created while rendering the same request.
+    ///
+    /// See [`ArcResource::new_deduped_with_options`] for details.
+    #[track_caller]
+    pub fn new_deduped<S, F, Fut>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: F,
+    ) -> Self
+    where
+        S: Serialize + PartialEq + Clone + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+        F: Fn(S) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        ArcResource::new_deduped_with_options(source, fetcher, false)
+    }
+}
+
+impl<T> ArcResource<T> {
+    /// Creates a new resource that uses the codec `C` to send its value from the server to the
+    /// client.
+    ///
+    /// `C` can be any [`codee`] codec that encodes to and decodes from a string, like
+    /// [`JsonSerdeCodec`], or to and from bytes, like `PostcardCodec` or `CborCodec`. Binary
+    /// data is sent to the client as a base64-encoded string.
+    ///
+    /// This is equivalent to [`ArcResource::new_with_options`] for a `ArcResource<T, C>`, and takes the
+    /// same `options`.
+    ///
+    /// ```rust
+    /// # use leptos_server::{codee::string::FromToStringCodec, ArcResource};
+    /// # async fn load_count(_: ()) -> u32 { 0 }
+    /// # fn component() {
+    /// // with the `postcard` feature, this could be `codee::binary::PostcardCodec`
+    /// let count = ArcResource::new_with_codec::<FromToStringCodec, _, _>(
+    ///     || (),
+    ///     load_count,
+    ///     false,
+    /// );
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn new_with_codec<C, S, Fut>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: impl Fn(S) -> Fut + Send + Sync + 'static,
+        options: impl Into<ResourceOptions<T>>,
+    ) -> ArcResource<T, C>
+    where
+        C: Encoder<T> + Decoder<T>,
+        <C as Encoder<T>>::Error: Debug,
+        <C as Decoder<T>>::Error: Debug,
+        <<C as Decoder<T>>::Encoded as FromEncodedStr>::DecodingError: Debug,
+        <C as Encoder<T>>::Encoded: IntoEncodedString,
+        <C as Decoder<T>>::Encoded: FromEncodedStr,
+        S: PartialEq + Clone + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        ArcResource::new_with_options(source, fetcher, options)
+    }
 }
 
 impl<T> ArcResource<T, FromToStringCodec>
@@ -1025,6 +1225,53 @@ where
     }
 }
 
+impl<T> Resource<T>
+where
+    T: Send + Sync,
+{
+    /// Creates a new resource that uses the codec `C` to send its value from the server to the
+    /// client.
+    ///
+    /// `C` can be any [`codee`] codec that encodes to and decodes from a string, like
+    /// [`JsonSerdeCodec`], or to and from bytes, like `PostcardCodec` or `CborCodec`. Binary
+    /// data is sent to the client as a base64-encoded string.
+    ///
+    /// This is equivalent to [`Resource::new_with_options`] for a `Resource<T, C>`, and takes the
+    /// same `options`.
+    ///
+    /// ```rust
+    /// # use leptos_server::{codee::string::FromToStringCodec, Resource};
+    /// # async fn load_count(_: ()) -> u32 { 0 }
+    /// # fn component() {
+    /// // with the `postcard` feature, this could be `codee::binary::PostcardCodec`
+    /// let count = Resource::new_with_codec::<FromToStringCodec, _, _>(
+    ///     || (),
+    ///     load_count,
+    ///     false,
+    /// );
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn new_with_codec<C, S, Fut>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: impl Fn(S) -> Fut + Send + Sync + 'static,
+        options: impl Into<ResourceOptions<T>>,
+    ) -> Resource<T, C>
+    where
+        C: Encoder<T> + Decoder<T>,
+        <C as Encoder<T>>::Error: Debug,
+        <C as Decoder<T>>::Error: Debug,
+        <<C as Decoder<T>>::Encoded as FromEncodedStr>::DecodingError: Debug,
+        <C as Encoder<T>>::Encoded: IntoEncodedString,
+        <C as Decoder<T>>::Encoded: FromEncodedStr,
+        S: PartialEq + Clone + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        Resource::new_with_options(source, fetcher, options)
+    }
+}
+
 impl<T> Resource<T, JsonSerdeCodec>
 where
     JsonSerdeCodec: Encoder<T> + Decoder<T>,
@@ -1083,6 +1330,24 @@ where
     {
         Resource::new_with_options(source, fetcher, true)
     }
+
+    /// Creates a new resource with the encoding [`JsonSerdeCodec`], sharing its work with any
+    /// identical resource created while rendering the same request.
+    ///
+    /// See [`Resource::new_deduped_with_options`] for details.
+    #[track_caller]
+    pub fn new_deduped<S, F, Fut>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: F,
+    ) -> Self
+    where
+        S: Serialize + PartialEq + Clone + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+        F: Fn(S) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        Resource::new_deduped_with_options(source, fetcher, false)
+    }
 }
 
 #[cfg(feature = "serde-wasm-bindgen")]
@@ -1342,7 +1607,9 @@ where
     /// a `Future` whose value will be serialized from the server to the client. If you are on
     /// the client, the initial value will be deserialized without re-running that async task.
     ///
-    /// If `blocking` is `true`, this is a blocking resource.
+    /// The `options` can be a `bool`, which sets whether this is a blocking resource, or a
+    /// [`ResourceOptions`], which can also set up retrying, polling, and refetching on focus or
+    /// reconnect.
     ///
     /// Blocking resources prevent any of the HTTP response from being sent until they have loaded.
     /// This is useful if you need their data to set HTML document metadata or information that
@@ -1351,7 +1618,7 @@ where
     pub fn new_with_options<S, Fut>(
         source: impl Fn() -> S + Send + Sync + 'static,
         fetcher: impl Fn(S) -> Fut + Send + Sync + 'static,
-        blocking: bool,
+        options: impl Into<ResourceOptions<T>>,
     ) -> Resource<T, Ser>
     where
         S: Send + Sync + Clone + PartialEq + 'static,
@@ -1359,7 +1626,35 @@ where
         Fut: Future<Output = T> + Send + 'static,
     {
         let ArcResource { data, refetch, .. }: ArcResource<T, Ser> =
-            ArcResource::new_with_options(source, fetcher, blocking);
+            ArcResource::new_with_options(source, fetcher, options);
+        Resource {
+            ser: PhantomData,
+            data: data.into(),
+            refetch: refetch.into(),
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+        }
+    }
+
+    /// Creates a new resource with the encoding `Ser`, sharing its work with any identical
+    /// resource that has already been created while rendering the current request.
+    ///
+    /// See [`ArcResource::new_deduped_with_options`] for details.
+    #[track_caller]
+    pub fn new_deduped_with_options<S, F, Fut>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: F,
+        options: impl Into<ResourceOptions<T>>,
+    ) -> Resource<T, Ser>
+    where
+        S: Serialize + Send + Sync + Clone + PartialEq + 'static,
+        T: Send + Sync + 'static,
+        Ser: Send + Sync + 'static,
+        F: Fn(S) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let ArcResource { data, refetch, .. }: ArcResource<T, Ser> =
+            ArcResource::new_deduped_with_options(source, fetcher, options);
         Resource {
             ser: PhantomData,
             data: data.into(),
@@ -0,0 +1,646 @@
+use reactive_graph::signal::ArcRwSignal;
+use std::{fmt::Debug, future::Future, sync::Arc, time::Duration};
+
+/// Options that control how an [`ArcResource`](crate::ArcResource) or
+/// [`Resource`](crate::Resource) loads its data.
+///
+/// Anywhere these options are accepted, a `bool` can be passed instead, which is the same as
+/// `ResourceOptions::new().blocking(value)`.
+///
+/// ```rust
+/// # use leptos_server::{ArcResource, ResourceOptions, Retry};
+/// # use std::time::Duration;
+/// # async fn load_stats(_: ()) -> Result<u32, String> { Ok(0) }
+/// # fn component() {
+/// let stats: ArcResource<_> = ArcResource::new_with_options(
+///     || (),
+///     load_stats,
+///     ResourceOptions::new()
+///         // try up to 3 more times if the fetcher returns `Err(_)`
+///         .retry(Retry::new(3).backoff(Duration::from_millis(250), 2))
+///         // reload every 30 seconds, unless the page is hidden
+///         .poll(Duration::from_secs(30))
+///         .refetch_on_focus(true)
+///         .refetch_on_reconnect(true),
+/// );
+/// # }
+/// ```
+///
+/// Retrying applies to every run of the fetcher, including on the server. Polling and refetching
+/// on focus or reconnect only apply in the browser, and do nothing when rendering on the server.
+/// None of these cause a value that was serialized from the server to be loaded again while
+/// hydrating: polling waits for a full interval before its first reload.
+pub struct ResourceOptions<T> {
+    pub(crate) blocking: bool,
+    pub(crate) retry: Option<RetryPolicy<T>>,
+    pub(crate) poll: Option<Duration>,
+    pub(crate) pause_when_hidden: bool,
+    pub(crate) refetch_on_focus: bool,
+    pub(crate) refetch_on_reconnect: bool,
+}
+
+impl<T> Debug for ResourceOptions<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceOptions")
+            .field("blocking", &self.blocking)
+            .field("retry", &self.retry.as_ref().map(|retry| &retry.retry))
+            .field("poll", &self.poll)
+            .field("pause_when_hidden", &self.pause_when_hidden)
+            .field("refetch_on_focus", &self.refetch_on_focus)
+            .field("refetch_on_reconnect", &self.refetch_on_reconnect)
+            .finish()
+    }
+}
+
+impl<T> Clone for ResourceOptions<T> {
+    fn clone(&self) -> Self {
+        Self {
+            blocking: self.blocking,
+            retry: self.retry.clone(),
+            poll: self.poll,
+            pause_when_hidden: self.pause_when_hidden,
+            refetch_on_focus: self.refetch_on_focus,
+            refetch_on_reconnect: self.refetch_on_reconnect,
+        }
+    }
+}
+
+impl<T> Default for ResourceOptions<T> {
+    fn default() -> Self {
+        Self {
+            blocking: false,
+            retry: None,
+            poll: None,
+            pause_when_hidden: true,
+            refetch_on_focus: false,
+            refetch_on_reconnect: false,
+        }
+    }
+}
+
+impl<T> From<bool> for ResourceOptions<T> {
+    fn from(blocking: bool) -> Self {
+        Self::new().blocking(blocking)
+    }
+}
+
+impl<T> ResourceOptions<T> {
+    /// Creates the default options: a non-blocking resource that loads whenever its source
+    /// changes, and never otherwise.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether this is a blocking resource.
+    ///
+    /// Blocking resources prevent any of the HTTP response from being sent until they have loaded.
+    /// This is useful if you need their data to set HTML document metadata or information that
+    /// needs to appear in HTTP headers.
+    pub fn blocking(mut self, blocking: bool) -> Self {
+        self.blocking = blocking;
+        self
+    }
+
+    /// Runs the fetcher again, following the given [`Retry`] policy, whenever the value it loads
+    /// matches `should_retry`.
+    ///
+    /// The resource keeps loading while it retries, and only takes the last value that was
+    /// loaded.
+    pub fn retry_if(
+        mut self,
+        retry: Retry,
+        should_retry: impl Fn(&T) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retry = Some(RetryPolicy {
+            retry,
+            should_retry: Arc::new(should_retry),
+        });
+        self
+    }
+
+    /// In the browser, reloads the resource every `interval`.
+    ///
+    /// By default, polling is paused while the page is hidden; see
+    /// [`pause_when_hidden`](Self::pause_when_hidden).
+    ///
+    /// If neither the `browser` nor the `ssr` feature is enabled, there is no timer to poll with,
+    /// so creating a resource with this option logs a warning and does not poll.
+    pub fn poll(mut self, interval: Duration) -> Self {
+        self.poll = Some(interval);
+        self
+    }
+
+    /// Sets whether polling skips reloading while the page is hidden. Defaults to `true`.
+    pub fn pause_when_hidden(mut self, pause: bool) -> Self {
+        self.pause_when_hidden = pause;
+        self
+    }
+
+    /// In the browser, reloads the resource whenever the window regains focus.
+    pub fn refetch_on_focus(mut self, refetch: bool) -> Self {
+        self.refetch_on_focus = refetch;
+        self
+    }
+
+    /// In the browser, reloads the resource whenever the network connection comes back online.
+    pub fn refetch_on_reconnect(mut self, refetch: bool) -> Self {
+        self.refetch_on_reconnect = refetch;
+        self
+    }
+}
+
+impl<T> ResourceOptions<T> {
+    /// Starts polling and listening for focus and reconnect events, each of which increments
+    /// `refetch`, until the current owner is cleaned up.
+    #[allow(unused_variables)] // `refetch` is only used in the browser
+    pub(crate) fn watch(&self, refetch: &ArcRwSignal<usize>) {
+        #[cfg(not(any(feature = "browser", feature = "ssr")))]
+        if self.poll.is_some() {
+            reactive_graph::log_warning(format_args!(
+                "A resource will not poll, because polling requires the \
+                 `browser` feature of `leptos_server`, which is enabled by \
+                 the `csr` and `hydrate` features of `leptos`."
+            ));
+        }
+
+        #[cfg(all(feature = "browser", target_family = "wasm"))]
+        {
+            use reactive_graph::{owner::Owner, traits::Update};
+            use std::sync::atomic::{AtomicBool, Ordering};
+
+            if let Some(interval) = self.poll {
+                let stopped = Arc::new(AtomicBool::new(false));
+                Owner::on_cleanup({
+                    let stopped = Arc::clone(&stopped);
+                    move || stopped.store(true, Ordering::Relaxed)
+                });
+                let refetch = refetch.clone();
+                let pause_when_hidden = self.pause_when_hidden;
+                reactive_graph::spawn(async move {
+                    while let Some(delay) = sleep(interval) {
+                        delay.await;
+                        if stopped.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        if !(pause_when_hidden && browser::is_hidden()) {
+                            refetch.update(|n| *n += 1);
+                        }
+                    }
+                });
+            }
+
+            let events = [
+                ("focus", self.refetch_on_focus),
+                ("online", self.refetch_on_reconnect),
+            ];
+            let listeners = events
+                .into_iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(event, _)| {
+                    let refetch = refetch.clone();
+                    browser::GlobalListener::new(event, move || {
+                        refetch.update(|n| *n += 1)
+                    })
+                })
+                .collect::<Vec<_>>();
+            if !listeners.is_empty() {
+                let listeners = send_wrapper::SendWrapper::new(listeners);
+                Owner::on_cleanup(move || drop(listeners));
+            }
+        }
+    }
+}
+
+impl<T, E> ResourceOptions<Result<T, E>>
+where
+    T: 'static,
+    E: 'static,
+{
+    /// Runs the fetcher again, following the given [`Retry`] policy, whenever it returns
+    /// `Err(_)`.
+    pub fn retry(self, retry: Retry) -> Self {
+        self.retry_if(retry, Result::is_err)
+    }
+}
+
+/// How many times, and how often, a resource retries a failed load.
+///
+/// By default, each retry waits one second longer than the last one. See
+/// [`ResourceOptions::retry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retry {
+    max_retries: usize,
+    delay: Duration,
+    factor: u32,
+    max_delay: Duration,
+}
+
+impl Retry {
+    /// Retries up to `max_retries` times after the first load.
+    pub fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            delay: Duration::from_secs(1),
+            factor: 2,
+            max_delay: Duration::from_secs(30),
+        }
+    }
+
+    /// Waits `delay` before the first retry, and multiplies the wait by `factor` for each
+    /// retry after it.
+    pub fn backoff(mut self, delay: Duration, factor: u32) -> Self {
+        self.delay = delay;
+        self.factor = factor;
+        self
+    }
+
+    /// Sets the longest time to wait between two retries. Defaults to 30 seconds.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Returns how long to wait before the given retry, counting from zero.
+    pub fn delay_for(&self, retry: usize) -> Duration {
+        let factor = self
+            .factor
+            .checked_pow(retry.try_into().unwrap_or(u32::MAX))
+            .unwrap_or(u32::MAX);
+        self.delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+pub(crate) struct RetryPolicy<T> {
+    retry: Retry,
+    should_retry: Arc<dyn Fn(&T) -> bool + Send + Sync>,
+}
+
+impl<T> Clone for RetryPolicy<T> {
+    fn clone(&self) -> Self {
+        Self {
+            retry: self.retry,
+            should_retry: Arc::clone(&self.should_retry),
+        }
+    }
+}
+
+impl<T> RetryPolicy<T> {
+    /// Given the first value that was loaded, runs `load` again until its value should not be
+    /// retried or there are no retries left.
+    pub(crate) async fn run<Fut>(self, first: T, load: impl Fn() -> Fut) -> T
+    where
+        Fut: Future<Output = T>,
+    {
+        let mut value = first;
+        for retry in 0..self.retry.max_retries {
+            if !(self.should_retry)(&value) {
+                break;
+            }
+            let Some(delay) = sleep(self.retry.delay_for(retry)) else {
+                reactive_graph::log_warning(format_args!(
+                    "A resource could not retry loading, because there is no \
+                     timer to wait between retries. On `wasm` targets, this \
+                     requires the `browser` feature of `leptos_server`."
+                ));
+                break;
+            };
+            delay.await;
+            value = load().await;
+        }
+        value
+    }
+}
+
+/// Returns a future that waits for the given duration, without depending on a particular async
+/// runtime, or `None` if there is no timer to wait with (on `wasm` without the `browser`
+/// feature).
+#[cfg(not(test))]
+pub(crate) fn sleep(
+    duration: Duration,
+) -> Option<impl Future<Output = ()> + Send> {
+    #[cfg(all(feature = "browser", target_family = "wasm"))]
+    {
+        use futures::channel::oneshot;
+        use wasm_bindgen::{closure::Closure, JsValue};
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let global = js_sys::global();
+        let callback = Closure::once_into_js(move || _ = tx.send(()));
+        let set_timeout = browser::function(&global, "setTimeout")?;
+        set_timeout
+            .call2(
+                &global,
+                &callback,
+                &JsValue::from_f64(duration.as_millis() as f64),
+            )
+            .ok()?;
+        Some(async move {
+            _ = rx.await;
+        })
+    }
+    #[cfg(not(target_family = "wasm"))]
+    {
+        Some(timer::sleep(duration))
+    }
+    #[cfg(all(not(feature = "browser"), target_family = "wasm"))]
+    {
+        _ = duration;
+        None::<std::future::Ready<()>>
+    }
+}
+
+/// In unit tests, waits on the virtual clock of the test executor instead.
+#[cfg(test)]
+pub(crate) fn sleep(
+    duration: Duration,
+) -> Option<impl Future<Output = ()> + Send> {
+    Some(any_spawner::testing::sleep(duration))
+}
+
+/// A single background thread that wakes every pending [`sleep`], so that waiting neither blocks
+/// the executor nor spawns a thread for each wait.
+#[cfg(not(target_family = "wasm"))]
+mod timer {
+    use futures::channel::oneshot;
+    use or_poisoned::OrPoisoned;
+    use std::{
+        collections::BTreeMap,
+        future::Future,
+        sync::{Condvar, LazyLock, Mutex, PoisonError},
+        time::{Duration, Instant},
+    };
+
+    /// Each pending wait, by its deadline and the order in which it was added, and the order of
+    /// the next wait.
+    type Pending = (BTreeMap<(Instant, u64), oneshot::Sender<()>>, u64);
+
+    #[derive(Default)]
+    struct Timers {
+        pending: Mutex<Pending>,
+        changed: Condvar,
+    }
+
+    static TIMERS: LazyLock<&'static Timers> = LazyLock::new(|| {
+        let timers: &'static Timers = Box::leak(Box::default());
+        std::thread::Builder::new()
+            .name("leptos_server timer".into())
+            .spawn(move || timers.run())
+            .expect("couldn't spawn the timer thread");
+        timers
+    });
+
+    impl Timers {
+        fn run(&self) {
+            let mut pending = self.pending.lock().or_poisoned();
+            loop {
+                let now = Instant::now();
+                let next = pending.0.first_key_value().map(|(key, _)| *key);
+                pending = match next {
+                    Some(key) if key.0 <= now => {
+                        if let Some(tx) = pending.0.remove(&key) {
+                            _ = tx.send(());
+                        }
+                        pending
+                    }
+                    Some((deadline, _)) => {
+                        self.changed
+                            .wait_timeout(pending, deadline - now)
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .0
+                    }
+                    None => self
+                        .changed
+                        .wait(pending)
+                        .unwrap_or_else(PoisonError::into_inner),
+                };
+            }
+        }
+    }
+
+    pub fn sleep(duration: Duration) -> impl Future<Output = ()> + Send {
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut pending = TIMERS.pending.lock().or_poisoned();
+            let (timers, next) = &mut *pending;
+            timers.insert((Instant::now() + duration, *next), tx);
+            *next += 1;
+        }
+        TIMERS.changed.notify_one();
+        async move {
+            _ = rx.await;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use futures::{executor::block_on, future::join};
+        use std::time::{Duration, Instant};
+
+        #[test]
+        fn sleeps_share_one_timer() {
+            let start = Instant::now();
+            block_on(join(
+                super::sleep(Duration::from_millis(20)),
+                super::sleep(Duration::from_millis(10)),
+            ));
+            assert!(start.elapsed() >= Duration::from_millis(20));
+        }
+    }
+}
+
+#[cfg(all(feature = "browser", target_family = "wasm"))]
+mod browser {
+    use js_sys::{Function, Reflect};
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+    pub fn function(target: &JsValue, name: &str) -> Option<Function> {
+        Reflect::get(target, &JsValue::from_str(name))
+            .ok()?
+            .dyn_into()
+            .ok()
+    }
+
+    pub fn is_hidden() -> bool {
+        Reflect::get(&js_sys::global(), &JsValue::from_str("document"))
+            .and_then(|document| {
+                Reflect::get(&document, &JsValue::from_str("visibilityState"))
+            })
+            .ok()
+            .and_then(|state| state.as_string())
+            .is_some_and(|state| state == "hidden")
+    }
+
+    /// A listener for an event on the global `window`, which is removed when this is dropped.
+    pub struct GlobalListener {
+        event: &'static str,
+        callback: JsValue,
+    }
+
+    impl GlobalListener {
+        pub fn new(event: &'static str, callback: impl Fn() + 'static) -> Self {
+            let global = js_sys::global();
+            let callback = Closure::<dyn Fn()>::new(callback).into_js_value();
+            if let Some(add) = function(&global, "addEventListener") {
+                _ = add.call2(&global, &JsValue::from_str(event), &callback);
+            }
+            Self { event, callback }
+        }
+    }
+
+    impl Drop for GlobalListener {
+        fn drop(&mut self) {
+            let global = js_sys::global();
+            if let Some(remove) = function(&global, "removeEventListener") {
+                _ = remove.call2(
+                    &global,
+                    &JsValue::from_str(self.event),
+                    &self.callback,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Retry, RetryPolicy};
+    use any_spawner::{testing::*, Executor};
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    };
+
+    type Value = Result<(), usize>;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn delay_grows_by_factor_up_to_max_delay() {
+        let retry = Retry::new(10).backoff(ms(100), 3).max_delay(ms(1_000));
+        assert_eq!(retry.delay_for(0), ms(100));
+        assert_eq!(retry.delay_for(1), ms(300));
+        assert_eq!(retry.delay_for(2), ms(900));
+        assert_eq!(retry.delay_for(3), ms(1_000));
+
+        let constant = Retry::new(3).backoff(ms(250), 1);
+        assert_eq!(constant.delay_for(0), ms(250));
+        assert_eq!(constant.delay_for(2), ms(250));
+    }
+
+    #[test]
+    fn delay_does_not_overflow() {
+        let retry = Retry::new(usize::MAX);
+        assert_eq!(retry.delay_for(40), Duration::from_secs(30));
+        assert_eq!(retry.delay_for(usize::MAX), Duration::from_secs(30));
+    }
+
+    /// Runs `policy` after a failed first load, with a loader that fails until its
+    /// `succeed_on`th call, and returns the number of loads and the eventual value.
+    fn run_policy(
+        policy: RetryPolicy<Value>,
+        succeed_on: usize,
+    ) -> (Arc<AtomicUsize>, Arc<Mutex<Option<Value>>>) {
+        let loads = Arc::new(AtomicUsize::new(0));
+        let value = Arc::new(Mutex::new(None));
+        Executor::spawn({
+            let loads = Arc::clone(&loads);
+            let value = Arc::clone(&value);
+            async move {
+                let result = policy
+                    .run(Err(0), || {
+                        let n = loads.fetch_add(1, Ordering::Relaxed) + 1;
+                        async move {
+                            if n >= succeed_on {
+                                Ok(())
+                            } else {
+                                Err(n)
+                            }
+                        }
+                    })
+                    .await;
+                *value.lock().unwrap() = Some(result);
+            }
+        });
+        run_until_stalled();
+        (loads, value)
+    }
+
+    fn policy(retry: Retry) -> RetryPolicy<Value> {
+        RetryPolicy {
+            retry,
+            should_retry: Arc::new(Result::is_err),
+        }
+    }
+
+    #[test]
+    fn each_retry_waits_for_its_delay() {
+        _ = Executor::init_testing();
+        let (loads, value) =
+            run_policy(policy(Retry::new(5).backoff(ms(100), 2)), 3);
+
+        assert_eq!(loads.load(Ordering::Relaxed), 0);
+        assert_eq!(pending_timers(), 1);
+        advance(ms(99));
+        assert_eq!(loads.load(Ordering::Relaxed), 0);
+        advance(ms(1));
+        assert_eq!(loads.load(Ordering::Relaxed), 1);
+
+        advance(ms(199));
+        assert_eq!(loads.load(Ordering::Relaxed), 1);
+        advance(ms(1));
+        assert_eq!(loads.load(Ordering::Relaxed), 2);
+
+        advance(ms(400));
+        assert_eq!(loads.load(Ordering::Relaxed), 3);
+        assert_eq!(*value.lock().unwrap(), Some(Ok(())));
+
+        // a value that should not be retried stops retrying
+        assert_eq!(pending_timers(), 0);
+        assert_eq!(pending_tasks(), 0);
+    }
+
+    #[test]
+    fn retries_stop_after_max_retries() {
+        _ = Executor::init_testing();
+        let (loads, value) =
+            run_policy(policy(Retry::new(2).backoff(ms(10), 1)), usize::MAX);
+
+        advance(ms(100));
+        assert_eq!(loads.load(Ordering::Relaxed), 2);
+        assert_eq!(*value.lock().unwrap(), Some(Err(2)));
+        assert_eq!(pending_timers(), 0);
+    }
+
+    #[test]
+    fn values_that_should_not_be_retried_are_kept() {
+        _ = Executor::init_testing();
+        let policy = RetryPolicy {
+            retry: Retry::new(3),
+            should_retry: Arc::new(|_: &Value| false),
+        };
+        let (loads, value) = run_policy(policy, usize::MAX);
+
+        assert_eq!(loads.load(Ordering::Relaxed), 0);
+        assert_eq!(*value.lock().unwrap(), Some(Err(0)));
+        assert_eq!(pending_timers(), 0);
+    }
+
+    #[cfg(not(any(feature = "browser", feature = "ssr")))]
+    #[test]
+    fn polling_without_a_timer_is_skipped() {
+        use super::ResourceOptions;
+        use reactive_graph::{signal::ArcRwSignal, traits::GetUntracked};
+
+        let refetch = ArcRwSignal::new(0);
+        ResourceOptions::<()>::new().poll(ms(10)).watch(&refetch);
+        assert_eq!(refetch.get_untracked(), 0);
+    }
+}
@@ -0,0 +1,653 @@
+use crate::{
+    decode_value, initial_value, run_in_resource_source_signal, FromEncodedStr,
+    IntoEncodedString, IS_SUPPRESSING_RESOURCE_LOAD,
+};
+use codee::{string::JsonSerdeCodec, Decoder, Encoder};
+use core::{fmt::Debug, marker::PhantomData};
+use futures::{Stream, StreamExt};
+use or_poisoned::OrPoisoned;
+use reactive_graph::{
+    computed::{ArcAsyncDerived, ArcMemo, AsyncDerivedReadyFuture},
+    graph::{Source, ToAnySubscriber},
+    owner::{ArenaItem, Owner},
+    prelude::*,
+    signal::ArcRwSignal,
+    unwrap_signal,
+};
+use std::{
+    future::pending,
+    panic::Location,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+type ItemStream<T> = Pin<Box<dyn Stream<Item = T> + Send>>;
+
+/// A reference-counted resource that loads a [`Stream`] of values.
+///
+/// Like a [`Resource`](crate::Resource), this runs its `fetcher` on the server and sends the
+/// data it loads to the client. Rather than a single value, the fetcher returns a [`Stream`],
+/// and the resource holds every item the stream has yielded so far. This is useful for data
+/// that arrives a piece at a time, like the tokens of a response from a language model.
+///
+/// Reading the resource inside a `<Suspense/>` or `<Transition/>` waits for the *first* item
+/// of the stream, then renders; each later item is pushed onto the list as it arrives.
+///
+/// When rendering on the server, the HTML is rendered with the first item, and each later item
+/// is sent to the client as part of the response as soon as it is ready. While hydrating, the
+/// client hydrates with the first item, then resumes from the items that have already been sent
+/// and continues with the rest of the server's stream, rather than running the `fetcher` again.
+pub struct ArcStreamResource<T, Ser = JsonSerdeCodec> {
+    ser: PhantomData<fn() -> Ser>,
+    refetch: ArcRwSignal<usize>,
+    first: ArcAsyncDerived<()>,
+    items: ArcRwSignal<Option<Vec<T>>>,
+    complete: ArcRwSignal<bool>,
+    #[cfg(any(debug_assertions, leptos_debuginfo))]
+    defined_at: &'static Location<'static>,
+}
+
+impl<T, Ser> Debug for ArcStreamResource<T, Ser> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_struct("ArcStreamResource");
+        d.field("ser", &self.ser)
+            .field("first", &self.first)
+            .field("items", &self.items)
+            .field("complete", &self.complete);
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        d.field("defined_at", self.defined_at);
+        d.finish_non_exhaustive()
+    }
+}
+
+impl<T, Ser> Clone for ArcStreamResource<T, Ser> {
+    fn clone(&self) -> Self {
+        Self {
+            ser: self.ser,
+            refetch: self.refetch.clone(),
+            first: self.first.clone(),
+            items: self.items.clone(),
+            complete: self.complete.clone(),
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: self.defined_at,
+        }
+    }
+}
+
+impl<T, Ser> ArcStreamResource<T, Ser>
+where
+    T: Send + Sync + 'static,
+    Ser: Encoder<T> + Decoder<T>,
+    <Ser as Encoder<T>>::Error: Debug,
+    <Ser as Decoder<T>>::Error: Debug,
+    <<Ser as Decoder<T>>::Encoded as FromEncodedStr>::DecodingError: Debug,
+    <Ser as Encoder<T>>::Encoded: IntoEncodedString,
+    <Ser as Decoder<T>>::Encoded: FromEncodedStr,
+{
+    /// Creates a new streaming resource with the encoding `Ser`.
+    ///
+    /// This takes a `source` function and a `fetcher`. The resource memoizes and reactively tracks
+    /// the value returned by `source`. Whenever that value changes, it will run the `fetcher` to
+    /// generate a new [`Stream`], and replace the items it holds with the items of the new stream
+    /// once the first one has loaded.
+    ///
+    /// If `blocking` is `true`, this is a blocking resource.
+    ///
+    /// Blocking resources prevent any of the HTTP response from being sent until the first item
+    /// has loaded.
+    #[track_caller]
+    pub fn new_with_options<S, St>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: impl Fn(S) -> St + Send + Sync + 'static,
+        #[allow(unused)] // this is used with `feature = "ssr"`
+        blocking: bool,
+    ) -> Self
+    where
+        S: PartialEq + Clone + Send + Sync + 'static,
+        St: Stream<Item = T> + Send + 'static,
+    {
+        let shared_context = Owner::current_shared_context();
+        let id = shared_context
+            .as_ref()
+            .map(|sc| sc.next_id())
+            .unwrap_or_default();
+
+        // while hydrating, resume the stream the server is sending rather than running the fetcher
+        let from_server = shared_context
+            .as_ref()
+            .filter(|sc| sc.during_hydration())
+            .and_then(|sc| sc.read_stream(&id))
+            .map(|chunks| {
+                Box::pin(chunks.filter_map(|chunk| async move {
+                    decode_value::<T, Ser>(&chunk)
+                })) as ItemStream<T>
+            });
+        let initial = initial_value::<T, Ser>(&id, shared_context.as_ref());
+        let is_ready = initial.is_some();
+        // the first chunk has already been read as the initial value
+        let from_server = from_server.map(|chunks| {
+            if is_ready {
+                Box::pin(chunks.skip(1)) as ItemStream<T>
+            } else {
+                chunks
+            }
+        });
+
+        // on the server, the rest of the stream is sent to the client rather than rendered, so
+        // that the client hydrates with the same items the server rendered
+        let serialize = cfg!(feature = "ssr")
+            && shared_context
+                .as_ref()
+                .is_some_and(|sc| !sc.is_browser() && sc.get_is_hydrating());
+
+        let items = ArcRwSignal::new(initial.map(|first| vec![first]));
+        let complete = ArcRwSignal::new(false);
+        let generation = Arc::new(AtomicUsize::new(0));
+        let rest = Arc::new(Mutex::new(None::<ItemStream<T>>));
+
+        let mut from_server = from_server;
+        if is_ready {
+            if let Some(from_server) = from_server.take() {
+                drive_stream(
+                    from_server,
+                    items.clone(),
+                    complete.clone(),
+                    Arc::clone(&generation),
+                    0,
+                );
+            }
+        }
+        let from_server = Arc::new(Mutex::new(from_server));
+
+        let refetch = ArcRwSignal::new(0);
+        let source = ArcMemo::new({
+            let refetch = refetch.clone();
+            move |_| (refetch.get(), run_in_resource_source_signal(&source))
+        });
+        let fun = {
+            let source = source.clone();
+            let items = items.clone();
+            let complete = complete.clone();
+            let rest = Arc::clone(&rest);
+            move || {
+                let (_, source) = source.get();
+                let stream = from_server
+                    .lock()
+                    .or_poisoned()
+                    .take()
+                    .unwrap_or_else(|| Box::pin(fetcher(source)));
+                let generation = Arc::clone(&generation);
+                let items = items.clone();
+                let complete = complete.clone();
+                let rest = Arc::clone(&rest);
+                async move {
+                    if IS_SUPPRESSING_RESOURCE_LOAD.load(Ordering::Relaxed) {
+                        pending::<()>().await;
+                    }
+                    // this is only taken once the run starts: when the resource already has its
+                    // initial value, the first run is created but never polled, and must not
+                    // replace the stream that is being resumed
+                    let this_run =
+                        generation.fetch_add(1, Ordering::Relaxed) + 1;
+                    let mut stream = stream;
+                    let first = stream.next().await;
+                    if generation.load(Ordering::Relaxed) != this_run {
+                        return;
+                    }
+                    let ended = first.is_none();
+                    items.set(Some(first.into_iter().collect()));
+                    complete.set(ended);
+                    if ended {
+                        return;
+                    }
+                    if serialize {
+                        *rest.lock().or_poisoned() = Some(stream);
+                    } else {
+                        drive_stream(
+                            stream, items, complete, generation, this_run,
+                        );
+                    }
+                }
+            }
+        };
+
+        let first = ArcAsyncDerived::new_with_manual_dependencies(
+            is_ready.then_some(()),
+            fun,
+            &source,
+        );
+        if is_ready {
+            source.with_untracked(|_| ());
+            source.add_subscriber(first.to_any_subscriber());
+        }
+
+        #[cfg(feature = "ssr")]
+        if let Some(shared_context) = shared_context {
+            if blocking {
+                shared_context.defer_stream(Box::pin(first.ready()));
+            }
+
+            if serialize {
+                let ready = first.ready();
+                let items = items.clone();
+                let chunks = futures::stream::once(async move {
+                    ready.await;
+                    let first = items.with_untracked(|items| {
+                        items
+                            .as_ref()
+                            .and_then(|items| items.first())
+                            .and_then(encode_value::<T, Ser>)
+                    });
+                    let rest = rest.lock().or_poisoned().take();
+                    futures::stream::iter(first).chain(
+                        futures::stream::iter(rest).flatten().filter_map(
+                            |item| async move { encode_value::<T, Ser>(&item) },
+                        ),
+                    )
+                })
+                .flatten();
+                shared_context
+                    .write_stream(id, Box::pin(SyncStream::new(chunks)));
+            }
+        }
+
+        ArcStreamResource {
+            ser: PhantomData,
+            refetch,
+            first,
+            items,
+            complete,
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+        }
+    }
+}
+
+impl<T, Ser> ArcStreamResource<T, Ser>
+where
+    T: Send + Sync + 'static,
+{
+    /// Returns a `Future` that is ready when the first item of the current stream has loaded.
+    pub fn ready(&self) -> AsyncDerivedReadyFuture {
+        self.first.ready()
+    }
+
+    /// Reactively returns `true` once the current stream has ended, and every item it yielded
+    /// has been loaded.
+    pub fn is_complete(&self) -> bool {
+        self.complete.get()
+    }
+
+    /// Synchronously, reactively reads the items the resource has loaded so far and applies the
+    /// function `f` to them, if the first item has loaded.
+    #[track_caller]
+    pub fn map<U>(&self, f: impl FnOnce(&[T]) -> U) -> Option<U> {
+        self.try_with(|items| items.as_deref().map(f))?
+    }
+
+    /// Re-runs the fetcher with the current source data, starting a new stream.
+    pub fn refetch(&self) {
+        *self.refetch.write() += 1;
+    }
+}
+
+impl<T> ArcStreamResource<T, JsonSerdeCodec>
+where
+    T: Send + Sync + 'static,
+    JsonSerdeCodec: Encoder<T> + Decoder<T>,
+    <JsonSerdeCodec as Encoder<T>>::Error: Debug,
+    <JsonSerdeCodec as Decoder<T>>::Error: Debug,
+    <<JsonSerdeCodec as Decoder<T>>::Encoded as FromEncodedStr>::DecodingError:
+        Debug,
+    <JsonSerdeCodec as Encoder<T>>::Encoded: IntoEncodedString,
+    <JsonSerdeCodec as Decoder<T>>::Encoded: FromEncodedStr,
+{
+    /// Creates a new streaming resource with the encoding [`JsonSerdeCodec`].
+    ///
+    /// See [`ArcStreamResource::new_with_options`] for details.
+    #[track_caller]
+    pub fn new<S, St>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: impl Fn(S) -> St + Send + Sync + 'static,
+    ) -> Self
+    where
+        S: PartialEq + Clone + Send + Sync + 'static,
+        St: Stream<Item = T> + Send + 'static,
+    {
+        ArcStreamResource::new_with_options(source, fetcher, false)
+    }
+
+    /// Creates a new blocking streaming resource with the encoding [`JsonSerdeCodec`].
+    ///
+    /// Blocking resources prevent any of the HTTP response from being sent until the first item
+    /// has loaded.
+    #[track_caller]
+    pub fn new_blocking<S, St>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: impl Fn(S) -> St + Send + Sync + 'static,
+    ) -> Self
+    where
+        S: PartialEq + Clone + Send + Sync + 'static,
+        St: Stream<Item = T> + Send + 'static,
+    {
+        ArcStreamResource::new_with_options(source, fetcher, true)
+    }
+}
+
+impl<T, Ser> DefinedAt for ArcStreamResource<T, Ser> {
+    fn defined_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            Some(self.defined_at)
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            None
+        }
+    }
+}
+
+impl<T, Ser> IsDisposed for ArcStreamResource<T, Ser> {
+    #[inline(always)]
+    fn is_disposed(&self) -> bool {
+        false
+    }
+}
+
+impl<T, Ser> Track for ArcStreamResource<T, Ser>
+where
+    T: 'static,
+{
+    fn track(&self) {
+        self.first.track();
+        self.items.track();
+    }
+}
+
+impl<T, Ser> ReadUntracked for ArcStreamResource<T, Ser>
+where
+    T: 'static,
+{
+    type Value = <ArcRwSignal<Option<Vec<T>>> as ReadUntracked>::Value;
+
+    fn try_read_untracked(&self) -> Option<Self::Value> {
+        // registers the resource with any `Suspense` while its first item is loading
+        _ = self.first.try_read_untracked();
+        self.items.try_read_untracked()
+    }
+}
+
+/// A resource that loads a [`Stream`] of values.
+///
+/// This is an arena-allocated version of [`ArcStreamResource`]; see its documentation for
+/// details.
+pub struct StreamResource<T, Ser = JsonSerdeCodec> {
+    inner: ArenaItem<ArcStreamResource<T, Ser>>,
+    #[cfg(any(debug_assertions, leptos_debuginfo))]
+    defined_at: &'static Location<'static>,
+}
+
+impl<T, Ser> Debug for StreamResource<T, Ser> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_struct("StreamResource");
+        d.field("inner", &self.inner);
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        d.field("defined_at", self.defined_at);
+        d.finish_non_exhaustive()
+    }
+}
+
+impl<T, Ser> Clone for StreamResource<T, Ser> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, Ser> Copy for StreamResource<T, Ser> {}
+
+impl<T, Ser> From<ArcStreamResource<T, Ser>> for StreamResource<T, Ser>
+where
+    T: Send + Sync + 'static,
+    Ser: 'static,
+{
+    #[track_caller]
+    fn from(value: ArcStreamResource<T, Ser>) -> Self {
+        Self {
+            inner: ArenaItem::new(value),
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+        }
+    }
+}
+
+impl<T, Ser> StreamResource<T, Ser>
+where
+    T: Send + Sync + 'static,
+    Ser: Encoder<T> + Decoder<T> + 'static,
+    <Ser as Encoder<T>>::Error: Debug,
+    <Ser as Decoder<T>>::Error: Debug,
+    <<Ser as Decoder<T>>::Encoded as FromEncodedStr>::DecodingError: Debug,
+    <Ser as Encoder<T>>::Encoded: IntoEncodedString,
+    <Ser as Decoder<T>>::Encoded: FromEncodedStr,
+{
+    /// Creates a new streaming resource with the encoding `Ser`.
+    ///
+    /// See [`ArcStreamResource::new_with_options`] for details.
+    #[track_caller]
+    pub fn new_with_options<S, St>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: impl Fn(S) -> St + Send + Sync + 'static,
+        blocking: bool,
+    ) -> Self
+    where
+        S: PartialEq + Clone + Send + Sync + 'static,
+        St: Stream<Item = T> + Send + 'static,
+    {
+        ArcStreamResource::new_with_options(source, fetcher, blocking).into()
+    }
+}
+
+impl<T> StreamResource<T, JsonSerdeCodec>
+where
+    T: Send + Sync + 'static,
+    JsonSerdeCodec: Encoder<T> + Decoder<T>,
+    <JsonSerdeCodec as Encoder<T>>::Error: Debug,
+    <JsonSerdeCodec as Decoder<T>>::Error: Debug,
+    <<JsonSerdeCodec as Decoder<T>>::Encoded as FromEncodedStr>::DecodingError:
+        Debug,
+    <JsonSerdeCodec as Encoder<T>>::Encoded: IntoEncodedString,
+    <JsonSerdeCodec as Decoder<T>>::Encoded: FromEncodedStr,
+{
+    /// Creates a new streaming resource with the encoding [`JsonSerdeCodec`].
+    ///
+    /// See [`ArcStreamResource::new_with_options`] for details.
+    #[track_caller]
+    pub fn new<S, St>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: impl Fn(S) -> St + Send + Sync + 'static,
+    ) -> Self
+    where
+        S: PartialEq + Clone + Send + Sync + 'static,
+        St: Stream<Item = T> + Send + 'static,
+    {
+        StreamResource::new_with_options(source, fetcher, false)
+    }
+
+    /// Creates a new blocking streaming resource with the encoding [`JsonSerdeCodec`].
+    ///
+    /// Blocking resources prevent any of the HTTP response from being sent until the first item
+    /// has loaded.
+    #[track_caller]
+    pub fn new_blocking<S, St>(
+        source: impl Fn() -> S + Send + Sync + 'static,
+        fetcher: impl Fn(S) -> St + Send + Sync + 'static,
+    ) -> Self
+    where
+        S: PartialEq + Clone + Send + Sync + 'static,
+        St: Stream<Item = T> + Send + 'static,
+    {
+        StreamResource::new_with_options(source, fetcher, true)
+    }
+}
+
+impl<T, Ser> StreamResource<T, Ser>
+where
+    T: Send + Sync + 'static,
+    Ser: 'static,
+{
+    /// Returns a `Future` that is ready when the first item of the current stream has loaded.
+    pub fn ready(&self) -> AsyncDerivedReadyFuture {
+        self.inner
+            .try_with_value(|inner| inner.ready())
+            .unwrap_or_else(unwrap_signal!(self))
+    }
+
+    /// Reactively returns `true` once the current stream has ended, and every item it yielded
+    /// has been loaded.
+    pub fn is_complete(&self) -> bool {
+        self.inner
+            .try_with_value(|inner| inner.is_complete())
+            .unwrap_or_default()
+    }
+
+    /// Synchronously, reactively reads the items the resource has loaded so far and applies the
+    /// function `f` to them, if the first item has loaded.
+    #[track_caller]
+    pub fn map<U>(&self, f: impl FnOnce(&[T]) -> U) -> Option<U> {
+        self.try_with(|items| items.as_deref().map(f))?
+    }
+
+    /// Re-runs the fetcher with the current source data, starting a new stream.
+    pub fn refetch(&self) {
+        self.inner.try_with_value(|inner| inner.refetch());
+    }
+}
+
+impl<T, Ser> DefinedAt for StreamResource<T, Ser> {
+    fn defined_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            Some(self.defined_at)
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            None
+        }
+    }
+}
+
+impl<T, Ser> IsDisposed for StreamResource<T, Ser>
+where
+    T: Send + Sync + 'static,
+    Ser: 'static,
+{
+    fn is_disposed(&self) -> bool {
+        self.inner.is_disposed()
+    }
+}
+
+impl<T, Ser> Track for StreamResource<T, Ser>
+where
+    T: Send + Sync + 'static,
+    Ser: 'static,
+{
+    fn track(&self) {
+        if let Some(inner) = self.inner.try_get_value() {
+            inner.track();
+        }
+    }
+}
+
+impl<T, Ser> ReadUntracked for StreamResource<T, Ser>
+where
+    T: Send + Sync + 'static,
+    Ser: 'static,
+{
+    type Value = <ArcRwSignal<Option<Vec<T>>> as ReadUntracked>::Value;
+
+    fn try_read_untracked(&self) -> Option<Self::Value> {
+        self.inner
+            .try_with_value(|inner| inner.try_read_untracked())
+            .flatten()
+    }
+}
+
+/// Pushes each remaining item of `stream` onto `items`, until the stream ends or a newer run of
+/// the resource has started.
+fn drive_stream<T>(
+    mut stream: ItemStream<T>,
+    items: ArcRwSignal<Option<Vec<T>>>,
+    complete: ArcRwSignal<bool>,
+    generation: Arc<AtomicUsize>,
+    this_run: usize,
+) where
+    T: Send + Sync + 'static,
+{
+    reactive_graph::spawn(async move {
+        while let Some(item) = stream.next().await {
+            if generation.load(Ordering::Relaxed) != this_run {
+                return;
+            }
+            items.update(|items| items.get_or_insert_with(Vec::new).push(item));
+        }
+        if generation.load(Ordering::Relaxed) == this_run {
+            complete.set(true);
+        }
+    });
+}
+
+/// Encodes an item to be sent to the client, or logs the error and returns [`None`] if it can't
+/// be serialized, in which case the item is left out of the stream.
+#[cfg(feature = "ssr")]
+fn encode_value<T, Ser>(value: &T) -> Option<String>
+where
+    Ser: Encoder<T>,
+    <Ser as Encoder<T>>::Error: Debug,
+    <Ser as Encoder<T>>::Encoded: IntoEncodedString,
+{
+    match Ser::encode(value) {
+        Ok(encoded) => Some(encoded.into_encoded_string()),
+        Err(e) => {
+            reactive_graph::log_warning(format_args!(
+                "couldn't serialize an item of a stream resource, so it will \
+                 not be sent to the client: {e:?}"
+            ));
+            None
+        }
+    }
+}
+
+/// Wraps a [`Stream`] that is `Send` but not `Sync`, so that it can be written to the shared
+/// context.
+#[cfg(feature = "ssr")]
+struct SyncStream<St>(Mutex<Pin<Box<St>>>);
+
+#[cfg(feature = "ssr")]
+impl<St> SyncStream<St> {
+    fn new(stream: St) -> Self {
+        Self(Mutex::new(Box::pin(stream)))
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl<St> Stream for SyncStream<St>
+where
+    St: Stream,
+{
+    type Item = St::Item;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.get_mut()
+            .0
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .as_mut()
+            .poll_next(cx)
+    }
+}
@@ -0,0 +1,146 @@
+#![cfg(all(feature = "cbor", feature = "ssr", feature = "hydration"))]
+
+use any_spawner::Executor;
+use hydration_context::{
+    PinnedFuture, PinnedStream, SerializedDataId, SharedContext,
+};
+use leptos_server::{ArcResource, CborCodec};
+use reactive_graph::{owner::Owner, testing::*};
+use std::sync::{Arc, Mutex};
+use throw_error::{Error, ErrorId};
+
+type Value = (String, Vec<u32>);
+
+/// Stands in for the shared context of a page with a single resource: on the server, it keeps
+/// the serialized value of the resource, and in the browser, it hydrates the resource from it.
+#[derive(Default)]
+struct Page {
+    browser: bool,
+    data: Mutex<Option<String>>,
+    pending: Mutex<Vec<PinnedFuture<String>>>,
+}
+
+impl std::fmt::Debug for Page {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Page")
+            .field("browser", &self.browser)
+            .field("data", &self.data)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SharedContext for Page {
+    fn is_browser(&self) -> bool {
+        self.browser
+    }
+
+    fn next_id(&self) -> SerializedDataId {
+        SerializedDataId::new(0)
+    }
+
+    fn write_async(&self, _id: SerializedDataId, fut: PinnedFuture<String>) {
+        self.pending.lock().unwrap().push(fut);
+    }
+
+    fn read_data(&self, _id: &SerializedDataId) -> Option<String> {
+        self.data.lock().unwrap().clone()
+    }
+
+    fn await_data(&self, _id: &SerializedDataId) -> Option<String> {
+        None
+    }
+
+    fn pending_data(&self) -> Option<PinnedStream<String>> {
+        None
+    }
+
+    fn during_hydration(&self) -> bool {
+        self.browser
+    }
+
+    fn hydration_complete(&self) {}
+
+    fn get_is_hydrating(&self) -> bool {
+        true
+    }
+
+    fn set_is_hydrating(&self, _is_hydrating: bool) {}
+
+    fn take_errors(&self) -> Vec<(SerializedDataId, ErrorId, Error)> {
+        Vec::new()
+    }
+
+    fn errors(&self, _boundary_id: &SerializedDataId) -> Vec<(ErrorId, Error)> {
+        Vec::new()
+    }
+
+    fn seal_errors(&self, _boundary_id: &SerializedDataId) {}
+
+    fn register_error(
+        &self,
+        _error_boundary: SerializedDataId,
+        _error_id: ErrorId,
+        _error: Error,
+    ) {
+    }
+
+    fn defer_stream(&self, _wait_for: PinnedFuture<()>) {}
+
+    fn await_deferred(&self) -> Option<PinnedFuture<()>> {
+        None
+    }
+
+    fn set_incomplete_chunk(&self, _id: SerializedDataId) {}
+
+    fn get_incomplete_chunk(&self, _id: &SerializedDataId) -> bool {
+        false
+    }
+}
+
+#[test]
+fn binary_codec_sends_the_value_to_the_client() {
+    _ = Executor::init_testing();
+    let value = (String::from("leptos"), vec![1, 2, 3]);
+
+    // on the server, the resource loads and serializes its value
+    let server = Arc::new(Page::default());
+    let owner = Owner::new_root(Some(server.clone()));
+    owner.set();
+    let resource = ArcResource::new_with_codec::<CborCodec, _, _>(
+        || (),
+        {
+            let value = value.clone();
+            move |_| {
+                let value = value.clone();
+                async move { value }
+            }
+        },
+        false,
+    );
+    run_until_stalled();
+    assert_eq!(resource.map(Clone::clone), Some(value.clone()));
+
+    let serialized = server.pending.lock().unwrap().pop().unwrap();
+    let serialized = block_on(serialized);
+    assert!(serialized
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/'));
+    drop(owner);
+
+    // in the browser, it is hydrated from that value, without loading again
+    let client = Arc::new(Page {
+        browser: true,
+        data: Mutex::new(Some(serialized)),
+        ..Default::default()
+    });
+    let owner = Owner::new_root(Some(client));
+    owner.set();
+    let resource = ArcResource::new_with_codec::<CborCodec, _, _>(
+        || (),
+        |_| async { unreachable!("the resource should not load again") },
+        false,
+    );
+    let hydrated: Option<Value> = resource.map(Clone::clone);
+    assert_eq!(hydrated, Some(value));
+    run_until_stalled();
+}
@@ -0,0 +1,195 @@
+#![cfg(all(feature = "ssr", feature = "hydration"))]
+
+use any_spawner::Executor;
+use hydration_context::{
+    MemoKey, PinnedFuture, PinnedStream, SerializedDataId, SharedContext,
+};
+use leptos_server::ArcResource;
+use reactive_graph::{owner::Owner, testing::*};
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use throw_error::{Error, ErrorId};
+
+/// Stands in for the shared context of a page: on the server, it keeps the serialized value of
+/// each resource by ID, and in the browser, it hydrates each resource from the value with its ID.
+#[derive(Default)]
+struct Page {
+    browser: bool,
+    next_id: AtomicUsize,
+    data: Mutex<HashMap<SerializedDataId, String>>,
+    pending: Mutex<Vec<(SerializedDataId, PinnedFuture<String>)>>,
+    reads: Mutex<Vec<SerializedDataId>>,
+    memos: Mutex<HashMap<MemoKey, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl std::fmt::Debug for Page {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Page")
+            .field("browser", &self.browser)
+            .field("data", &self.data)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SharedContext for Page {
+    fn is_browser(&self) -> bool {
+        self.browser
+    }
+
+    fn next_id(&self) -> SerializedDataId {
+        SerializedDataId::new(self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn write_async(&self, id: SerializedDataId, fut: PinnedFuture<String>) {
+        self.pending.lock().unwrap().push((id, fut));
+    }
+
+    fn read_data(&self, id: &SerializedDataId) -> Option<String> {
+        self.reads.lock().unwrap().push(id.clone());
+        self.data.lock().unwrap().get(id).cloned()
+    }
+
+    fn await_data(&self, _id: &SerializedDataId) -> Option<String> {
+        None
+    }
+
+    fn pending_data(&self) -> Option<PinnedStream<String>> {
+        None
+    }
+
+    fn during_hydration(&self) -> bool {
+        self.browser
+    }
+
+    fn hydration_complete(&self) {}
+
+    fn get_is_hydrating(&self) -> bool {
+        true
+    }
+
+    fn set_is_hydrating(&self, _is_hydrating: bool) {}
+
+    fn take_errors(&self) -> Vec<(SerializedDataId, ErrorId, Error)> {
+        Vec::new()
+    }
+
+    fn errors(&self, _boundary_id: &SerializedDataId) -> Vec<(ErrorId, Error)> {
+        Vec::new()
+    }
+
+    fn seal_errors(&self, _boundary_id: &SerializedDataId) {}
+
+    fn register_error(
+        &self,
+        _error_boundary: SerializedDataId,
+        _error_id: ErrorId,
+        _error: Error,
+    ) {
+    }
+
+    fn defer_stream(&self, _wait_for: PinnedFuture<()>) {}
+
+    fn await_deferred(&self) -> Option<PinnedFuture<()>> {
+        None
+    }
+
+    fn set_incomplete_chunk(&self, _id: SerializedDataId) {}
+
+    fn get_incomplete_chunk(&self, _id: &SerializedDataId) -> bool {
+        false
+    }
+
+    fn memoized(&self, key: &MemoKey) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.memos.lock().unwrap().get(key).cloned()
+    }
+
+    fn memoize(&self, key: MemoKey, value: Arc<dyn Any + Send + Sync>) {
+        self.memos.lock().unwrap().insert(key, value);
+    }
+}
+
+/// Creates the resource that several components on the page use to load the same user.
+fn user(id: u32, fetches: RunCounter) -> ArcResource<String> {
+    ArcResource::new_deduped(
+        move || id,
+        move |id| {
+            // only count fetches that run, not ones that are dropped before they are polled
+            let fetches = fetches.clone();
+            async move {
+                fetches.increment();
+                format!("user {id}")
+            }
+        },
+    )
+}
+
+#[test]
+fn identical_resources_fetch_once_on_the_server() {
+    _ = Executor::init_testing();
+    let server = Arc::new(Page::default());
+    let owner = Owner::new_root(Some(server.clone()));
+    owner.set();
+
+    let fetches = RunCounter::new();
+    let first = user(1, fetches.clone());
+    let second = user(1, fetches.clone());
+    let other = user(2, fetches.clone());
+    run_until_stalled();
+
+    // the second resource reuses the first, and only a different source loads again
+    fetches.assert_runs(2);
+    assert_eq!(first.map(Clone::clone), Some("user 1".to_string()));
+    assert_eq!(second.map(Clone::clone), Some("user 1".to_string()));
+    assert_eq!(other.map(Clone::clone), Some("user 2".to_string()));
+
+    // the shared value is only serialized once, but every resource took an ID
+    let ids = server
+        .pending
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(id, _)| id.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [SerializedDataId::new(0), SerializedDataId::new(2)]);
+}
+
+#[test]
+fn hydrating_duplicates_read_the_first_resources_value() {
+    _ = Executor::init_testing();
+    let client = Arc::new(Page {
+        browser: true,
+        data: Mutex::new(HashMap::from([
+            (SerializedDataId::new(0), r#""user 1""#.to_string()),
+            (SerializedDataId::new(2), r#""user 2""#.to_string()),
+        ])),
+        ..Default::default()
+    });
+    let owner = Owner::new_root(Some(client.clone()));
+    owner.set();
+
+    let fetches = RunCounter::new();
+    let first = user(1, fetches.clone());
+    let second = user(1, fetches.clone());
+    let other = user(2, fetches.clone());
+    run_until_stalled();
+
+    // the duplicate is hydrated from the ID of the first resource, without loading again
+    fetches.assert_runs(0);
+    assert_eq!(
+        *client.reads.lock().unwrap(),
+        [
+            SerializedDataId::new(0),
+            SerializedDataId::new(0),
+            SerializedDataId::new(2)
+        ]
+    );
+    assert_eq!(first.map(Clone::clone), Some("user 1".to_string()));
+    assert_eq!(second.map(Clone::clone), Some("user 1".to_string()));
+    assert_eq!(other.map(Clone::clone), Some("user 2".to_string()));
+}
@@ -0,0 +1,234 @@
+use any_spawner::Executor;
+use futures::channel::mpsc;
+use leptos_server::ArcStreamResource;
+use reactive_graph::{owner::Owner, testing::*};
+use std::sync::{Arc, Mutex};
+
+type Chunks = Arc<Mutex<Vec<mpsc::UnboundedReceiver<i32>>>>;
+
+/// Returns a fetcher that yields the next of `streams` each time it runs, along with the
+/// senders used to push items onto each stream.
+fn streams(
+    count: usize,
+) -> (
+    Vec<mpsc::UnboundedSender<i32>>,
+    impl Fn(()) -> mpsc::UnboundedReceiver<i32>,
+) {
+    let (senders, receivers): (Vec<_>, Vec<_>) =
+        (0..count).map(|_| mpsc::unbounded()).unzip();
+    let receivers: Chunks = Arc::new(Mutex::new(receivers));
+    let fetcher = move |_| receivers.lock().unwrap().remove(0);
+    (senders, fetcher)
+}
+
+fn items(resource: &ArcStreamResource<i32>) -> Option<Vec<i32>> {
+    resource.map(|items| items.to_vec())
+}
+
+#[test]
+fn refetch_discards_the_stale_stream() {
+    _ = Executor::init_testing();
+    let owner = Owner::new();
+    owner.set();
+
+    let (senders, fetcher) = streams(2);
+    let resource = ArcStreamResource::new(|| (), fetcher);
+    run_until_stalled();
+    assert_eq!(items(&resource), None);
+
+    senders[0].unbounded_send(1).unwrap();
+    run_until_stalled();
+    senders[0].unbounded_send(2).unwrap();
+    run_until_stalled();
+    assert_eq!(items(&resource), Some(vec![1, 2]));
+
+    // the old items are kept until the first item of the new stream has loaded
+    resource.refetch();
+    run_until_stalled();
+    assert_eq!(items(&resource), Some(vec![1, 2]));
+    senders[1].unbounded_send(10).unwrap();
+    run_until_stalled();
+    assert_eq!(items(&resource), Some(vec![10]));
+
+    // items from the first stream, and its end, are ignored once it has been replaced
+    senders[0].unbounded_send(3).unwrap();
+    senders[0].close_channel();
+    run_until_stalled();
+    assert_eq!(items(&resource), Some(vec![10]));
+    assert!(!resource.is_complete());
+
+    senders[1].unbounded_send(11).unwrap();
+    senders[1].close_channel();
+    run_until_stalled();
+    assert_eq!(items(&resource), Some(vec![10, 11]));
+    assert!(resource.is_complete());
+}
+
+#[cfg(feature = "hydration")]
+mod hydration {
+    use super::*;
+    use futures::Stream;
+    use hydration_context::{
+        PinnedFuture, PinnedStream, SerializedDataId, SharedContext,
+    };
+    use throw_error::{Error, ErrorId};
+
+    /// Stands in for the client's shared context while it hydrates a page that includes a
+    /// single stream resource, whose chunks the server is still sending.
+    #[derive(Debug)]
+    struct Hydrating {
+        /// The first chunk, if it was sent before the page was hydrated.
+        first: Option<String>,
+        chunks: Mutex<Option<mpsc::UnboundedReceiver<String>>>,
+    }
+
+    impl Hydrating {
+        fn new(
+            first: Option<&str>,
+        ) -> (Arc<Self>, mpsc::UnboundedSender<String>) {
+            let (tx, rx) = mpsc::unbounded();
+            let context = Arc::new(Self {
+                first: first.map(str::to_string),
+                chunks: Mutex::new(Some(rx)),
+            });
+            (context, tx)
+        }
+    }
+
+    impl SharedContext for Hydrating {
+        fn is_browser(&self) -> bool {
+            true
+        }
+
+        fn next_id(&self) -> SerializedDataId {
+            SerializedDataId::new(0)
+        }
+
+        fn write_async(
+            &self,
+            _id: SerializedDataId,
+            _fut: PinnedFuture<String>,
+        ) {
+        }
+
+        fn read_stream(
+            &self,
+            _id: &SerializedDataId,
+        ) -> Option<PinnedStream<String>> {
+            self.chunks
+                .lock()
+                .unwrap()
+                .take()
+                .map(|chunks| Box::pin(chunks) as PinnedStream<String>)
+        }
+
+        fn read_data(&self, _id: &SerializedDataId) -> Option<String> {
+            self.first.clone()
+        }
+
+        fn await_data(&self, _id: &SerializedDataId) -> Option<String> {
+            None
+        }
+
+        fn pending_data(&self) -> Option<PinnedStream<String>> {
+            None
+        }
+
+        fn during_hydration(&self) -> bool {
+            true
+        }
+
+        fn hydration_complete(&self) {}
+
+        fn get_is_hydrating(&self) -> bool {
+            true
+        }
+
+        fn set_is_hydrating(&self, _is_hydrating: bool) {}
+
+        fn take_errors(&self) -> Vec<(SerializedDataId, ErrorId, Error)> {
+            Vec::new()
+        }
+
+        fn errors(
+            &self,
+            _boundary_id: &SerializedDataId,
+        ) -> Vec<(ErrorId, Error)> {
+            Vec::new()
+        }
+
+        fn seal_errors(&self, _boundary_id: &SerializedDataId) {}
+
+        fn register_error(
+            &self,
+            _error_boundary: SerializedDataId,
+            _error_id: ErrorId,
+            _error: Error,
+        ) {
+        }
+
+        fn defer_stream(&self, _wait_for: PinnedFuture<()>) {}
+
+        fn await_deferred(&self) -> Option<PinnedFuture<()>> {
+            None
+        }
+
+        fn set_incomplete_chunk(&self, _id: SerializedDataId) {}
+
+        fn get_incomplete_chunk(&self, _id: &SerializedDataId) -> bool {
+            false
+        }
+    }
+
+    fn not_fetched(_: ()) -> impl Stream<Item = i32> {
+        futures::stream::once(async {
+            panic!("the fetcher's stream should not be polled while resuming")
+        })
+    }
+
+    #[test]
+    fn first_item_is_read_as_the_initial_value() {
+        _ = Executor::init_testing();
+        let (context, chunks) = Hydrating::new(Some("1"));
+        let owner = Owner::new_root(Some(context));
+        owner.set();
+
+        let resource = ArcStreamResource::new(|| (), not_fetched);
+        // the first item is available synchronously, for hydration
+        assert_eq!(items(&resource), Some(vec![1]));
+
+        // the resumed stream starts with the first chunk again, which is not pushed twice
+        for chunk in ["1", "2", "3"] {
+            chunks.unbounded_send(chunk.to_string()).unwrap();
+        }
+        run_until_stalled();
+        assert_eq!(items(&resource), Some(vec![1, 2, 3]));
+        assert!(!resource.is_complete());
+
+        chunks.close_channel();
+        run_until_stalled();
+        assert!(resource.is_complete());
+    }
+
+    #[test]
+    fn stream_is_resumed_before_its_first_item_arrives() {
+        _ = Executor::init_testing();
+        let (context, chunks) = Hydrating::new(None);
+        let owner = Owner::new_root(Some(context));
+        owner.set();
+
+        let resource = ArcStreamResource::new(|| (), not_fetched);
+        run_until_stalled();
+        assert_eq!(items(&resource), None);
+
+        chunks.unbounded_send("1".to_string()).unwrap();
+        run_until_stalled();
+        assert_eq!(items(&resource), Some(vec![1]));
+
+        chunks.unbounded_send("2".to_string()).unwrap();
+        chunks.close_channel();
+        run_until_stalled();
+        assert_eq!(items(&resource), Some(vec![1, 2]));
+        assert!(resource.is_complete());
+    }
+}
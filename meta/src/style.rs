@@ -1,11 +1,15 @@
 use crate::{register, OrDefaultNonce};
 use leptos::{
-    component, oco::Oco, prelude::*, tachys::html::element::style, IntoView,
+    component, either::Either, oco::Oco, prelude::*,
+    shadow_root::ShadowRootContext, tachys::html::element::style, IntoView,
 };
 
 /// Injects an [`HTMLStyleElement`](https://developer.mozilla.org/en-US/docs/Web/API/HTMLStyleElement) into the document
 /// head, accepting any of the valid attributes for that tag.
 ///
+/// Inside a shadow root (for example, inside a [`ShadowRoot`](leptos::shadow_root::ShadowRoot)),
+/// the style is rendered in place instead, so that it only applies inside that shadow root.
+///
 /// ```
 /// use leptos::prelude::*;
 /// use leptos_meta::*;
@@ -44,13 +48,16 @@ pub fn Style(
     #[prop(optional)]
     children: Option<Children>,
 ) -> impl IntoView {
-    register(
-        style()
-            .id(id)
-            .media(media)
-            .nonce(nonce.or_default_nonce())
-            .title(title)
-            .blocking(blocking)
-            .child(children.map(|c| c())),
-    )
+    let el = style()
+        .id(id)
+        .media(media)
+        .nonce(nonce.or_default_nonce())
+        .title(title)
+        .blocking(blocking)
+        .child(children.map(|c| c()));
+    if use_context::<ShadowRootContext>().is_some() {
+        Either::Left(el)
+    } else {
+        Either::Right(register(el))
+    }
 }
@@ -5,6 +5,7 @@ use crate::{
         ToAnySource, ToAnySubscriber,
     },
     owner::{Storage, StorageAccess, SyncStorage},
+    profiling::{NodeKind, ProfileSite},
     signal::{
         guards::{Mapped, Plain, ReadGuard},
         ArcReadSignal, ArcRwSignal,
@@ -154,13 +155,14 @@ where
     pub fn new_owning(
         fun: impl Fn(Option<T>) -> (T, bool) + Send + Sync + 'static,
     ) -> Self {
+        let site = ProfileSite::new(NodeKind::Memo);
         let inner = Arc::new_cyclic(|weak| {
             let subscriber = AnySubscriber(
                 weak.as_ptr() as usize,
                 Weak::clone(weak) as Weak<dyn Subscriber + Send + Sync>,
             );
 
-            MemoInner::new(Arc::new(fun), subscriber)
+            MemoInner::new(Arc::new(fun), subscriber, site)
         });
         Self {
             #[cfg(any(debug_assertions, leptos_debuginfo))]
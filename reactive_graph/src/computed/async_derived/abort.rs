@@ -0,0 +1,398 @@
+use crate::owner::Owner;
+use or_poisoned::OrPoisoned;
+use pin_project_lite::pin_project;
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+thread_local! {
+    static CURRENT: RefCell<Option<CurrentSignal>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone)]
+enum CurrentSignal {
+    Signal(AbortSignal),
+    Lazy(LazyAbortSignal),
+}
+
+type AbortCallback = Box<dyn FnOnce() + Send + Sync>;
+
+/// A cooperative cancellation token, modeled on the browser’s
+/// [`AbortSignal`](https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal).
+///
+/// Each run of an [`ArcAsyncDerived`](super::ArcAsyncDerived) (and therefore of any resource built
+/// on top of one) and each run of an [`Effect`](crate::effect::Effect) can ask for its own signal
+/// with [`AbortSignal::current`], both while the fetcher or effect function is running and while
+/// the `Future` it returned is being polled. The signal is aborted when that run is superseded by
+/// a newer one, or when the reactive node is disposed.
+///
+/// Aborting is opt-in: the signal is only created for runs that ask for it. If an async derived
+/// value’s future asked for its signal and is still pending when the signal is aborted, the future
+/// is dropped rather than being run to completion. Because a superseded `Future` is dropped, a
+/// server function call it was awaiting is dropped too, which cancels the underlying HTTP request.
+/// A run that did not ask for its signal is always run to completion, so an async derived value
+/// whose sources change more often than its fetcher resolves still receives values.
+///
+/// ```rust
+/// # use reactive_graph::computed::*;
+/// # use reactive_graph::signal::*;
+/// # use reactive_graph::prelude::*;
+/// # tokio_test::block_on(async move {
+/// # any_spawner::Executor::init_tokio(); let owner = reactive_graph::owner::Owner::new(); owner.set();
+/// let id = RwSignal::new(1);
+/// let user = ArcAsyncDerived::new(move || {
+///     let id = id.get();
+///     // the signal for this run is aborted if `id` changes before the request finishes
+///     let abort = AbortSignal::current().unwrap();
+///     async move {
+///         abort.on_abort(move || println!("cancelled request for user {id}"));
+///         id * 10
+///     }
+/// });
+/// assert_eq!(user.await, 10);
+/// # });
+/// ```
+#[derive(Clone, Default)]
+pub struct AbortSignal {
+    inner: Arc<AbortInner>,
+}
+
+#[derive(Default)]
+struct AbortInner {
+    aborted: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+    callbacks: Mutex<Vec<AbortCallback>>,
+}
+
+impl Debug for AbortSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AbortSignal")
+            .field("aborted", &self.is_aborted())
+            .finish()
+    }
+}
+
+impl PartialEq for AbortSignal {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for AbortSignal {}
+
+impl AbortSignal {
+    /// Creates a new signal that has not been aborted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the signal for the async derived value, resource, or effect that is currently
+    /// running or being polled, if any.
+    pub fn current() -> Option<Self> {
+        let current = CURRENT.with(|current| current.borrow().clone());
+        match current? {
+            CurrentSignal::Signal(signal) => Some(signal),
+            CurrentSignal::Lazy(lazy) => Some(lazy.signal()),
+        }
+    }
+
+    /// Whether this signal has been aborted.
+    pub fn is_aborted(&self) -> bool {
+        self.inner.aborted.load(Ordering::Acquire)
+    }
+
+    /// Aborts the signal, running any callbacks registered with [`on_abort`](Self::on_abort) and
+    /// waking any tasks waiting on [`aborted`](Self::aborted).
+    ///
+    /// Aborting a signal more than once has no further effect.
+    pub fn abort(&self) {
+        if self.inner.aborted.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let callbacks =
+            std::mem::take(&mut *self.inner.callbacks.lock().or_poisoned());
+        for callback in callbacks {
+            callback();
+        }
+        for waker in
+            std::mem::take(&mut *self.inner.wakers.lock().or_poisoned())
+        {
+            waker.wake();
+        }
+    }
+
+    /// Registers a callback that will run when the signal is aborted.
+    ///
+    /// If the signal has already been aborted, the callback runs immediately.
+    pub fn on_abort(&self, callback: impl FnOnce() + Send + Sync + 'static) {
+        if self.is_aborted() {
+            callback();
+            return;
+        }
+        self.inner
+            .callbacks
+            .lock()
+            .or_poisoned()
+            .push(Box::new(callback));
+        // the signal may have been aborted between the check above and registering the callback
+        if self.is_aborted() {
+            let callbacks =
+                std::mem::take(&mut *self.inner.callbacks.lock().or_poisoned());
+            for callback in callbacks {
+                callback();
+            }
+        }
+    }
+
+    /// Returns a [`Future`] that resolves once the signal has been aborted.
+    pub fn aborted(&self) -> Aborted {
+        Aborted {
+            signal: self.clone(),
+        }
+    }
+
+    /// Wraps a [`Future`] so that it resolves to `None` as soon as the signal is aborted, dropping
+    /// the inner `Future` without polling it again.
+    ///
+    /// While the inner `Future` is being polled, this signal is returned by
+    /// [`AbortSignal::current`].
+    pub fn wrap<Fut: Future>(&self, fut: Fut) -> Abortable<Fut> {
+        Abortable {
+            signal: self.clone(),
+            fut: Some(fut),
+        }
+    }
+
+    /// Runs the function with this signal set as the [current](AbortSignal::current) signal.
+    pub fn with_current<T>(&self, fun: impl FnOnce() -> T) -> T {
+        with_current(CurrentSignal::Signal(self.clone()), fun)
+    }
+
+    fn register_waker(&self, waker: &Waker) {
+        let mut wakers = self.inner.wakers.lock().or_poisoned();
+        if !wakers.iter().any(|existing| existing.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+}
+
+fn with_current<T>(signal: CurrentSignal, fun: impl FnOnce() -> T) -> T {
+    let prev = CURRENT.with(|current| current.replace(Some(signal)));
+    let _reset = ResetCurrent(prev);
+    fun()
+}
+
+struct ResetCurrent(Option<CurrentSignal>);
+
+impl Drop for ResetCurrent {
+    fn drop(&mut self) {
+        let prev = self.0.take();
+        CURRENT.with(|current| *current.borrow_mut() = prev);
+    }
+}
+
+/// A [`Future`] that resolves once an [`AbortSignal`] has been aborted.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Aborted {
+    signal: AbortSignal,
+}
+
+impl Future for Aborted {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.signal.is_aborted() {
+            return Poll::Ready(());
+        }
+        self.signal.register_waker(cx.waker());
+        // check again in case the signal was aborted while the waker was being registered
+        if self.signal.is_aborted() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+pin_project! {
+    /// A [`Future`] that is dropped as soon as its [`AbortSignal`] is aborted.
+    ///
+    /// Created by [`AbortSignal::wrap`].
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Abortable<Fut> {
+        signal: AbortSignal,
+        #[pin]
+        fut: Option<Fut>,
+    }
+}
+
+impl<Fut> Abortable<Fut> {
+    /// The signal that cancels this `Future`.
+    pub fn signal(&self) -> &AbortSignal {
+        &self.signal
+    }
+}
+
+impl<Fut> Future for Abortable<Fut>
+where
+    Fut: Future,
+{
+    type Output = Option<Fut::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        if this.signal.is_aborted() {
+            this.fut.set(None);
+            return Poll::Ready(None);
+        }
+        let Some(fut) = this.fut.as_mut().as_pin_mut() else {
+            return Poll::Ready(None);
+        };
+        match this.signal.with_current(|| fut.poll(cx)) {
+            Poll::Ready(value) => {
+                this.fut.set(None);
+                Poll::Ready(Some(value))
+            }
+            Poll::Pending => {
+                this.signal.register_waker(cx.waker());
+                if this.signal.is_aborted() {
+                    this.fut.set(None);
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                }
+            }
+        }
+    }
+}
+
+/// The [`AbortSignal`] for the current run of a reactive node, which is only created if the run
+/// asks for it with [`AbortSignal::current`].
+///
+/// One of these is shared by every run of a node, so that runs that never ask for their signal
+/// do not allocate one or register a cleanup for it.
+#[derive(Clone)]
+pub(crate) struct LazyAbortSignal {
+    inner: Arc<LazyInner>,
+}
+
+struct LazyInner {
+    owner: Owner,
+    signal: Mutex<Option<AbortSignal>>,
+}
+
+impl LazyAbortSignal {
+    /// Creates a lazy signal whose signals are aborted when `owner` is cleaned up.
+    pub fn new(owner: Owner) -> Self {
+        Self {
+            inner: Arc::new(LazyInner {
+                owner,
+                signal: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Returns the signal for the current run, creating it if necessary.
+    fn signal(&self) -> AbortSignal {
+        let mut signal = self.inner.signal.lock().or_poisoned();
+        signal
+            .get_or_insert_with(|| {
+                let signal = AbortSignal::new();
+                self.inner.owner.with(|| {
+                    Owner::on_cleanup({
+                        let signal = signal.clone();
+                        move || signal.abort()
+                    })
+                });
+                signal
+            })
+            .clone()
+    }
+
+    /// Whether the current run has asked for its signal.
+    pub fn is_requested(&self) -> bool {
+        self.inner.signal.lock().or_poisoned().is_some()
+    }
+
+    /// Aborts the signal for the current run, if it has been created.
+    pub fn abort(&self) {
+        let signal = self.inner.signal.lock().or_poisoned().take();
+        if let Some(signal) = signal {
+            signal.abort();
+        }
+    }
+
+    /// Starts a new run, and runs the function with this set as the current signal.
+    pub fn enter<T>(&self, fun: impl FnOnce() -> T) -> T {
+        self.abort();
+        self.with_current(fun)
+    }
+
+    /// Runs the function with this set as the current signal, without starting a new run.
+    pub fn with_current<T>(&self, fun: impl FnOnce() -> T) -> T {
+        with_current(CurrentSignal::Lazy(self.clone()), fun)
+    }
+
+    /// Wraps a [`Future`] so that this is the current signal while it is polled, and so that it
+    /// resolves to `None` once the signal for the current run has been aborted.
+    pub fn wrap<Fut: Future>(&self, fut: Fut) -> LazyAbortable<Fut> {
+        LazyAbortable {
+            signal: self.clone(),
+            fut: Some(fut),
+        }
+    }
+
+    fn is_aborted(&self) -> bool {
+        self.inner
+            .signal
+            .lock()
+            .or_poisoned()
+            .as_ref()
+            .is_some_and(AbortSignal::is_aborted)
+    }
+}
+
+pin_project! {
+    /// A [`Future`] that is dropped once the signal for the current run of a
+    /// [`LazyAbortSignal`] has been aborted.
+    #[must_use = "futures do nothing unless polled"]
+    pub(crate) struct LazyAbortable<Fut> {
+        signal: LazyAbortSignal,
+        #[pin]
+        fut: Option<Fut>,
+    }
+}
+
+impl<Fut> Future for LazyAbortable<Fut>
+where
+    Fut: Future,
+{
+    type Output = Option<Fut::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        if this.signal.is_aborted() {
+            this.fut.set(None);
+            return Poll::Ready(None);
+        }
+        let Some(fut) = this.fut.as_mut().as_pin_mut() else {
+            return Poll::Ready(None);
+        };
+        match this.signal.with_current(|| fut.poll(cx)) {
+            Poll::Ready(value) => {
+                this.fut.set(None);
+                Poll::Ready(Some(value))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use super::{
     inner::{ArcAsyncDerivedInner, AsyncDerivedState},
-    AsyncDerivedReadyFuture, ScopedFuture,
+    AsyncDerivedReadyFuture, LazyAbortSignal, ScopedFuture,
 };
 #[cfg(feature = "sandboxed-arenas")]
 use crate::owner::Sandboxed;
@@ -13,6 +13,7 @@ use crate::{
         SubscriberSet, ToAnySource, ToAnySubscriber, WithObserver,
     },
     owner::{use_context, Owner},
+    profiling::{NodeKind, ProfileSite},
     send_wrapper_ext::SendOption,
     signal::{
         guards::{AsyncPlain, Mapped, MappedMut, ReadGuard, WriteGuard},
@@ -26,7 +27,11 @@ use crate::{
 };
 use async_lock::RwLock as AsyncRwLock;
 use core::fmt::Debug;
-use futures::{channel::oneshot, FutureExt, StreamExt};
+use futures::{
+    channel::oneshot,
+    future::{self, Either},
+    FutureExt, StreamExt,
+};
 use or_poisoned::OrPoisoned;
 use std::{
     future::Future,
@@ -86,6 +91,12 @@ use std::{
 /// # });
 /// ```
 ///
+/// ## Cancellation
+/// If a source changes while the previous `Future` is still pending, that run is superseded, and
+/// the next run begins once it has finished. A run can opt into being cancelled instead by asking
+/// for its [`AbortSignal`] with [`AbortSignal::current`]: the signal is then aborted and the
+/// `Future` is dropped as soon as the run is superseded, or when the value is disposed.
+///
 /// ## Core Trait Implementations
 /// - [`.get()`](crate::traits::Get) clones the current value as an `Option<T>`.
 ///   If you call it within an effect, it will cause that effect to subscribe
@@ -223,6 +234,7 @@ impl<T> DefinedAt for ArcAsyncDerived<T> {
 macro_rules! spawn_derived {
     ($spawner:expr, $initial:ident, $fun:ident, $should_spawn:literal, $force_spawn:literal, $should_track:literal, $source:expr) => {{
         let (notifier, mut rx) = channel();
+        let site = ProfileSite::new(NodeKind::AsyncDerived);
 
         let is_ready = $initial.is_some() && !$force_spawn;
 
@@ -249,17 +261,19 @@ macro_rules! spawn_derived {
             loading: Arc::new(AtomicBool::new(!is_ready)),
         };
         let any_subscriber = this.to_any_subscriber();
+        let abort = LazyAbortSignal::new(owner.clone());
         let initial_fut = if $should_track {
             owner.with_cleanup(|| {
                 any_subscriber
-                    .with_observer(|| ScopedFuture::new($fun()))
+                    .with_observer(|| site.run(|| abort.enter(|| ScopedFuture::new($fun()))))
             })
         } else {
             owner.with_cleanup(|| {
                 any_subscriber
-                    .with_observer_untracked(|| ScopedFuture::new($fun()))
+                    .with_observer_untracked(|| site.run(|| abort.enter(|| ScopedFuture::new($fun()))))
             })
         };
+        let initial_fut = abort.wrap(initial_fut);
         #[cfg(feature = "sandboxed-arenas")]
         let initial_fut = Sandboxed::new(initial_fut);
         let mut initial_fut = Box::pin(initial_fut);
@@ -272,11 +286,11 @@ macro_rules! spawn_derived {
                 // so that the correct value is set synchronously
                 let initial = initial_fut.as_mut().now_or_never();
                 match initial {
-                    None => {
+                    None | Some(None) => {
                         inner.write().or_poisoned().notifier.notify();
                         (false, Some(initial_fut))
                     }
-                    Some(orig_value) => {
+                    Some(Some(orig_value)) => {
                         let mut guard = this.inner.write().or_poisoned();
 
                         guard.state = AsyncDerivedState::Clean;
@@ -320,11 +334,18 @@ macro_rules! spawn_derived {
                         .map(|inner| inner.state == AsyncDerivedState::Dirty)
                         .unwrap_or(false);
                     if already_dirty {
+                        abort.abort();
                         initial_fut.take();
                     }
 
-                    while rx.next().await.is_some() {
-                        let update_if_necessary = !owner.paused() && if $should_track {
+                    // whether a newer run superseded the previous one, which means
+                    // a new run should begin without waiting for another notification
+                    let mut superseded = false;
+                    loop {
+                        if !superseded && rx.next().await.is_none() {
+                            break;
+                        }
+                        let update_if_necessary = mem::take(&mut superseded) || !owner.paused() && if $should_track {
                             any_subscriber
                                 .with_observer(|| any_subscriber.update_if_necessary())
                         } else {
@@ -340,14 +361,15 @@ macro_rules! spawn_derived {
                                         let fut = if $should_track {
                                             owner.with_cleanup(|| {
                                                 any_subscriber
-                                                    .with_observer(|| ScopedFuture::new($fun()))
+                                                    .with_observer(|| site.run(|| abort.enter(|| ScopedFuture::new($fun()))))
                                             })
                                         } else {
                                             owner.with_cleanup(|| {
                                                 any_subscriber
-                                                    .with_observer_untracked(|| ScopedFuture::new($fun()))
+                                                    .with_observer_untracked(|| site.run(|| abort.enter(|| ScopedFuture::new($fun()))))
                                             })
                                         };
+                                        let fut = abort.wrap(fut);
                                         #[cfg(feature = "sandboxed-arenas")]
                                         let fut = Sandboxed::new(fut);
                                         Box::pin(fut)
@@ -377,7 +399,41 @@ macro_rules! spawn_derived {
                                         version
                                     };
 
-                                    let new_value = fut.await;
+                                    // if the sources change again before this run has finished, it is
+                                    // superseded, and the next run starts as soon as it is done. a run
+                                    // that asked for its abort signal is aborted right away instead
+                                    let mut fut = std::pin::pin!(site.instrument(fut));
+                                    let new_value = loop {
+                                        match future::select(fut.as_mut(), rx.next()).await {
+                                            Either::Left((new_value, _)) => break new_value,
+                                            Either::Right((Some(()), _)) => {
+                                                let changed = !owner.paused() && if $should_track {
+                                                    any_subscriber
+                                                        .with_observer(|| any_subscriber.update_if_necessary())
+                                                } else {
+                                                    any_subscriber
+                                                        .with_observer_untracked(|| any_subscriber.update_if_necessary())
+                                                };
+                                                if changed {
+                                                    superseded = true;
+                                                    if abort.is_requested() {
+                                                        abort.abort();
+                                                        break None;
+                                                    }
+                                                }
+                                            }
+                                            Either::Right((None, _)) => {
+                                                abort.abort();
+                                                break None;
+                                            }
+                                        }
+                                    };
+
+                                    let Some(new_value) = new_value else {
+                                        // the next run will resolve the same transition
+                                        first_run = Some(ready_tx);
+                                        continue;
+                                    };
 
                                     let latest_version = {
                                         let mut guard = inner.write().or_poisoned();
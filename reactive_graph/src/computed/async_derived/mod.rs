@@ -1,3 +1,5 @@
+mod abort;
+pub use abort::*;
 mod arc_async_derived;
 pub use arc_async_derived::*;
 #[allow(clippy::module_inception)] // not a pub mod, who cares?
@@ -4,6 +4,7 @@ use crate::{
         Source, SourceSet, Subscriber, SubscriberSet, WithObserver,
     },
     owner::{Owner, Storage, StorageAccess},
+    profiling::ProfileSite,
 };
 use or_poisoned::OrPoisoned;
 use std::{
@@ -21,6 +22,7 @@ where
     pub(crate) fun: Arc<dyn Fn(Option<T>) -> (T, bool) + Send + Sync>,
     pub(crate) owner: Owner,
     pub(crate) reactivity: RwLock<MemoInnerReactivity>,
+    pub(crate) site: ProfileSite,
 }
 
 pub(crate) struct MemoInnerReactivity {
@@ -47,6 +49,7 @@ where
     pub fn new(
         fun: Arc<dyn Fn(Option<T>) -> (T, bool) + Send + Sync>,
         any_subscriber: AnySubscriber,
+        site: ProfileSite,
     ) -> Self {
         Self {
             value: Arc::new(RwLock::new(None)),
@@ -58,6 +61,7 @@ where
                 subscribers: SubscriberSet::new(),
                 any_subscriber,
             }),
+            site,
         }
     }
 }
@@ -140,7 +144,9 @@ where
 
             let (new_value, changed) = self.owner.with_cleanup(|| {
                 any_subscriber.with_observer(|| {
-                    (self.fun)(value.map(StorageAccess::into_taken))
+                    self.site.run(|| {
+                        (self.fun)(value.map(StorageAccess::into_taken))
+                    })
                 })
             });
 
@@ -5,7 +5,7 @@ use crate::{
         guards::{Mapped, Plain, ReadGuard},
         ArcReadSignal,
     },
-    traits::{DefinedAt, Dispose, Get, ReadUntracked, Track},
+    traits::{DefinedAt, Dispose, Get, IsDisposed, ReadUntracked, Track},
     unwrap_signal,
 };
 use std::{fmt::Debug, hash::Hash, panic::Location};
@@ -287,6 +287,15 @@ where
     }
 }
 
+impl<T, S> IsDisposed for Memo<T, S>
+where
+    S: Storage<T>,
+{
+    fn is_disposed(&self) -> bool {
+        self.inner.is_disposed()
+    }
+}
+
 impl<T, S> Track for Memo<T, S>
 where
     T: 'static,
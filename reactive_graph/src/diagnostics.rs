@@ -8,6 +8,9 @@
 //! This module provides utilities to suppress those warnings by entering a
 //! [`SpecialNonReactiveZone`].
 
+#[cfg(feature = "lifetime-diagnostics")]
+pub mod lifetimes;
+
 /// Marks an execution block that is known not to be reactive, and suppresses warnings.
 #[derive(Debug)]
 pub struct SpecialNonReactiveZone;
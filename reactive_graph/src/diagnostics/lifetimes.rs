@@ -0,0 +1,475 @@
+//! Diagnostics for finding leaked owners and arena-allocated values, and for explaining accesses
+//! to values that have already been disposed.
+//!
+//! With the `lifetime-diagnostics` feature enabled, the reactive system records
+//! 1. where every [`Owner`] was created, and which owner it is a child of;
+//! 2. where every [`ArenaItem`](crate::owner::ArenaItem) (and therefore every arena-allocated
+//!    signal, memo, effect, or [`StoredValue`](crate::owner::StoredValue)) was created, and which
+//!    owner it belongs to;
+//! 3. how and where each arena item was disposed; and
+//! 4. which [`Effect`](crate::effect::Effect)s are still alive.
+//!
+//! This makes it possible to list everything that is still alive, grouped by owner and by
+//! creation site ([`arena_report`]), to find values and owners that outlived the owner that
+//! should have cleaned them up ([`leaked_arena_entries`], [`leaked_owners`],
+//! [`stray_effects`]), and to explain where a disposed value came from when it is accessed
+//! ([`last_disposed_access`]). The panic message for accessing a disposed signal includes this
+//! explanation automatically.
+//!
+//! ```
+//! # #[cfg(feature = "lifetime-diagnostics")] {
+//! use reactive_graph::{
+//!     diagnostics::lifetimes, owner::Owner, prelude::*, signal::RwSignal,
+//! };
+//!
+//! let owner = Owner::new();
+//! let count = owner.with(|| RwSignal::new(0));
+//!
+//! // the signal is stored in the arena, and owned by `owner`
+//! let report = lifetimes::arena_report();
+//! let group = report
+//!     .iter()
+//!     .find(|group| group.owner == Some(owner.debug_id()))
+//!     .unwrap();
+//! assert_eq!(group.sites.len(), 1);
+//!
+//! // cleaning up the owner disposes the signal, and records why
+//! owner.cleanup();
+//! assert_eq!(count.try_get(), None);
+//! let access = lifetimes::last_disposed_access().unwrap();
+//! assert_eq!(access.owner, Some(owner.debug_id()));
+//! assert_eq!(access.disposal, lifetimes::Disposal::OwnerCleanup);
+//! # }
+//! ```
+//!
+//! Values are tracked by their arena key, so records may be inaccurate when the
+//! `sandboxed-arenas` feature is used to create multiple arenas.
+
+use crate::owner::{NodeId, Owner, OwnerInner};
+use or_poisoned::OrPoisoned;
+use rustc_hash::FxHashMap;
+use std::{
+    any::Any,
+    cell::Cell,
+    cmp::Reverse,
+    collections::VecDeque,
+    fmt::Display,
+    panic::Location,
+    sync::{Arc, LazyLock, Mutex, RwLock, Weak},
+};
+
+/// The number of disposed values whose records are kept for [`last_disposed_access`].
+const DISPOSED_HISTORY: usize = 1024;
+
+#[derive(Default)]
+struct Registry {
+    owners: FxHashMap<usize, OwnerRecord>,
+    nodes: FxHashMap<NodeId, NodeRecord>,
+    disposed: FxHashMap<NodeId, (NodeRecord, Disposal)>,
+    disposed_order: VecDeque<NodeId>,
+    effects: Vec<EffectRecord>,
+}
+
+struct OwnerRecord {
+    inner: Weak<RwLock<OwnerInner>>,
+    created_at: &'static Location<'static>,
+    parent: Option<usize>,
+}
+
+#[derive(Clone)]
+struct NodeRecord {
+    type_name: &'static str,
+    created_at: &'static Location<'static>,
+    owner: Option<usize>,
+    owner_ancestry: Vec<usize>,
+}
+
+struct EffectRecord {
+    inner: Weak<dyn Any + Send + Sync>,
+    defined_at: &'static Location<'static>,
+    owner: Option<usize>,
+}
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(Default::default);
+
+thread_local! {
+    static LAST_DISPOSED_ACCESS: Cell<Option<NodeId>> = const { Cell::new(None) };
+}
+
+impl Registry {
+    fn owner_alive(&self, id: usize) -> bool {
+        self.owners
+            .get(&id)
+            .map(|owner| owner.inner.strong_count() > 0)
+            .unwrap_or(false)
+    }
+
+    fn prune_owners(&mut self) {
+        self.owners
+            .retain(|_, owner| owner.inner.strong_count() > 0);
+    }
+}
+
+/// How an arena-allocated value was disposed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disposal {
+    /// It was disposed when the owner it belonged to was cleaned up or dropped.
+    OwnerCleanup,
+    /// It was disposed explicitly, e.g., by calling [`Dispose::dispose`](crate::traits::Dispose).
+    Disposed(&'static Location<'static>),
+    /// Its value was taken out of the arena, e.g., by calling
+    /// [`IntoInner::into_inner`](crate::traits::IntoInner).
+    Taken(&'static Location<'static>),
+}
+
+/// A value that is currently stored in the arena.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArenaEntry {
+    /// The name of the stored type.
+    pub type_name: &'static str,
+    /// Where the value was created.
+    pub created_at: &'static Location<'static>,
+    /// The [`Owner::debug_id`] of the owner the value belongs to, if any.
+    pub owner: Option<usize>,
+    /// Whether the owner is still alive. If it is not, the value has leaked.
+    pub owner_alive: bool,
+}
+
+/// All the live arena values belonging to one owner. See [`arena_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnerGroup {
+    /// The [`Owner::debug_id`] of the owner, or `None` for values created with no owner.
+    pub owner: Option<usize>,
+    /// Where the owner was created, if it is known.
+    pub owner_created_at: Option<&'static Location<'static>>,
+    /// Whether the owner is still alive.
+    pub owner_alive: bool,
+    /// The values belonging to this owner, grouped by creation site.
+    pub sites: Vec<SiteGroup>,
+}
+
+/// All the live arena values created at one location. See [`arena_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiteGroup {
+    /// Where the values were created.
+    pub created_at: &'static Location<'static>,
+    /// The name of the stored type.
+    pub type_name: &'static str,
+    /// The number of values that are still alive.
+    pub count: usize,
+}
+
+/// An owner that is still alive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnerInfo {
+    /// The [`Owner::debug_id`] of the owner.
+    pub id: usize,
+    /// Where the owner was created.
+    pub created_at: &'static Location<'static>,
+    /// The [`Owner::debug_id`] of the owner's parent at creation, if any.
+    pub parent: Option<usize>,
+    /// Whether the parent is still alive.
+    pub parent_alive: bool,
+    /// The number of arena values that belong to this owner.
+    pub arena_entries: usize,
+}
+
+/// An effect that is still alive, even though the owner it was created under is gone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrayEffect {
+    /// Where the effect was created.
+    pub defined_at: &'static Location<'static>,
+    /// The [`Owner::debug_id`] of the owner the effect was created under, if any.
+    pub owner: Option<usize>,
+    /// The number of strong references to the effect that are keeping it alive.
+    pub strong_count: usize,
+}
+
+/// Describes a value that was accessed after it had been disposed. See [`last_disposed_access`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisposedAccess {
+    /// The name of the stored type.
+    pub type_name: &'static str,
+    /// Where the value was created.
+    pub created_at: &'static Location<'static>,
+    /// The [`Owner::debug_id`] of the owner the value belonged to, if any.
+    pub owner: Option<usize>,
+    /// The ancestors of that owner at the time the value was created, nearest first.
+    pub owner_ancestry: Vec<usize>,
+    /// How the value was disposed.
+    pub disposal: Disposal,
+}
+
+impl Display for DisposedAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The {} was created at {}",
+            self.type_name, self.created_at
+        )?;
+        match self.owner {
+            Some(owner) => {
+                write!(f, " under owner {owner:#x}")?;
+                if !self.owner_ancestry.is_empty() {
+                    f.write_str(" (ancestors: ")?;
+                    for (idx, ancestor) in
+                        self.owner_ancestry.iter().enumerate()
+                    {
+                        if idx > 0 {
+                            f.write_str(" -> ")?;
+                        }
+                        write!(f, "{ancestor:#x}")?;
+                    }
+                    f.write_str(")")?;
+                }
+            }
+            None => f.write_str(" with no owner")?,
+        }
+        match self.disposal {
+            Disposal::OwnerCleanup => f.write_str(
+                ", and was disposed when that owner was cleaned up.",
+            ),
+            Disposal::Disposed(at) => write!(f, ", and was disposed at {at}."),
+            Disposal::Taken(at) => {
+                write!(f, ", and its value was taken out at {at}.")
+            }
+        }
+    }
+}
+
+pub(crate) fn record_owner(
+    owner: &Owner,
+    created_at: &'static Location<'static>,
+) {
+    let parent = owner.parent().map(|parent| parent.debug_id());
+    let mut registry = REGISTRY.lock().or_poisoned();
+    registry.owners.insert(
+        owner.debug_id(),
+        OwnerRecord {
+            inner: Arc::downgrade(&owner.inner),
+            created_at,
+            parent,
+        },
+    );
+}
+
+pub(crate) fn record_created(
+    node: NodeId,
+    type_name: &'static str,
+    created_at: &'static Location<'static>,
+    owner: Option<&Owner>,
+) {
+    let record = NodeRecord {
+        type_name,
+        created_at,
+        owner: owner.map(Owner::debug_id),
+        owner_ancestry: owner.map(Owner::ancestry).unwrap_or_default(),
+    };
+    let mut registry = REGISTRY.lock().or_poisoned();
+    registry.disposed.remove(&node);
+    registry.nodes.insert(node, record);
+}
+
+pub(crate) fn record_disposed(
+    nodes: impl IntoIterator<Item = NodeId>,
+    disposal: Disposal,
+) {
+    let mut registry = REGISTRY.lock().or_poisoned();
+    for node in nodes {
+        if let Some(record) = registry.nodes.remove(&node) {
+            registry.disposed.insert(node, (record, disposal));
+            registry.disposed_order.push_back(node);
+            if registry.disposed_order.len() > DISPOSED_HISTORY {
+                if let Some(oldest) = registry.disposed_order.pop_front() {
+                    registry.disposed.remove(&oldest);
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn record_disposed_access(node: NodeId) {
+    LAST_DISPOSED_ACCESS.set(Some(node));
+}
+
+pub(crate) fn record_effect(
+    inner: Weak<dyn Any + Send + Sync>,
+    defined_at: &'static Location<'static>,
+) {
+    let owner = Owner::current().map(|owner| owner.debug_id());
+    let mut registry = REGISTRY.lock().or_poisoned();
+    registry
+        .effects
+        .retain(|effect| effect.inner.strong_count() > 0);
+    registry.effects.push(EffectRecord {
+        inner,
+        defined_at,
+        owner,
+    });
+}
+
+/// Returns every value that is currently stored in the arena.
+pub fn live_arena_entries() -> Vec<ArenaEntry> {
+    let registry = REGISTRY.lock().or_poisoned();
+    let mut entries = registry
+        .nodes
+        .values()
+        .map(|record| ArenaEntry {
+            type_name: record.type_name,
+            created_at: record.created_at,
+            owner: record.owner,
+            owner_alive: record
+                .owner
+                .map(|owner| registry.owner_alive(owner))
+                .unwrap_or(false),
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| {
+        (
+            entry.created_at.file(),
+            entry.created_at.line(),
+            entry.created_at.column(),
+        )
+    });
+    entries
+}
+
+/// Returns every value that is still stored in the arena, even though the owner it belonged to
+/// has been dropped.
+pub fn leaked_arena_entries() -> Vec<ArenaEntry> {
+    live_arena_entries()
+        .into_iter()
+        .filter(|entry| entry.owner.is_some() && !entry.owner_alive)
+        .collect()
+}
+
+/// Returns every value that is currently stored in the arena, grouped by owner and then by the
+/// location at which it was created.
+///
+/// Owners that have been dropped are listed first, followed by those with the most live values.
+pub fn arena_report() -> Vec<OwnerGroup> {
+    let entries = live_arena_entries();
+    let registry = REGISTRY.lock().or_poisoned();
+
+    let mut groups = FxHashMap::<Option<usize>, OwnerGroup>::default();
+    for entry in entries {
+        let group = groups.entry(entry.owner).or_insert_with(|| OwnerGroup {
+            owner: entry.owner,
+            owner_created_at: entry
+                .owner
+                .and_then(|owner| registry.owners.get(&owner))
+                .map(|owner| owner.created_at),
+            owner_alive: entry.owner_alive,
+            sites: Vec::new(),
+        });
+        match group.sites.iter_mut().find(|site| {
+            site.created_at == entry.created_at
+                && site.type_name == entry.type_name
+        }) {
+            Some(site) => site.count += 1,
+            None => group.sites.push(SiteGroup {
+                created_at: entry.created_at,
+                type_name: entry.type_name,
+                count: 1,
+            }),
+        }
+    }
+
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    for group in &mut groups {
+        group.sites.sort_by_key(|site| Reverse(site.count));
+    }
+    groups.sort_by_key(|group| {
+        let count = group.sites.iter().map(|site| site.count).sum::<usize>();
+        (group.owner_alive, Reverse(count))
+    });
+    groups
+}
+
+/// Returns every [`Owner`] that is still alive.
+pub fn live_owners() -> Vec<OwnerInfo> {
+    let mut registry = REGISTRY.lock().or_poisoned();
+    registry.prune_owners();
+    let mut owners = registry
+        .owners
+        .iter()
+        .map(|(&id, owner)| OwnerInfo {
+            id,
+            created_at: owner.created_at,
+            parent: owner.parent,
+            parent_alive: owner
+                .parent
+                .map(|parent| registry.owner_alive(parent))
+                .unwrap_or(false),
+            arena_entries: registry
+                .nodes
+                .values()
+                .filter(|node| node.owner == Some(id))
+                .count(),
+        })
+        .collect::<Vec<_>>();
+    owners.sort_by_key(|owner| {
+        (
+            owner.created_at.file(),
+            owner.created_at.line(),
+            owner.created_at.column(),
+        )
+    });
+    owners
+}
+
+/// Returns every [`Owner`] that is still alive, even though its parent has been dropped.
+///
+/// This usually means that a clone of the owner has been kept somewhere it should not have been,
+/// so it will never be cleaned up along with the rest of the tree.
+pub fn leaked_owners() -> Vec<OwnerInfo> {
+    live_owners()
+        .into_iter()
+        .filter(|owner| owner.parent.is_some() && !owner.parent_alive)
+        .collect()
+}
+
+/// Returns every [`Effect`](crate::effect::Effect) that is still alive, even though the owner it
+/// was created under has been dropped.
+pub fn stray_effects() -> Vec<StrayEffect> {
+    let mut registry = REGISTRY.lock().or_poisoned();
+    registry.prune_owners();
+    registry
+        .effects
+        .retain(|effect| effect.inner.strong_count() > 0);
+    registry
+        .effects
+        .iter()
+        .filter(|effect| {
+            effect
+                .owner
+                .map(|owner| !registry.owner_alive(owner))
+                .unwrap_or(false)
+        })
+        .map(|effect| StrayEffect {
+            defined_at: effect.defined_at,
+            owner: effect.owner,
+            strong_count: effect.inner.strong_count(),
+        })
+        .collect()
+}
+
+/// Returns a description of the most recent attempt on this thread to access an arena-allocated
+/// value that had already been disposed.
+///
+/// Returns `None` if there has been no such access, or if the value was disposed so long ago that
+/// its record has been discarded.
+pub fn last_disposed_access() -> Option<DisposedAccess> {
+    let node = LAST_DISPOSED_ACCESS.get()?;
+    let registry = REGISTRY.lock().or_poisoned();
+    registry
+        .disposed
+        .get(&node)
+        .map(|(record, disposal)| DisposedAccess {
+            type_name: record.type_name,
+            created_at: record.created_at,
+            owner: record.owner,
+            owner_ancestry: record.owner_ancestry.clone(),
+            disposal: *disposal,
+        })
+}
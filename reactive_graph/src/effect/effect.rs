@@ -1,11 +1,13 @@
 use crate::{
     channel::{channel, Receiver},
+    computed::LazyAbortSignal,
     effect::{inner::EffectInner, EffectFunction},
     graph::{
         AnySubscriber, ReactiveNode, SourceSet, Subscriber, ToAnySubscriber,
         WithObserver,
     },
     owner::{ArenaItem, LocalStorage, Owner, Storage, SyncStorage},
+    profiling::{NodeKind, ProfileSite},
     traits::Dispose,
 };
 use any_spawner::Executor;
@@ -13,6 +15,7 @@ use futures::StreamExt;
 use or_poisoned::OrPoisoned;
 use std::{
     mem,
+    panic::Location,
     sync::{atomic::AtomicBool, Arc, RwLock},
 };
 
@@ -77,6 +80,15 @@ use std::{
 ///    this with a web framework, this generally means that effects **do not run on the server**.
 ///    and you can call browser-specific APIs within the effect function without causing issues.
 ///    If you need an effect to run on the server, use [`Effect::new_isomorphic`].
+///
+/// ## Cancelling Async Work
+///
+/// Each run of an effect, including the `handler` of [`Effect::watch`], can ask for its own
+/// [`AbortSignal`](crate::computed::AbortSignal) with
+/// [`AbortSignal::current`](crate::computed::AbortSignal::current) while the effect function
+/// runs. It is aborted when the effect runs again, or when it is disposed. Wrapping a spawned task
+/// with [`AbortSignal::wrap`](crate::computed::AbortSignal::wrap) cancels it when it is no longer
+/// needed. Runs that do not ask for a signal do not create one.
 #[derive(Debug, Clone, Copy)]
 pub struct Effect<S> {
     inner: Option<ArenaItem<StoredEffect, S>>,
@@ -92,7 +104,9 @@ impl<S> Dispose for Effect<S> {
     }
 }
 
-fn effect_base() -> (Receiver, Owner, Arc<RwLock<EffectInner>>) {
+fn effect_base(
+    defined_at: &'static Location<'static>,
+) -> (Receiver, Owner, Arc<RwLock<EffectInner>>) {
     let (mut observer, rx) = channel();
 
     // spawn the effect asynchronously
@@ -107,6 +121,14 @@ fn effect_base() -> (Receiver, Owner, Arc<RwLock<EffectInner>>) {
         sources: SourceSet::new(),
     }));
 
+    #[cfg(feature = "lifetime-diagnostics")]
+    crate::diagnostics::lifetimes::record_effect(
+        Arc::downgrade(&inner) as _,
+        defined_at,
+    );
+    #[cfg(not(feature = "lifetime-diagnostics"))]
+    let _ = defined_at;
+
     (rx, owner, inner)
 }
 
@@ -165,12 +187,16 @@ impl Effect<LocalStorage> {
     /// This spawns a task on the local thread using
     /// [`spawn_local`](any_spawner::Executor::spawn_local). For an effect that can be spawned on
     /// any thread, use [`new_sync`](Effect::new_sync).
+    #[track_caller]
     pub fn new<T, M>(mut fun: impl EffectFunction<T, M> + 'static) -> Self
     where
         T: 'static,
     {
+        let site = ProfileSite::new(NodeKind::Effect);
+        let defined_at = Location::caller();
         let inner = cfg!(feature = "effects").then(|| {
-            let (mut rx, owner, inner) = effect_base();
+            let (mut rx, owner, inner) = effect_base(defined_at);
+            let abort = LazyAbortSignal::new(owner.clone());
             let value = Arc::new(RwLock::new(None::<T>));
             let mut first_run = true;
 
@@ -186,13 +212,18 @@ impl Effect<LocalStorage> {
                             }) || first_run)
                         {
                             first_run = false;
+                            let _profile = site.enter();
                             subscriber.clear_sources(&subscriber);
 
                             let old_value =
                                 mem::take(&mut *value.write().or_poisoned());
                             let new_value = owner.with_cleanup(|| {
-                                subscriber.with_observer(|| {
-                                    run_in_effect_scope(|| fun.run(old_value))
+                                abort.enter(|| {
+                                    subscriber.with_observer(|| {
+                                        run_in_effect_scope(|| {
+                                            fun.run(old_value)
+                                        })
+                                    })
                                 })
                             });
                             *value.write().or_poisoned() = Some(new_value);
@@ -310,6 +341,7 @@ impl Effect<LocalStorage> {
     /// # }).await;
     /// # });
     /// ```
+    #[track_caller]
     pub fn watch<D, T>(
         mut dependency_fn: impl FnMut() -> D + 'static,
         mut handler: impl FnMut(&D, Option<&D>, Option<T>) -> T + 'static,
@@ -319,8 +351,11 @@ impl Effect<LocalStorage> {
         D: 'static,
         T: 'static,
     {
+        let site = ProfileSite::new(NodeKind::Effect);
+        let defined_at = Location::caller();
         let inner = cfg!(feature = "effects").then(|| {
-            let (mut rx, owner, inner) = effect_base();
+            let (mut rx, owner, inner) = effect_base(defined_at);
+            let abort = LazyAbortSignal::new(owner.clone());
             let mut first_run = true;
             let dep_value = Arc::new(RwLock::new(None::<D>));
             let watch_value = Arc::new(RwLock::new(None::<T>));
@@ -337,13 +372,16 @@ impl Effect<LocalStorage> {
                                 subscriber.update_if_necessary()
                             }) || first_run)
                         {
+                            let _profile = site.enter();
                             subscriber.clear_sources(&subscriber);
 
                             let old_dep_value = mem::take(
                                 &mut *dep_value.write().or_poisoned(),
                             );
                             let new_dep_value = owner.with_cleanup(|| {
-                                subscriber.with_observer(&mut dependency_fn)
+                                abort.enter(|| {
+                                    subscriber.with_observer(&mut dependency_fn)
+                                })
                             });
 
                             let old_watch_value = mem::take(
@@ -351,11 +389,14 @@ impl Effect<LocalStorage> {
                             );
 
                             if immediate || !first_run {
-                                let new_watch_value = handler(
-                                    &new_dep_value,
-                                    old_dep_value.as_ref(),
-                                    old_watch_value,
-                                );
+                                let new_watch_value =
+                                    abort.with_current(|| {
+                                        handler(
+                                            &new_dep_value,
+                                            old_dep_value.as_ref(),
+                                            old_watch_value,
+                                        )
+                                    });
 
                                 *watch_value.write().or_poisoned() =
                                     Some(new_watch_value);
@@ -383,6 +424,7 @@ impl Effect<SyncStorage> {
     ///
     /// This spawns a task that can be run on any thread. For an effect that will be spawned on
     /// the current thread, use [`new`](Effect::new).
+    #[track_caller]
     pub fn new_sync<T, M>(
         fun: impl EffectFunction<T, M> + Send + Sync + 'static,
     ) -> Self
@@ -400,13 +442,17 @@ impl Effect<SyncStorage> {
     /// that are read inside it change.
     ///
     /// This will run whether the `effects` feature is enabled or not.
+    #[track_caller]
     pub fn new_isomorphic<T, M>(
         mut fun: impl EffectFunction<T, M> + Send + Sync + 'static,
     ) -> Self
     where
         T: Send + Sync + 'static,
     {
-        let (mut rx, owner, inner) = effect_base();
+        let site = ProfileSite::new(NodeKind::Effect);
+        let defined_at = Location::caller();
+        let (mut rx, owner, inner) = effect_base(defined_at);
+        let abort = LazyAbortSignal::new(owner.clone());
         let mut first_run = true;
         let value = Arc::new(RwLock::new(None::<T>));
 
@@ -422,13 +468,16 @@ impl Effect<SyncStorage> {
                             || first_run)
                     {
                         first_run = false;
+                        let _profile = site.enter();
                         subscriber.clear_sources(&subscriber);
 
                         let old_value =
                             mem::take(&mut *value.write().or_poisoned());
                         let new_value = owner.with_cleanup(|| {
-                            subscriber.with_observer(|| {
-                                run_in_effect_scope(|| fun.run(old_value))
+                            abort.enter(|| {
+                                subscriber.with_observer(|| {
+                                    run_in_effect_scope(|| fun.run(old_value))
+                                })
                             })
                         });
                         *value.write().or_poisoned() = Some(new_value);
@@ -445,6 +494,7 @@ impl Effect<SyncStorage> {
     }
 
     /// This is to [`Effect::watch`] what [`Effect::new_sync`] is to [`Effect::new`].
+    #[track_caller]
     pub fn watch_sync<D, T>(
         mut dependency_fn: impl FnMut() -> D + Send + Sync + 'static,
         mut handler: impl FnMut(&D, Option<&D>, Option<T>) -> T
@@ -457,7 +507,10 @@ impl Effect<SyncStorage> {
         D: Send + Sync + 'static,
         T: Send + Sync + 'static,
     {
-        let (mut rx, owner, inner) = effect_base();
+        let site = ProfileSite::new(NodeKind::Effect);
+        let defined_at = Location::caller();
+        let (mut rx, owner, inner) = effect_base(defined_at);
+        let abort = LazyAbortSignal::new(owner.clone());
         let mut first_run = true;
         let dep_value = Arc::new(RwLock::new(None::<D>));
         let watch_value = Arc::new(RwLock::new(None::<T>));
@@ -475,13 +528,16 @@ impl Effect<SyncStorage> {
                                 subscriber.update_if_necessary()
                             }) || first_run)
                         {
+                            let _profile = site.enter();
                             subscriber.clear_sources(&subscriber);
 
                             let old_dep_value = mem::take(
                                 &mut *dep_value.write().or_poisoned(),
                             );
                             let new_dep_value = owner.with_cleanup(|| {
-                                subscriber.with_observer(&mut dependency_fn)
+                                abort.enter(|| {
+                                    subscriber.with_observer(&mut dependency_fn)
+                                })
                             });
 
                             let old_watch_value = mem::take(
@@ -489,11 +545,14 @@ impl Effect<SyncStorage> {
                             );
 
                             if immediate || !first_run {
-                                let new_watch_value = handler(
-                                    &new_dep_value,
-                                    old_dep_value.as_ref(),
-                                    old_watch_value,
-                                );
+                                let new_watch_value =
+                                    abort.with_current(|| {
+                                        handler(
+                                            &new_dep_value,
+                                            old_dep_value.as_ref(),
+                                            old_watch_value,
+                                        )
+                                    });
 
                                 *watch_value.write().or_poisoned() =
                                     Some(new_watch_value);
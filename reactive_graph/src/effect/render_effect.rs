@@ -6,6 +6,7 @@ use crate::{
         WithObserver,
     },
     owner::Owner,
+    profiling::{NodeKind, ProfileSite},
 };
 use futures::StreamExt;
 use or_poisoned::OrPoisoned;
@@ -31,6 +32,11 @@ use std::{
 ///
 /// Like an [`Effect`](super::Effect), a render effect runs only with the `effects` feature
 /// enabled.
+///
+/// Unlike an `Effect`, a render effect does not provide an
+/// [`AbortSignal`](crate::computed::AbortSignal) for its runs. Render effects run for every
+/// reactive update to the view, and only make synchronous changes, so there is no async work for
+/// a signal to cancel.
 #[must_use = "A RenderEffect will be canceled when it is dropped. Creating a \
               RenderEffect that is not stored in some other data structure or \
               leaked will drop it immediately, and it will not react to \
@@ -59,6 +65,7 @@ where
     T: 'static,
 {
     /// Creates a new render effect, which immediately runs `fun`.
+    #[track_caller]
     pub fn new(fun: impl FnMut(Option<T>) -> T + 'static) -> Self {
         #[cfg(feature = "subsecond")]
         let (hot_fn_ptr, fun) = {
@@ -80,6 +87,7 @@ where
 
         Self::new_with_value_erased(
             Box::new(fun),
+            ProfileSite::new(NodeKind::RenderEffect),
             None,
             #[cfg(feature = "subsecond")]
             hot_fn_ptr,
@@ -87,6 +95,7 @@ where
     }
 
     /// Creates a new render effect with an initial value.
+    #[track_caller]
     pub fn new_with_value(
         fun: impl FnMut(Option<T>) -> T + 'static,
         initial_value: Option<T>,
@@ -111,6 +120,7 @@ where
 
         Self::new_with_value_erased(
             Box::new(fun),
+            ProfileSite::new(NodeKind::RenderEffect),
             initial_value,
             #[cfg(feature = "subsecond")]
             hot_fn_ptr,
@@ -127,15 +137,19 @@ where
         #[cfg(feature = "subsecond")]
         let fun = move |prev| fun.call((prev,));
 
+        // `#[track_caller]` has no effect on an `async fn`, so these effects are all profiled
+        // at this location
         Self::new_with_async_value_erased(
             Box::new(fun),
             Box::pin(value.into_future()),
+            ProfileSite::new(NodeKind::RenderEffect),
         )
         .await
     }
 
     fn new_with_value_erased(
         #[allow(unused_mut)] mut fun: Box<dyn FnMut(Option<T>) -> T + 'static>,
+        site: ProfileSite,
         initial_value: Option<T>,
         // this argument can be used to invalidate individual effects in the future
         // in present experiments, I have found that it is not actually granular enough to make a difference
@@ -163,6 +177,7 @@ where
         #[cfg(not(feature = "effects"))]
         {
             let _ = initial_value;
+            let _ = site;
             let _ = owner;
             let _ = &mut rx;
             let _ = fun;
@@ -215,9 +230,9 @@ where
                 move |prev| fun.call((prev,))
             };
 
-            *value.write().or_poisoned() = Some(
-                owner.with(|| subscriber.with_observer(|| fun(initial_value))),
-            );
+            *value.write().or_poisoned() = Some(owner.with(|| {
+                subscriber.with_observer(|| site.run(|| fun(initial_value)))
+            }));
 
             any_spawner::Executor::spawn_local({
                 let value = Arc::clone(&value);
@@ -229,6 +244,7 @@ where
                                 subscriber.update_if_necessary()
                             })
                         {
+                            let _profile = site.enter();
                             subscriber.clear_sources(&subscriber);
 
                             let old_value =
@@ -249,6 +265,7 @@ where
     async fn new_with_async_value_erased(
         mut fun: Box<dyn FnMut(Option<T>) -> T + 'static>,
         initial_value: Pin<Box<dyn Future<Output = T>>>,
+        site: ProfileSite,
     ) -> Self {
         // codegen optimisation:
         fn prep() -> (Owner, Arc<RwLock<EffectInner>>, crate::channel::Receiver)
@@ -270,6 +287,7 @@ where
         #[cfg(not(feature = "effects"))]
         {
             drop(initial_value);
+            let _ = site;
             let _ = owner;
             let _ = &mut rx;
             let _ = &mut fun;
@@ -296,6 +314,7 @@ where
                                 subscriber.update_if_necessary()
                             })
                         {
+                            let _profile = site.enter();
                             subscriber.clear_sources(&subscriber);
 
                             let old_value =
@@ -332,6 +351,7 @@ where
     T: Send + Sync + 'static,
 {
     /// Creates a render effect that will run whether the `effects` feature is enabled or not.
+    #[track_caller]
     pub fn new_isomorphic(
         fun: impl FnMut(Option<T>) -> T + Send + Sync + 'static,
     ) -> Self {
@@ -342,6 +362,7 @@ where
 
         fn erased<T: Send + Sync + 'static>(
             mut fun: Box<dyn FnMut(Option<T>) -> T + Send + Sync + 'static>,
+            site: ProfileSite,
         ) -> RenderEffect<T> {
             let (observer, mut rx) = channel();
             let value = Arc::new(RwLock::new(None::<T>));
@@ -352,8 +373,11 @@ where
                 sources: SourceSet::new(),
             }));
 
-            let initial_value = owner
-                .with(|| inner.to_any_subscriber().with_observer(|| fun(None)));
+            let initial_value = owner.with(|| {
+                inner
+                    .to_any_subscriber()
+                    .with_observer(|| site.run(|| fun(None)))
+            });
             *value.write().or_poisoned() = Some(initial_value);
 
             crate::spawn({
@@ -367,6 +391,7 @@ where
                                 subscriber.update_if_necessary()
                             })
                         {
+                            let _profile = site.enter();
                             subscriber.clear_sources(&subscriber);
 
                             let old_value =
@@ -383,7 +408,7 @@ where
             RenderEffect { value, inner }
         }
 
-        erased(Box::new(fun))
+        erased(Box::new(fun), ProfileSite::new(NodeKind::RenderEffect))
     }
 }
 
@@ -81,10 +81,16 @@ pub mod diagnostics;
 pub mod effect;
 pub mod graph;
 pub mod owner;
+#[cfg(feature = "profiling")]
+pub mod profiling;
+#[cfg(not(feature = "profiling"))]
+mod profiling;
 pub mod send_wrapper_ext;
 #[cfg(feature = "serde")]
 mod serde;
 pub mod signal;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod trait_options;
 pub mod traits;
 pub mod transition;
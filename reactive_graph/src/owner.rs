@@ -1,5 +1,7 @@
 //! The reactive ownership model, which manages effect cancellation, cleanups, and arena allocation.
 
+#[cfg(feature = "lifetime-diagnostics")]
+use crate::diagnostics::lifetimes::{self, Disposal};
 #[cfg(feature = "hydration")]
 use hydration_context::SharedContext;
 use or_poisoned::OrPoisoned;
@@ -24,7 +26,7 @@ pub use arc_stored_value::ArcStoredValue;
 pub use arena::sandboxed::Sandboxed;
 #[cfg(feature = "sandboxed-arenas")]
 use arena::ArenaMap;
-use arena::NodeId;
+pub(crate) use arena::NodeId;
 pub use arena_item::*;
 pub use context::*;
 pub use storage::*;
@@ -145,6 +147,7 @@ impl Owner {
     }
 
     /// Creates a new `Owner` and registers it as a child of the current `Owner`, if there is one.
+    #[track_caller]
     pub fn new() -> Self {
         #[cfg(not(feature = "hydration"))]
         let parent = OWNER.with(|o| {
@@ -186,6 +189,8 @@ impl Owner {
                 .children
                 .push(Arc::downgrade(&this.inner));
         }
+        #[cfg(feature = "lifetime-diagnostics")]
+        lifetimes::record_owner(&this, std::panic::Location::caller());
         this
     }
 
@@ -213,6 +218,8 @@ impl Owner {
             #[cfg(feature = "hydration")]
             shared_context,
         };
+        #[cfg(feature = "lifetime-diagnostics")]
+        lifetimes::record_owner(&this, std::panic::Location::caller());
         this.set();
         this
     }
@@ -237,6 +244,7 @@ impl Owner {
     }
 
     /// Creates a new `Owner` that is the child of the current `Owner`, if any.
+    #[track_caller]
     pub fn child(&self) -> Self {
         let parent = Some(Arc::downgrade(&self.inner));
         let mut inner = self.inner.write().or_poisoned();
@@ -258,6 +266,9 @@ impl Owner {
             shared_context: self.shared_context.clone(),
         };
         inner.children.push(Arc::downgrade(&child.inner));
+        drop(inner);
+        #[cfg(feature = "lifetime-diagnostics")]
+        lifetimes::record_owner(&child, std::panic::Location::caller());
         child
     }
 
@@ -529,6 +540,11 @@ impl Drop for OwnerInner {
         }
 
         let nodes = mem::take(&mut self.nodes);
+        #[cfg(feature = "lifetime-diagnostics")]
+        lifetimes::record_disposed(
+            nodes.iter().copied(),
+            Disposal::OwnerCleanup,
+        );
         if !nodes.is_empty() {
             #[cfg(not(feature = "sandboxed-arenas"))]
             Arena::with_mut(|arena| {
@@ -570,6 +586,11 @@ impl Cleanup for RwLock<OwnerInner> {
             cleanup();
         }
 
+        #[cfg(feature = "lifetime-diagnostics")]
+        lifetimes::record_disposed(
+            nodes.iter().copied(),
+            Disposal::OwnerCleanup,
+        );
         if !nodes.is_empty() {
             #[cfg(not(feature = "sandboxed-arenas"))]
             Arena::with_mut(|arena| {
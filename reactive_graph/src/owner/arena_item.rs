@@ -2,8 +2,12 @@ use super::{
     arena::{Arena, NodeId},
     LocalStorage, Storage, SyncStorage, OWNER,
 };
+#[cfg(feature = "lifetime-diagnostics")]
+use crate::diagnostics::lifetimes::{self, Disposal};
 use crate::traits::{Dispose, IntoInner, IsDisposed};
 use send_wrapper::SendWrapper;
+#[cfg(feature = "lifetime-diagnostics")]
+use std::panic::Location;
 use std::{any::Any, hash::Hash, marker::PhantomData};
 
 /// A copyable, stable reference for any value, stored on the arena whose ownership is managed by the
@@ -57,6 +61,13 @@ where
                 owner.register(node);
             }
         });
+        #[cfg(feature = "lifetime-diagnostics")]
+        lifetimes::record_created(
+            node,
+            std::any::type_name::<T>(),
+            Location::caller(),
+            super::Owner::current().as_ref(),
+        );
 
         Self {
             node,
@@ -102,7 +113,7 @@ impl<T, S: Storage<T>> ArenaItem<T, S> {
     /// Applies a function to a reference to the stored value and returns the result, or `None` if it has already been disposed.
     #[track_caller]
     pub fn try_with_value<U>(&self, fun: impl FnOnce(&T) -> U) -> Option<U> {
-        S::try_with(self.node, fun)
+        self.note_disposed_access(S::try_with(self.node, fun))
     }
 
     /// Applies a function to a mutable reference to the stored value and returns the result, or `None` if it has already been disposed.
@@ -111,7 +122,16 @@ impl<T, S: Storage<T>> ArenaItem<T, S> {
         &self,
         fun: impl FnOnce(&mut T) -> U,
     ) -> Option<U> {
-        S::try_with_mut(self.node, fun)
+        self.note_disposed_access(S::try_with_mut(self.node, fun))
+    }
+
+    #[inline(always)]
+    fn note_disposed_access<U>(&self, value: Option<U>) -> Option<U> {
+        #[cfg(feature = "lifetime-diagnostics")]
+        if value.is_none() {
+            lifetimes::record_disposed_access(self.node);
+        }
+        value
     }
 }
 
@@ -119,7 +139,7 @@ impl<T: Clone, S: Storage<T>> ArenaItem<T, S> {
     /// Returns a clone of the stored value, or `None` if it has already been disposed.
     #[track_caller]
     pub fn try_get_value(&self) -> Option<T> {
-        S::try_with(self.node, Clone::clone)
+        self.note_disposed_access(S::try_with(self.node, Clone::clone))
     }
 }
 
@@ -132,6 +152,11 @@ impl<T, S> IsDisposed for ArenaItem<T, S> {
 impl<T, S> Dispose for ArenaItem<T, S> {
     fn dispose(self) {
         Arena::with_mut(|arena| arena.remove(self.node));
+        #[cfg(feature = "lifetime-diagnostics")]
+        lifetimes::record_disposed(
+            [self.node],
+            Disposal::Disposed(Location::caller()),
+        );
     }
 }
 
@@ -139,7 +164,13 @@ impl<T, S: Storage<T>> IntoInner for ArenaItem<T, S> {
     type Value = T;
 
     #[inline(always)]
+    #[track_caller]
     fn into_inner(self) -> Option<Self::Value> {
+        #[cfg(feature = "lifetime-diagnostics")]
+        lifetimes::record_disposed(
+            [self.node],
+            Disposal::Taken(Location::caller()),
+        );
         S::take(self.node)
     }
 }
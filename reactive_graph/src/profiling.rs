@@ -0,0 +1,595 @@
+//! An opt-in profiler that counts how many times each reactive computation runs, and how long it
+//! takes.
+//!
+//! With the `profiling` feature enabled, every run of a [`Memo`](crate::computed::Memo),
+//! [`ArcMemo`](crate::computed::ArcMemo), [`Effect`](crate::effect::Effect),
+//! [`RenderEffect`](crate::effect::RenderEffect), and
+//! [`AsyncDerived`](crate::computed::AsyncDerived) is recorded, keyed by the location at which it
+//! was created. Recording only happens between calls to [`enable`] and [`disable`], so the
+//! feature can be compiled in without measuring anything until it is needed.
+//!
+//! ```
+//! # #[cfg(feature = "profiling")] {
+//! use reactive_graph::{computed::Memo, owner::Owner, prelude::*, profiling, signal::RwSignal};
+//!
+//! let owner = Owner::new();
+//! owner.set();
+//! profiling::enable();
+//!
+//! let count = RwSignal::new(0);
+//! let double = Memo::new(move |_| count.get() * 2);
+//! for i in 1..=3 {
+//!     count.set(i);
+//!     double.get_untracked();
+//! }
+//!
+//! let report = profiling::report();
+//! let hottest = &report.entries()[0];
+//! assert_eq!(hottest.kind, profiling::NodeKind::Memo);
+//! assert_eq!(hottest.runs, 3);
+//!
+//! // export the report, e.g., to write it to a file
+//! let _json = report.to_json();
+//! let _folded = report.to_folded();
+//! # profiling::disable();
+//! # }
+//! ```
+//!
+//! The time recorded for each node is inclusive: if a memo is first read inside an effect, the
+//! time taken to compute the memo is also counted toward the effect. The per-stack self times
+//! exported by [`ProfileReport::to_folded`] can be used to separate the two.
+
+#[cfg(feature = "profiling")]
+use or_poisoned::OrPoisoned;
+#[cfg(feature = "profiling")]
+use rustc_hash::FxHashMap;
+use std::future::Future;
+#[cfg(feature = "profiling")]
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    panic::Location,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, LazyLock, Mutex,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// The kind of reactive node that was profiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum NodeKind {
+    /// A [`Memo`](crate::computed::Memo) or [`ArcMemo`](crate::computed::ArcMemo).
+    Memo,
+    /// An [`Effect`](crate::effect::Effect).
+    Effect,
+    /// A [`RenderEffect`](crate::effect::RenderEffect).
+    RenderEffect,
+    /// An [`AsyncDerived`](crate::computed::AsyncDerived) or
+    /// [`ArcAsyncDerived`](crate::computed::ArcAsyncDerived).
+    AsyncDerived,
+}
+
+#[cfg(feature = "profiling")]
+impl NodeKind {
+    /// The name of this kind of node.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeKind::Memo => "Memo",
+            NodeKind::Effect => "Effect",
+            NodeKind::RenderEffect => "RenderEffect",
+            NodeKind::AsyncDerived => "AsyncDerived",
+        }
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl Display for NodeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The creation site of a reactive node, used to attribute its runs in the profile.
+///
+/// Without the `profiling` feature, this is a zero-sized type that does nothing.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ProfileSite {
+    #[cfg(feature = "profiling")]
+    data: &'static SiteData,
+}
+
+/// Records a run of a node when dropped. See [`ProfileSite::enter`].
+#[must_use]
+pub(crate) struct ProfileGuard {
+    #[cfg(feature = "profiling")]
+    _frame: Option<Frame>,
+}
+
+impl ProfileSite {
+    #[track_caller]
+    #[inline(always)]
+    pub(crate) fn new(kind: NodeKind) -> Self {
+        #[cfg(feature = "profiling")]
+        {
+            Self {
+                data: SiteData::intern(kind, Location::caller()),
+            }
+        }
+        #[cfg(not(feature = "profiling"))]
+        {
+            _ = kind;
+            Self {}
+        }
+    }
+
+    /// Records one run of this node, lasting until the returned guard is dropped.
+    #[inline(always)]
+    pub(crate) fn enter(self) -> ProfileGuard {
+        #[cfg(feature = "profiling")]
+        {
+            ProfileGuard {
+                _frame: is_enabled().then(|| Frame::enter(self, true)),
+            }
+        }
+        #[cfg(not(feature = "profiling"))]
+        {
+            ProfileGuard {}
+        }
+    }
+
+    /// Runs the function, recording one run of this node and the time it took.
+    #[inline(always)]
+    pub(crate) fn run<T>(self, fun: impl FnOnce() -> T) -> T {
+        let _profile = self.enter();
+        fun()
+    }
+
+    /// Wraps the future, adding the time spent polling it to this node, without counting a run.
+    #[inline(always)]
+    pub(crate) fn instrument<Fut: Future>(
+        self,
+        fut: Fut,
+    ) -> impl Future<Output = Fut::Output> {
+        #[cfg(feature = "profiling")]
+        {
+            Instrumented {
+                inner: fut,
+                site: self,
+            }
+        }
+        #[cfg(not(feature = "profiling"))]
+        {
+            fut
+        }
+    }
+}
+
+/// Statistics about all the runs of a single reactive node.
+#[cfg(feature = "profiling")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileEntry {
+    /// The kind of node.
+    pub kind: NodeKind,
+    /// Where the node was created.
+    pub location: &'static Location<'static>,
+    /// The number of times the node has run.
+    pub runs: u64,
+    /// The total wall time spent running the node, including any nested nodes it ran.
+    pub total: Duration,
+}
+
+#[cfg(feature = "profiling")]
+impl ProfileEntry {
+    /// The mean wall time of a single run.
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.runs) {
+            Ok(0) => Duration::ZERO,
+            Ok(runs) => self.total / runs,
+            Err(_) => Duration::from_secs_f64(
+                self.total.as_secs_f64() / self.runs as f64,
+            ),
+        }
+    }
+}
+
+/// A snapshot of the profile data collected so far. See [`report`].
+#[cfg(feature = "profiling")]
+#[derive(Debug, Clone, Default)]
+pub struct ProfileReport {
+    entries: Vec<ProfileEntry>,
+    stacks: Vec<(String, Duration)>,
+}
+
+#[cfg(feature = "profiling")]
+impl ProfileReport {
+    /// All profiled nodes, sorted by total time, longest first.
+    pub fn entries(&self) -> &[ProfileEntry] {
+        &self.entries
+    }
+
+    /// Returns the entries created at the given file and line.
+    pub fn entries_at<'a>(
+        &'a self,
+        file: &'a str,
+        line: u32,
+    ) -> impl Iterator<Item = &'a ProfileEntry> + 'a {
+        self.entries.iter().filter(move |entry| {
+            entry.location.file() == file && entry.location.line() == line
+        })
+    }
+
+    /// Serializes the report as a JSON array of objects, with durations in nanoseconds.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("[");
+        for (idx, entry) in self.entries.iter().enumerate() {
+            if idx > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"kind\":\"");
+            json.push_str(entry.kind.as_str());
+            json.push_str("\",\"file\":");
+            push_json_string(&mut json, entry.location.file());
+            json.push_str(&format!(
+                ",\"line\":{},\"column\":{},\"runs\":{},\"total_ns\":{},\
+                 \"mean_ns\":{}}}",
+                entry.location.line(),
+                entry.location.column(),
+                entry.runs,
+                entry.total.as_nanos(),
+                entry.mean().as_nanos()
+            ));
+        }
+        json.push(']');
+        json
+    }
+
+    /// Serializes the report in the “folded stacks” format used by `tracing-flame` and
+    /// `inferno`, with one line per distinct stack of nested nodes and the self time of that
+    /// stack in nanoseconds.
+    ///
+    /// The output can be passed to `inferno-flamegraph` to render a flamegraph.
+    pub fn to_folded(&self) -> String {
+        let mut folded = String::new();
+        for (stack, time) in &self.stacks {
+            folded.push_str(stack);
+            folded.push(' ');
+            folded.push_str(&time.as_nanos().to_string());
+            folded.push('\n');
+        }
+        folded
+    }
+}
+
+#[cfg(feature = "profiling")]
+fn push_json_string(buf: &mut String, value: &str) {
+    buf.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            c if c.is_control() => {
+                buf.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+}
+
+#[cfg(feature = "profiling")]
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The data recorded for all the nodes created at a single location.
+#[cfg(feature = "profiling")]
+#[derive(Debug)]
+struct SiteData {
+    kind: NodeKind,
+    location: &'static Location<'static>,
+    /// The name of this site in folded stacks.
+    label: String,
+    runs: AtomicU64,
+    /// The total time spent running nodes created here, in nanoseconds.
+    total: AtomicU64,
+}
+
+#[cfg(feature = "profiling")]
+type SiteKey = (NodeKind, &'static Location<'static>);
+
+#[cfg(feature = "profiling")]
+static SITES: LazyLock<Mutex<FxHashMap<SiteKey, &'static SiteData>>> =
+    LazyLock::new(Default::default);
+
+#[cfg(feature = "profiling")]
+impl SiteData {
+    /// Returns the data for a creation site. This is allocated the first time a node is created
+    /// at that site, and kept for the rest of the program, so that runs can be recorded without
+    /// looking the site up again.
+    fn intern(
+        kind: NodeKind,
+        location: &'static Location<'static>,
+    ) -> &'static SiteData {
+        SITES
+            .lock()
+            .or_poisoned()
+            .entry((kind, location))
+            .or_insert_with(|| {
+                Box::leak(Box::new(SiteData {
+                    kind,
+                    location,
+                    label: format!("{kind} ({location})"),
+                    runs: AtomicU64::new(0),
+                    total: AtomicU64::new(0),
+                }))
+            })
+    }
+}
+
+// sites are interned, so they can be compared and hashed by address
+#[cfg(feature = "profiling")]
+impl PartialEq for ProfileSite {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.data, other.data)
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl Eq for ProfileSite {}
+
+#[cfg(feature = "profiling")]
+impl Hash for ProfileSite {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self.data, state)
+    }
+}
+
+/// The self time of each distinct stack of nested nodes that has run on one thread.
+#[cfg(feature = "profiling")]
+type Stacks = FxHashMap<Vec<ProfileSite>, Duration>;
+
+/// The stacks recorded on every thread, so they can be collected into a report.
+#[cfg(feature = "profiling")]
+static THREAD_STACKS: LazyLock<Mutex<Vec<Arc<Mutex<Stacks>>>>> =
+    LazyLock::new(Default::default);
+
+/// Starts recording runs of reactive nodes.
+#[cfg(feature = "profiling")]
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Stops recording runs of reactive nodes. Data that has already been recorded is kept.
+#[cfg(feature = "profiling")]
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+/// Whether runs of reactive nodes are currently being recorded.
+#[cfg(feature = "profiling")]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Clears all the data that has been recorded so far.
+#[cfg(feature = "profiling")]
+pub fn reset() {
+    for site in SITES.lock().or_poisoned().values() {
+        site.runs.store(0, Ordering::Relaxed);
+        site.total.store(0, Ordering::Relaxed);
+    }
+    for stacks in THREAD_STACKS.lock().or_poisoned().iter() {
+        stacks.lock().or_poisoned().clear();
+    }
+}
+
+/// Returns a snapshot of all the data that has been recorded so far.
+#[cfg(feature = "profiling")]
+pub fn report() -> ProfileReport {
+    let mut entries = SITES
+        .lock()
+        .or_poisoned()
+        .values()
+        .map(|site| ProfileEntry {
+            kind: site.kind,
+            location: site.location,
+            runs: site.runs.load(Ordering::Relaxed),
+            total: Duration::from_nanos(site.total.load(Ordering::Relaxed)),
+        })
+        .filter(|entry| entry.runs > 0 || !entry.total.is_zero())
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| {
+        b.total
+            .cmp(&a.total)
+            .then(b.runs.cmp(&a.runs))
+            .then_with(|| a.location.file().cmp(b.location.file()))
+            .then(a.location.line().cmp(&b.location.line()))
+    });
+
+    let mut stacks = FxHashMap::<String, Duration>::default();
+    for thread in THREAD_STACKS.lock().or_poisoned().iter() {
+        for (sites, time) in thread.lock().or_poisoned().iter() {
+            let path = sites
+                .iter()
+                .map(|site| site.data.label.as_str())
+                .collect::<Vec<_>>()
+                .join(";");
+            *stacks.entry(path).or_default() += *time;
+        }
+    }
+    let mut stacks = stacks.into_iter().collect::<Vec<_>>();
+    stacks.sort();
+    ProfileReport { entries, stacks }
+}
+
+#[cfg(feature = "profiling")]
+struct OpenFrame {
+    start: Instant,
+    children: Duration,
+}
+
+/// The nodes that are currently running on a thread.
+#[cfg(feature = "profiling")]
+struct ThreadStack {
+    sites: Vec<ProfileSite>,
+    frames: Vec<OpenFrame>,
+}
+
+#[cfg(feature = "profiling")]
+thread_local! {
+    static STACK: RefCell<ThreadStack> = const {
+        RefCell::new(ThreadStack {
+            sites: Vec::new(),
+            frames: Vec::new(),
+        })
+    };
+    static STACKS: Arc<Mutex<Stacks>> = {
+        let stacks = Arc::default();
+        THREAD_STACKS.lock().or_poisoned().push(Arc::clone(&stacks));
+        stacks
+    };
+}
+
+/// An entry on the current thread's stack of running nodes, which is recorded when dropped.
+#[cfg(feature = "profiling")]
+struct Frame {
+    site: ProfileSite,
+    count_run: bool,
+}
+
+#[cfg(feature = "profiling")]
+impl Frame {
+    fn enter(site: ProfileSite, count_run: bool) -> Self {
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            stack.sites.push(site);
+            stack.frames.push(OpenFrame {
+                start: Instant::now(),
+                children: Duration::ZERO,
+            });
+        });
+        Self { site, count_run }
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl Drop for Frame {
+    fn drop(&mut self) {
+        let elapsed = STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let frame = stack.frames.pop()?;
+            let elapsed = frame.start.elapsed();
+            let self_time = elapsed.saturating_sub(frame.children);
+            if let Some(parent) = stack.frames.last_mut() {
+                parent.children += elapsed;
+            }
+            STACKS.with(|stacks| {
+                let mut stacks = stacks.lock().or_poisoned();
+                match stacks.get_mut(stack.sites.as_slice()) {
+                    Some(time) => *time += self_time,
+                    None => {
+                        stacks.insert(stack.sites.clone(), self_time);
+                    }
+                }
+            });
+            stack.sites.pop();
+            Some(elapsed)
+        });
+        let Some(elapsed) = elapsed else {
+            return;
+        };
+
+        let data = self.site.data;
+        if self.count_run {
+            data.runs.fetch_add(1, Ordering::Relaxed);
+        }
+        data.total.fetch_add(
+            u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+}
+
+#[cfg(feature = "profiling")]
+pin_project_lite::pin_project! {
+    struct Instrumented<Fut> {
+        #[pin]
+        inner: Fut,
+        site: ProfileSite,
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl<Fut: Future> Future for Instrumented<Fut> {
+    type Output = Fut::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _frame = is_enabled().then(|| Frame::enter(*this.site, false));
+        this.inner.poll(cx)
+    }
+}
+
+/// A monotonic clock that works both natively and in the browser, where
+/// [`std::time::Instant`] is not supported.
+#[cfg(feature = "profiling")]
+#[derive(Clone, Copy)]
+struct Instant {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    inner: std::time::Instant,
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    millis: f64,
+}
+
+#[cfg(all(
+    feature = "profiling",
+    target_arch = "wasm32",
+    target_os = "unknown"
+))]
+thread_local! {
+    // read from the global object rather than `window`, so that this also works in workers
+    static PERFORMANCE: Option<web_sys::Performance> = {
+        use web_sys::{js_sys, wasm_bindgen::JsCast};
+
+        js_sys::Reflect::get(&js_sys::global(), &"performance".into())
+            .ok()
+            .and_then(|performance| performance.dyn_into().ok())
+    };
+}
+
+#[cfg(feature = "profiling")]
+impl Instant {
+    fn now() -> Self {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        {
+            Self {
+                inner: std::time::Instant::now(),
+            }
+        }
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        {
+            Self {
+                millis: PERFORMANCE.with(|performance| {
+                    performance
+                        .as_ref()
+                        .map(|performance| performance.now())
+                        .unwrap_or_default()
+                }),
+            }
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        {
+            self.inner.elapsed()
+        }
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        {
+            let now = Self::now().millis;
+            Duration::from_secs_f64((now - self.millis).max(0.0) / 1000.0)
+        }
+    }
+}
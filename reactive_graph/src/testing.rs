@@ -0,0 +1,89 @@
+//! Utilities for testing reactive code deterministically.
+//!
+//! Effects, async derived values, and resources all run as tasks on the global
+//! [`Executor`](any_spawner::Executor). This module re-exports the manually-driven test executor
+//! and virtual clock from [`any_spawner::testing`], and adds helpers for asserting how many times
+//! reactive code has run.
+//!
+//! ```
+//! # #[cfg(feature = "effects")] {
+//! use reactive_graph::{
+//!     effect::Effect, owner::Owner, prelude::*, signal::RwSignal, testing::*,
+//! };
+//!
+//! _ = any_spawner::Executor::init_testing();
+//! let owner = Owner::new();
+//! owner.set();
+//!
+//! let count = RwSignal::new(0);
+//! let runs = RunCounter::new();
+//! Effect::new({
+//!     let runs = runs.clone();
+//!     move || {
+//!         count.track();
+//!         runs.increment();
+//!     }
+//! });
+//!
+//! // effects run on the next tick, which only happens when we drive the executor
+//! runs.assert_runs(0);
+//! run_until_stalled();
+//! runs.assert_runs(1);
+//!
+//! count.set(1);
+//! count.set(2);
+//! run_until_stalled();
+//! runs.assert_runs(2);
+//! # }
+//! ```
+
+pub use any_spawner::testing::{
+    advance, block_on, is_stalled, now, pending_tasks, pending_timers,
+    run_until_stalled, sleep, Sleep,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// A shared counter that can be incremented from inside an effect, memo, or async block to record
+/// how many times it has run.
+///
+/// Clones share the same count.
+#[derive(Debug, Clone, Default)]
+pub struct RunCounter(Arc<AtomicUsize>);
+
+impl RunCounter {
+    /// Creates a new counter, starting at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one run.
+    pub fn increment(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of runs recorded so far.
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Resets the count to zero.
+    pub fn reset(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+
+    /// Asserts that exactly `expected` runs have been recorded.
+    ///
+    /// # Panics
+    /// Panics if the number of recorded runs is different.
+    #[track_caller]
+    pub fn assert_runs(&self, expected: usize) {
+        let actual = self.get();
+        assert_eq!(
+            actual, expected,
+            "expected {expected} runs, but {actual} were recorded"
+        );
+    }
+}
@@ -97,6 +97,7 @@ pub trait Dispose {
     /// 1. Detaches the signal from the reactive graph, preventing it from triggering
     ///    further updates; and
     /// 2. Drops the value contained in the signal.
+    #[track_caller]
     fn dispose(self);
 }
 
@@ -675,7 +676,8 @@ pub fn panic_getting_disposed_signal(
     defined_at: Option<&'static Location<'static>>,
     location: &'static Location<'static>,
 ) -> String {
-    if let Some(defined_at) = defined_at {
+    #[allow(unused_mut)]
+    let mut message = if let Some(defined_at) = defined_at {
         format!(
             "At {location}, you tried to access a reactive value which was \
              defined at {defined_at}, but it has already been disposed."
@@ -685,7 +687,14 @@ pub fn panic_getting_disposed_signal(
             "At {location}, you tried to access a reactive value, but it has \
              already been disposed."
         )
-    }
+    };
+    #[cfg(feature = "lifetime-diagnostics")]
+    if let Some(access) = crate::diagnostics::lifetimes::last_disposed_access()
+    {
+        message.push(' ');
+        message.push_str(&access.to_string());
+    }
+    message
 }
 
 /// A variation of the [`Read`] trait that provides a signposted "always-non-reactive" API.
@@ -1,11 +1,14 @@
 use any_spawner::Executor;
 use reactive_graph::{
-    computed::{ArcAsyncDerived, AsyncDerived},
+    computed::{AbortSignal, ArcAsyncDerived, AsyncDerived},
     owner::Owner,
     signal::RwSignal,
     traits::{Get, Read, Set, With, WithUntracked},
 };
-use std::future::pending;
+use std::{
+    future::pending,
+    sync::{Arc, Mutex},
+};
 
 #[tokio::test]
 async fn arc_async_derived_calculates_eagerly() {
@@ -137,3 +140,25 @@ async fn async_derived_with_initial() {
     signal2.set(1);
     assert_eq!(derived.await, 2);
 }
+
+#[tokio::test]
+async fn pending_run_is_aborted_on_cleanup() {
+    _ = Executor::init_tokio();
+    let owner = Owner::new();
+    owner.set();
+
+    let signal = Arc::new(Mutex::new(None));
+    let _value = ArcAsyncDerived::new({
+        let signal = Arc::clone(&signal);
+        move || {
+            *signal.lock().unwrap() = AbortSignal::current();
+            pending::<()>()
+        }
+    });
+    let signal = signal.lock().unwrap().clone().unwrap();
+    assert!(!signal.is_aborted());
+
+    owner.cleanup();
+    assert!(signal.is_aborted());
+    signal.aborted().await;
+}
@@ -255,3 +255,80 @@ async fn paused_effect_pauses() {
         })
         .await
 }
+
+#[cfg(feature = "effects")]
+#[tokio::test]
+async fn effect_abort_signal_is_aborted_on_rerun() {
+    use imports::*;
+    use reactive_graph::computed::AbortSignal;
+
+    _ = Executor::init_tokio();
+    let owner = Owner::new();
+    owner.set();
+    task::LocalSet::new()
+        .run_until(async {
+            let a = RwSignal::new(0);
+            let signals = Arc::new(RwLock::new(Vec::new()));
+
+            Effect::new({
+                let signals = Arc::clone(&signals);
+                move |_| {
+                    a.track();
+                    signals
+                        .write()
+                        .unwrap()
+                        .push(AbortSignal::current().unwrap());
+                }
+            });
+
+            Executor::tick().await;
+            a.set(1);
+            Executor::tick().await;
+
+            let signals = signals.read().unwrap();
+            assert_eq!(signals.len(), 2);
+            assert!(signals[0].is_aborted());
+            assert!(!signals[1].is_aborted());
+        })
+        .await;
+}
+
+#[cfg(feature = "effects")]
+#[tokio::test]
+async fn watch_abort_signal_is_aborted_on_rerun() {
+    use imports::*;
+    use reactive_graph::computed::AbortSignal;
+
+    _ = Executor::init_tokio();
+    let owner = Owner::new();
+    owner.set();
+    task::LocalSet::new()
+        .run_until(async {
+            let a = RwSignal::new(0);
+            let signals = Arc::new(RwLock::new(Vec::new()));
+
+            Effect::watch(
+                move || a.get(),
+                {
+                    let signals = Arc::clone(&signals);
+                    move |_, _, _| {
+                        signals
+                            .write()
+                            .unwrap()
+                            .push(AbortSignal::current().unwrap());
+                    }
+                },
+                true,
+            );
+
+            Executor::tick().await;
+            a.set(1);
+            Executor::tick().await;
+
+            let signals = signals.read().unwrap();
+            assert_eq!(signals.len(), 2);
+            assert!(signals[0].is_aborted());
+            assert!(!signals[1].is_aborted());
+        })
+        .await;
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "lifetime-diagnostics")]
+
+use reactive_graph::{
+    diagnostics::lifetimes::{self, Disposal},
+    owner::{Owner, StoredValue},
+    prelude::*,
+    signal::RwSignal,
+};
+
+#[test]
+fn arena_report_groups_by_owner_and_site() {
+    let owner = Owner::new();
+    let line = line!() + 2;
+    let signals =
+        owner.with(|| (0..3).map(|n| RwSignal::new(n)).collect::<Vec<_>>());
+    let _stored = owner.with(|| StoredValue::new("stored"));
+
+    let report = lifetimes::arena_report();
+    let group = report
+        .iter()
+        .find(|group| group.owner == Some(owner.debug_id()))
+        .expect("owner should have live values");
+    assert!(group.owner_alive);
+    assert_eq!(group.sites.len(), 2);
+    assert_eq!(group.sites[0].count, 3);
+    assert_eq!(group.sites[0].created_at.line(), line);
+    assert_eq!(group.sites[1].count, 1);
+
+    drop(owner);
+    assert!(signals.iter().all(|signal| signal.try_get().is_none()));
+}
+
+#[test]
+fn child_owner_that_outlives_its_parent_is_leaked() {
+    let parent = Owner::new();
+    let (child, child_line) = (parent.with(|| Owner::new()), line!());
+    let child_id = child.debug_id();
+
+    // keeping a clone of the child alive means it is never dropped, and anything created under it
+    // after its parent has been cleaned up stays in the arena
+    let leaked_child = child.clone();
+    drop(child);
+    drop(parent);
+    let signal = leaked_child.with(|| RwSignal::new(0));
+
+    let owner = lifetimes::leaked_owners()
+        .into_iter()
+        .find(|owner| owner.id == child_id)
+        .expect("child owner should be leaked");
+    assert_eq!(owner.created_at.line(), child_line);
+    assert_eq!(owner.arena_entries, 1);
+
+    assert_eq!(signal.get_untracked(), 0);
+
+    drop(leaked_child);
+    assert!(!lifetimes::live_owners()
+        .iter()
+        .any(|owner| owner.id == child_id));
+}
+
+#[test]
+fn disposed_access_records_where_it_was_disposed() {
+    let owner = Owner::new();
+    owner.set();
+
+    let (signal, created_line) = (RwSignal::new(0), line!());
+    let disposed_line = line!() + 1;
+    signal.dispose();
+    assert_eq!(signal.try_get(), None);
+
+    let access = lifetimes::last_disposed_access().unwrap();
+    assert_eq!(access.created_at.line(), created_line);
+    assert_eq!(access.owner, Some(owner.debug_id()));
+    match access.disposal {
+        Disposal::Disposed(location) => {
+            assert_eq!(location.file(), file!());
+            assert_eq!(location.line(), disposed_line);
+        }
+        other => panic!("unexpected disposal {other:?}"),
+    }
+    assert!(access.to_string().contains(&format!(":{created_line}:")));
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "profiling")]
+
+use reactive_graph::{
+    computed::{ArcMemo, Memo},
+    owner::Owner,
+    prelude::*,
+    profiling::{self, NodeKind},
+    signal::RwSignal,
+};
+
+#[test]
+fn memo_runs_are_counted_by_location() {
+    let owner = Owner::new();
+    owner.set();
+    profiling::enable();
+
+    let a = RwSignal::new(1);
+    let (hot, hot_line) = (Memo::new(move |_| a.get() * 2), line!());
+    let (cold, cold_line) = (ArcMemo::new(move |_| a.get() > 100), line!());
+
+    for i in 0..10 {
+        a.set(i);
+        hot.get_untracked();
+        if i % 5 == 0 {
+            cold.get_untracked();
+        }
+    }
+
+    let report = profiling::report();
+    let hot = report.entries_at(file!(), hot_line).collect::<Vec<_>>();
+    assert_eq!(hot.len(), 1);
+    assert_eq!(hot[0].kind, NodeKind::Memo);
+    assert_eq!(hot[0].runs, 10);
+
+    let cold = report.entries_at(file!(), cold_line).collect::<Vec<_>>();
+    assert_eq!(cold.len(), 1);
+    assert_eq!(cold[0].runs, 2);
+
+    let json = report.to_json();
+    assert!(json.starts_with('[') && json.ends_with(']'));
+    assert!(json.contains(&format!("\"line\":{hot_line},")));
+    assert!(json.contains("\"runs\":10,"));
+}
+
+#[test]
+fn nested_memos_are_folded_into_stacks() {
+    let owner = Owner::new();
+    owner.set();
+    profiling::enable();
+
+    let a = RwSignal::new(1);
+    let (inner, inner_line) = (Memo::new(move |_| a.get() + 1), line!());
+    let (outer, outer_line) = (Memo::new(move |_| inner.get() * 2), line!());
+
+    assert_eq!(outer.get_untracked(), 4);
+
+    let folded = profiling::report().to_folded();
+    let stack = folded
+        .lines()
+        .find(|line| {
+            line.contains(&format!("{}:{outer_line}:", file!()))
+                && line.contains(&format!("{}:{inner_line}:", file!()))
+        })
+        .expect("nested stack should be recorded");
+    let (frames, time) = stack.rsplit_once(' ').unwrap();
+    let frames = frames.split(';').collect::<Vec<_>>();
+    assert_eq!(frames.len(), 2);
+    assert!(frames[0].starts_with("Memo ("));
+    assert!(frames[0].contains(&format!(":{outer_line}:")));
+    assert!(frames[1].contains(&format!(":{inner_line}:")));
+    assert!(time.parse::<u128>().is_ok());
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "testing")]
+
+use any_spawner::Executor;
+use reactive_graph::{
+    computed::{AbortSignal, ArcAsyncDerived},
+    owner::Owner,
+    prelude::*,
+    signal::RwSignal,
+    testing::*,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[cfg(feature = "effects")]
+#[test]
+fn effect_runs_are_deterministic() {
+    use reactive_graph::{computed::Memo, effect::Effect};
+
+    _ = Executor::init_testing();
+    let owner = Owner::new();
+    owner.set();
+
+    let a = RwSignal::new(0);
+    let b = RwSignal::new(0);
+    let sum = Memo::new(move |_| a.get() + b.get());
+    let runs = RunCounter::new();
+
+    Effect::new({
+        let runs = runs.clone();
+        move || {
+            _ = sum.get();
+            runs.increment();
+        }
+    });
+    runs.assert_runs(0);
+    run_until_stalled();
+    runs.assert_runs(1);
+
+    // multiple synchronous changes are batched into a single run
+    a.set(1);
+    b.set(2);
+    run_until_stalled();
+    runs.assert_runs(2);
+    assert_eq!(sum.get_untracked(), 3);
+
+    // a change that doesn't change the memo doesn't rerun the effect
+    a.set(2);
+    b.set(1);
+    run_until_stalled();
+    runs.assert_runs(2);
+}
+
+#[test]
+fn async_derived_with_virtual_clock() {
+    _ = Executor::init_testing();
+    let owner = Owner::new();
+    owner.set();
+
+    let source = RwSignal::new(1);
+    let runs = RunCounter::new();
+    let value = ArcAsyncDerived::new({
+        let runs = runs.clone();
+        move || {
+            let source = source.get();
+            runs.increment();
+            async move {
+                sleep(Duration::from_millis(100)).await;
+                source * 10
+            }
+        }
+    });
+
+    run_until_stalled();
+    runs.assert_runs(1);
+    assert_eq!(value.get_untracked(), None);
+    assert_eq!(pending_timers(), 1);
+
+    advance(Duration::from_millis(100));
+    assert_eq!(value.get_untracked(), Some(10));
+
+    source.set(2);
+    run_until_stalled();
+    runs.assert_runs(2);
+    assert_eq!(value.get_untracked(), Some(10));
+
+    advance(Duration::from_millis(100));
+    assert_eq!(value.get_untracked(), Some(20));
+    assert_eq!(block_on(value.clone()), 20);
+}
+
+#[test]
+fn superseded_run_is_aborted() {
+    _ = Executor::init_testing();
+    let owner = Owner::new();
+    owner.set();
+
+    let source = RwSignal::new(1);
+    let completed = RunCounter::new();
+    let signals = Arc::new(Mutex::new(Vec::new()));
+    let value = ArcAsyncDerived::new({
+        let completed = completed.clone();
+        let signals = Arc::clone(&signals);
+        move || {
+            let source = source.get();
+            signals
+                .lock()
+                .unwrap()
+                .push(AbortSignal::current().unwrap());
+            let completed = completed.clone();
+            async move {
+                sleep(Duration::from_millis(20)).await;
+                completed.increment();
+                source
+            }
+        }
+    });
+    advance(Duration::from_millis(20));
+    assert_eq!(value.get_untracked(), Some(1));
+
+    // the second run is superseded while it is still sleeping
+    source.set(2);
+    run_until_stalled();
+    advance(Duration::from_millis(5));
+    source.set(3);
+    run_until_stalled();
+    advance(Duration::from_millis(20));
+    assert_eq!(value.get_untracked(), Some(3));
+
+    // the second run was aborted and dropped before it completed
+    completed.assert_runs(2);
+    let signals = signals.lock().unwrap();
+    assert_eq!(signals.len(), 3);
+    assert!(signals[1].is_aborted());
+    assert!(!signals[2].is_aborted());
+}
+
+#[test]
+fn run_without_abort_signal_is_not_starved() {
+    _ = Executor::init_testing();
+    let owner = Owner::new();
+    owner.set();
+
+    // the source changes twice as often as the fetcher resolves
+    let source = RwSignal::new(0);
+    let value = ArcAsyncDerived::new(move || {
+        let source = source.get();
+        async move {
+            sleep(Duration::from_millis(20)).await;
+            source
+        }
+    });
+    run_until_stalled();
+
+    for i in 1..=4 {
+        advance(Duration::from_millis(10));
+        source.set(i);
+        run_until_stalled();
+    }
+
+    // runs that did not ask for their signal are not aborted, so values still arrive
+    assert!(value.get_untracked().is_some());
+    advance(Duration::from_millis(40));
+    assert_eq!(value.get_untracked(), Some(4));
+}
@@ -97,6 +97,62 @@ impl<T> UntrackableGuard for StoreFieldWriter<T> {
     }
 }
 
+impl<T> ArcField<T>
+where
+    T: 'static,
+{
+    /// Erases the type of any store field, including ones whose full type is generic.
+    #[track_caller]
+    pub fn new<S>(field: S) -> Self
+    where
+        S: StoreField<Value = T> + Send + Sync + 'static,
+    {
+        let value = Arc::new(field);
+        ArcField {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+            path: Arc::new({
+                let value = Arc::clone(&value);
+                move || value.path().into_iter().collect()
+            }),
+            path_unkeyed: Arc::new({
+                let value = Arc::clone(&value);
+                move || value.path_unkeyed().into_iter().collect()
+            }),
+            get_trigger: Arc::new({
+                let value = Arc::clone(&value);
+                move |path| value.get_trigger(path)
+            }),
+            get_trigger_unkeyed: Arc::new({
+                let value = Arc::clone(&value);
+                move |path| value.get_trigger_unkeyed(path)
+            }),
+            read: Arc::new({
+                let value = Arc::clone(&value);
+                move || value.reader().map(StoreFieldReader::new)
+            }),
+            write: Arc::new({
+                let value = Arc::clone(&value);
+                move || value.writer().map(StoreFieldWriter::new)
+            }),
+            keys: Arc::new({
+                let value = Arc::clone(&value);
+                move || value.keys()
+            }),
+            track_field: Arc::new({
+                let value = Arc::clone(&value);
+                move || value.track_field()
+            }),
+            notify: Arc::new(move || {
+                let trigger =
+                    value.get_trigger(value.path().into_iter().collect());
+                trigger.this.notify();
+                trigger.children.notify();
+            }),
+        }
+    }
+}
+
 impl<T> StoreField for ArcField<T> {
     type Value = T;
     type Reader = StoreFieldReader<T>;
@@ -0,0 +1,99 @@
+use crate::{ArcField, Field, StoreField, StorePath};
+use or_poisoned::OrPoisoned;
+use reactive_graph::{
+    computed::Memo,
+    owner::{Owner, WeakOwner},
+    traits::IsDisposed,
+};
+use rustc_hash::FxHashMap;
+use std::{
+    any::Any,
+    sync::{Arc, Mutex},
+};
+
+/// The path of the field a computed field belongs to, and the computed field's name.
+type MemoKey = (StorePath, &'static str);
+
+/// The memos for the computed fields of a store.
+///
+/// Each memo is created the first time its field is accessed, and belongs to the owner that was
+/// current when the store was created, so that it lives as long as the store does rather than as
+/// long as whichever part of the app accessed it first.
+#[derive(Clone)]
+pub(crate) struct ComputedMemos {
+    owner: Option<WeakOwner>,
+    memos: Arc<Mutex<FxHashMap<MemoKey, Box<dyn Any + Send + Sync>>>>,
+}
+
+impl Default for ComputedMemos {
+    fn default() -> Self {
+        Self {
+            owner: Owner::current().map(|owner| owner.downgrade()),
+            memos: Default::default(),
+        }
+    }
+}
+
+impl ComputedMemos {
+    fn get_or_create<U>(
+        &self,
+        path: StorePath,
+        name: &'static str,
+        create: impl FnOnce() -> Memo<U>,
+    ) -> Memo<U>
+    where
+        U: Send + Sync + 'static,
+    {
+        let mut memos = self.memos.lock().or_poisoned();
+        let key = (path, name);
+        if let Some(memo) = memos
+            .get(&key)
+            .and_then(|memo| memo.downcast_ref::<Memo<U>>())
+            .filter(|memo| !memo.is_disposed())
+        {
+            return *memo;
+        }
+
+        let memo = match self.owner.as_ref().and_then(WeakOwner::upgrade) {
+            Some(owner) => owner.with(create),
+            None => create(),
+        };
+        memos.insert(key, Box::new(memo));
+        memo
+    }
+}
+
+/// Returns the memo for a computed field declared with `#[store(computed(...))]`.
+///
+/// The function receives the field that the computed field belongs to, so that it only tracks
+/// the subfields it actually reads. The memo is created the first time it is accessed for a
+/// given field of a store, and the same memo is returned after that.
+///
+/// # Usage
+///
+/// You shouldn't call this function from your code, since it's a part of
+/// implementation details of `reactive_stores`. It is exposed to implement
+/// computed fields in the derive `Store` macro.
+#[doc(hidden)]
+#[track_caller]
+pub fn computed_field<S, T, U>(
+    field: S,
+    name: &'static str,
+    fun: impl Fn(Field<T>) -> U + Send + Sync + 'static,
+) -> Memo<U>
+where
+    S: StoreField<Value = T> + Send + Sync + 'static,
+    T: 'static,
+    U: PartialEq + Send + Sync + 'static,
+{
+    let keys = field.keys();
+    let path = field.path().into_iter().collect::<StorePath>();
+    let create = move || {
+        let field = Field::from(ArcField::new(field));
+        Memo::new(move |_| fun(field))
+    };
+    match keys {
+        Some(keys) => keys.computed().get_or_create(path, name, create),
+        None => create(),
+    }
+}
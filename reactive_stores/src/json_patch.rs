@@ -0,0 +1,639 @@
+use crate::{ArcStore, Patch, PatchField, Store};
+use futures::{channel::mpsc::UnboundedReceiver, Stream};
+use or_poisoned::OrPoisoned;
+use reactive_graph::{
+    effect::Effect,
+    owner::{Owner, Storage, SyncStorage},
+    traits::{Dispose, ReadUntracked, Track},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use thiserror::Error;
+
+/// A single [RFC 6902](https://datatracker.ietf.org/doc/html/rfc6902) JSON Patch operation.
+///
+/// Paths are [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON Pointers into the
+/// serialized form of the store’s value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// Adds a value to an object or inserts it into an array.
+    Add {
+        /// The location at which to add the value.
+        path: String,
+        /// The value to add.
+        value: Value,
+    },
+    /// Removes the value at the given location.
+    Remove {
+        /// The location of the value to remove.
+        path: String,
+    },
+    /// Replaces the value at the given location.
+    Replace {
+        /// The location of the value to replace.
+        path: String,
+        /// The new value.
+        value: Value,
+    },
+    /// Removes the value at one location and adds it at another.
+    Move {
+        /// The location of the value to move.
+        from: String,
+        /// The location to move it to.
+        path: String,
+    },
+    /// Copies the value at one location to another.
+    Copy {
+        /// The location of the value to copy.
+        from: String,
+        /// The location to copy it to.
+        path: String,
+    },
+    /// Checks that the value at the given location is equal to the given value.
+    Test {
+        /// The location of the value to test.
+        path: String,
+        /// The expected value.
+        value: Value,
+    },
+}
+
+/// An error that occurred while applying a JSON Patch to a store.
+#[derive(Debug, Error)]
+pub enum JsonPatchError {
+    /// The value could not be converted to or from JSON.
+    #[error("could not convert the store value: {0}")]
+    Serde(#[from] serde_json::Error),
+    /// A path was not a valid JSON Pointer.
+    #[error("invalid JSON Pointer {0:?}")]
+    InvalidPointer(String),
+    /// A path did not point to an existing location.
+    #[error("no value at {0:?}")]
+    NotFound(String),
+    /// A `test` operation did not match.
+    #[error("test failed at {0:?}")]
+    TestFailed(String),
+    /// The store has been disposed.
+    #[error("the store has been disposed")]
+    Disposed,
+}
+
+/// The snapshots held by each active [`changes`](ArcStore::changes) stream for a store.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChangeLog(Arc<Mutex<Vec<Weak<Mutex<Value>>>>>);
+
+impl ChangeLog {
+    fn register(&self, snapshot: &Arc<Mutex<Value>>) {
+        let mut snapshots = self.0.lock().or_poisoned();
+        snapshots.retain(|snapshot| snapshot.strong_count() > 0);
+        snapshots.push(Arc::downgrade(snapshot));
+    }
+
+    fn snapshots(&self) -> Vec<Arc<Mutex<Value>>> {
+        self.0
+            .lock()
+            .or_poisoned()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect()
+    }
+}
+
+impl<T> ArcStore<T>
+where
+    T: Serialize + Send + Sync + 'static,
+{
+    /// Returns a [`Stream`] of the changes made to this store, as JSON Patch documents.
+    ///
+    /// Each item contains the operations needed to bring the serialized value from its previous
+    /// state to its current one. Like [`ToStream`](reactive_graph::traits::ToStream), this is
+    /// driven by an effect, so several writes made in the same tick are combined into a single
+    /// item. Changes made by [`apply_patch`](ArcStore::apply_patch) are not emitted, so that
+    /// applying remote changes does not echo them back to where they came from.
+    ///
+    /// The stream ends when the current [`Owner`] is cleaned up.
+    pub fn changes(&self) -> impl Stream<Item = Vec<PatchOperation>> + Send {
+        self.changes_receiver()
+    }
+
+    fn changes_receiver(&self) -> UnboundedReceiver<Vec<PatchOperation>> {
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+
+        let close_channel = tx.clone();
+        Owner::on_cleanup(move || close_channel.close_channel());
+
+        let snapshot = Arc::new(Mutex::new(self.to_json().unwrap_or_default()));
+        self.changes.register(&snapshot);
+
+        // the stream may be dropped without the owner being cleaned up (or there may be no owner)
+        // so the effect disposes of itself the next time it runs after that
+        let effect = Arc::new(OnceLock::<Effect<SyncStorage>>::new());
+        let handle = Effect::new_isomorphic({
+            let effect = Arc::clone(&effect);
+            let mut state = Some((self.clone(), snapshot));
+            move |_| {
+                if tx.is_closed() {
+                    state = None;
+                    if let Some(effect) = effect.get() {
+                        effect.dispose();
+                    }
+                }
+                let Some((this, snapshot)) = &state else {
+                    return;
+                };
+                this.track();
+                let mut snapshot = snapshot.lock().or_poisoned();
+                let Some(current) = this.to_json() else {
+                    return;
+                };
+                let mut ops = Vec::new();
+                diff(&snapshot, &current, &mut String::new(), &mut ops);
+                *snapshot = current;
+                if !ops.is_empty() {
+                    _ = tx.unbounded_send(ops);
+                }
+            }
+        });
+        _ = effect.set(handle);
+
+        rx
+    }
+
+    fn to_json(&self) -> Option<Value> {
+        let value = self.try_read_untracked()?;
+        serde_json::to_value(&*value).ok()
+    }
+}
+
+impl<T> ArcStore<T>
+where
+    T: Serialize + DeserializeOwned + PatchField + Send + Sync + 'static,
+{
+    /// Applies a JSON Patch document to the store.
+    ///
+    /// The operations are applied to the serialized value, which is then deserialized and
+    /// [patched](Patch::patch) into the store, so only the fields that have actually changed are
+    /// notified. If any operation fails, the store is left unchanged.
+    pub fn apply_patch(
+        &self,
+        ops: &[PatchOperation],
+    ) -> Result<(), JsonPatchError> {
+        self.apply_patch_with(ops, |_, new| new)
+    }
+
+    /// Applies a JSON Patch document to the store, resolving the result with a function.
+    ///
+    /// The function receives the current value and the value with the operations applied, and
+    /// returns the value that will be [patched](Patch::patch) into the store. This can be used to
+    /// merge changes received from elsewhere with local ones. Like the operations themselves, any
+    /// difference introduced by the function is not emitted by [`changes`](ArcStore::changes).
+    pub fn apply_patch_with(
+        &self,
+        ops: &[PatchOperation],
+        resolve: impl FnOnce(&T, T) -> T,
+    ) -> Result<(), JsonPatchError> {
+        let mut value = self.to_json().ok_or(JsonPatchError::Disposed)?;
+        apply(&mut value, ops)?;
+        let new = serde_json::from_value::<T>(value.clone())?;
+        let new = {
+            let current =
+                self.try_read_untracked().ok_or(JsonPatchError::Disposed)?;
+            resolve(&current, new)
+        };
+        let mut resolved = Vec::new();
+        diff(
+            &value,
+            &serde_json::to_value(&new)?,
+            &mut String::new(),
+            &mut resolved,
+        );
+
+        // hold the snapshot locks while patching, so that the change streams do not see the
+        // new value before their snapshots have been updated to include it
+        let snapshots = self.changes.snapshots();
+        let mut guards = snapshots
+            .iter()
+            .map(|snapshot| snapshot.lock().or_poisoned())
+            .collect::<Vec<_>>();
+        for snapshot in &mut guards {
+            let mut updated = snapshot.clone();
+            if apply(&mut updated, ops).is_ok()
+                && apply(&mut updated, &resolved).is_ok()
+            {
+                **snapshot = updated;
+            }
+        }
+        self.patch(new);
+        Ok(())
+    }
+}
+
+impl<T, S> Store<T, S>
+where
+    T: Serialize + Send + Sync + 'static,
+    S: Storage<ArcStore<T>>,
+{
+    /// Returns a [`Stream`] of the changes made to this store, as JSON Patch documents.
+    ///
+    /// See [`ArcStore::changes`].
+    pub fn changes(&self) -> impl Stream<Item = Vec<PatchOperation>> + Send {
+        match self.inner.try_get_value() {
+            Some(inner) => inner.changes_receiver(),
+            // the sender is dropped immediately, so the stream ends
+            None => futures::channel::mpsc::unbounded().1,
+        }
+    }
+}
+
+impl<T, S> Store<T, S>
+where
+    T: Serialize + DeserializeOwned + PatchField + Send + Sync + 'static,
+    S: Storage<ArcStore<T>>,
+{
+    /// Applies a JSON Patch document to the store.
+    ///
+    /// See [`ArcStore::apply_patch`].
+    pub fn apply_patch(
+        &self,
+        ops: &[PatchOperation],
+    ) -> Result<(), JsonPatchError> {
+        self.inner
+            .try_get_value()
+            .ok_or(JsonPatchError::Disposed)?
+            .apply_patch(ops)
+    }
+
+    /// Applies a JSON Patch document to the store, resolving the result with a function.
+    ///
+    /// See [`ArcStore::apply_patch_with`].
+    pub fn apply_patch_with(
+        &self,
+        ops: &[PatchOperation],
+        resolve: impl FnOnce(&T, T) -> T,
+    ) -> Result<(), JsonPatchError> {
+        self.inner
+            .try_get_value()
+            .ok_or(JsonPatchError::Disposed)?
+            .apply_patch_with(ops, resolve)
+    }
+}
+
+/// Computes the operations needed to turn `old` into `new`, appending them to `ops`.
+fn diff(
+    old: &Value,
+    new: &Value,
+    path: &mut String,
+    ops: &mut Vec<PatchOperation>,
+) {
+    if old == new {
+        return;
+    }
+    let len = path.len();
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for key in old.keys().filter(|key| !new.contains_key(*key)) {
+                push_segment(path, key);
+                ops.push(PatchOperation::Remove { path: path.clone() });
+                path.truncate(len);
+            }
+            for (key, new) in new {
+                push_segment(path, key);
+                match old.get(key) {
+                    Some(old) => diff(old, new, path, ops),
+                    None => ops.push(PatchOperation::Add {
+                        path: path.clone(),
+                        value: new.clone(),
+                    }),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for (idx, (old, new)) in old.iter().zip(new).enumerate() {
+                push_segment(path, &idx.to_string());
+                diff(old, new, path, ops);
+                path.truncate(len);
+            }
+            // remove from the end, so that the remaining indices stay valid
+            for idx in (new.len()..old.len()).rev() {
+                push_segment(path, &idx.to_string());
+                ops.push(PatchOperation::Remove { path: path.clone() });
+                path.truncate(len);
+            }
+            for (idx, new) in new.iter().enumerate().skip(old.len()) {
+                push_segment(path, &idx.to_string());
+                ops.push(PatchOperation::Add {
+                    path: path.clone(),
+                    value: new.clone(),
+                });
+                path.truncate(len);
+            }
+        }
+        _ => ops.push(PatchOperation::Replace {
+            path: path.clone(),
+            value: new.clone(),
+        }),
+    }
+}
+
+fn push_segment(path: &mut String, segment: &str) {
+    path.push('/');
+    for c in segment.chars() {
+        match c {
+            '~' => path.push_str("~0"),
+            '/' => path.push_str("~1"),
+            c => path.push(c),
+        }
+    }
+}
+
+/// Applies the operations to `value` in order. If any operation fails, `value` is left unchanged.
+fn apply(
+    value: &mut Value,
+    ops: &[PatchOperation],
+) -> Result<(), JsonPatchError> {
+    let mut patched = value.clone();
+    for op in ops {
+        match op {
+            PatchOperation::Add { path, value } => {
+                add(&mut patched, path, value.clone())?
+            }
+            PatchOperation::Remove { path } => {
+                remove(&mut patched, path)?;
+            }
+            PatchOperation::Replace { path, value } => {
+                *pointer_mut(&mut patched, path)? = value.clone();
+            }
+            PatchOperation::Move { from, path } => {
+                let moved = remove(&mut patched, from)?;
+                add(&mut patched, path, moved)?;
+            }
+            PatchOperation::Copy { from, path } => {
+                let copied = pointer_mut(&mut patched, from)?.clone();
+                add(&mut patched, path, copied)?;
+            }
+            PatchOperation::Test { path, value } => {
+                if pointer_mut(&mut patched, path)? != value {
+                    return Err(JsonPatchError::TestFailed(path.clone()));
+                }
+            }
+        }
+    }
+    *value = patched;
+    Ok(())
+}
+
+fn split_pointer(path: &str) -> Result<(&str, String), JsonPatchError> {
+    let (parent, last) = path
+        .rsplit_once('/')
+        .ok_or_else(|| JsonPatchError::InvalidPointer(path.to_string()))?;
+    Ok((parent, unescape(last)))
+}
+
+fn unescape(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+fn pointer_mut<'a>(
+    value: &'a mut Value,
+    path: &str,
+) -> Result<&'a mut Value, JsonPatchError> {
+    if !path.is_empty() && !path.starts_with('/') {
+        return Err(JsonPatchError::InvalidPointer(path.to_string()));
+    }
+    value
+        .pointer_mut(path)
+        .ok_or_else(|| JsonPatchError::NotFound(path.to_string()))
+}
+
+fn add(
+    value: &mut Value,
+    path: &str,
+    new: Value,
+) -> Result<(), JsonPatchError> {
+    if path.is_empty() {
+        *value = new;
+        return Ok(());
+    }
+    let (parent, key) = split_pointer(path)?;
+    match pointer_mut(value, parent)? {
+        Value::Object(map) => {
+            map.insert(key, new);
+        }
+        Value::Array(items) => {
+            let idx = if key == "-" {
+                items.len()
+            } else {
+                key.parse::<usize>()
+                    .ok()
+                    .filter(|idx| *idx <= items.len())
+                    .ok_or_else(|| JsonPatchError::NotFound(path.to_string()))?
+            };
+            items.insert(idx, new);
+        }
+        _ => return Err(JsonPatchError::NotFound(path.to_string())),
+    }
+    Ok(())
+}
+
+fn remove(value: &mut Value, path: &str) -> Result<Value, JsonPatchError> {
+    let (parent, key) = split_pointer(path)?;
+    let removed = match pointer_mut(value, parent)? {
+        Value::Object(map) => map.remove(&key),
+        Value::Array(items) => key
+            .parse::<usize>()
+            .ok()
+            .filter(|idx| *idx < items.len())
+            .map(|idx| items.remove(idx)),
+        _ => None,
+    };
+    removed.ok_or_else(|| JsonPatchError::NotFound(path.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply, diff, PatchOperation};
+    use crate::{self as reactive_stores, ArcStore, Patch, Store};
+    use futures::StreamExt;
+    use reactive_graph::{
+        effect::Effect,
+        owner::Owner,
+        traits::{Get, Read, Set, Write},
+    };
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Debug, Clone, Store, Patch, Serialize, Deserialize)]
+    struct Doc {
+        title: String,
+        tags: Vec<String>,
+        meta: Meta,
+    }
+
+    #[derive(Debug, Clone, Store, Patch, Serialize, Deserialize)]
+    struct Meta {
+        views: u32,
+        #[serde(rename = "a/b")]
+        odd_name: bool,
+    }
+
+    fn doc() -> Doc {
+        Doc {
+            title: "Hello".into(),
+            tags: vec!["a".into(), "b".into()],
+            meta: Meta {
+                views: 0,
+                odd_name: false,
+            },
+        }
+    }
+
+    #[test]
+    fn diff_and_apply_round_trip() {
+        let old = json!({ "a": 1, "b": [1, 2, 3], "c": { "d~": true } });
+        let new = json!({ "a": 2, "b": [1], "c": { "d~": false, "e": null } });
+        let mut ops = Vec::new();
+        diff(&old, &new, &mut String::new(), &mut ops);
+        assert!(ops.contains(&PatchOperation::Replace {
+            path: "/c/d~0".into(),
+            value: json!(false),
+        }));
+        let mut patched = old.clone();
+        apply(&mut patched, &ops).unwrap();
+        assert_eq!(patched, new);
+    }
+
+    #[test]
+    fn failed_patch_leaves_value_unchanged() {
+        let mut value = json!({ "a": 1 });
+        let ops = vec![
+            PatchOperation::Replace {
+                path: "/a".into(),
+                value: json!(2),
+            },
+            PatchOperation::Test {
+                path: "/a".into(),
+                value: json!(3),
+            },
+        ];
+        assert!(apply(&mut value, &ops).is_err());
+        assert_eq!(value, json!({ "a": 1 }));
+    }
+
+    #[tokio::test]
+    async fn changes_are_emitted_as_json_patch() {
+        _ = any_spawner::Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+
+        let store = Store::new(doc());
+        let mut changes = store.changes();
+        crate::tests::tick().await;
+
+        store.meta().views().set(1);
+        store.tags().write().push("c".into());
+        crate::tests::tick().await;
+
+        // both writes were made in the same tick, so they arrive together
+        let ops = changes.next().await.unwrap();
+        assert_eq!(ops.len(), 2);
+        assert!(ops.contains(&PatchOperation::Replace {
+            path: "/meta/views".into(),
+            value: json!(1),
+        }));
+        assert!(ops.contains(&PatchOperation::Add {
+            path: "/tags/2".into(),
+            value: json!("c"),
+        }));
+
+        store.meta().odd_name().set(true);
+        let ops = changes.next().await.unwrap();
+        assert_eq!(
+            ops,
+            vec![PatchOperation::Replace {
+                path: "/meta/a~1b".into(),
+                value: json!(true),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn dropped_change_streams_stop_tracking_the_store() {
+        _ = any_spawner::Executor::init_tokio();
+
+        // no owner, so nothing else will stop the effect
+        let store = ArcStore::new(doc());
+        let changes = store.changes();
+        crate::tests::tick().await;
+        assert_eq!(store.changes.snapshots().len(), 1);
+
+        drop(changes);
+        store.clone().title().set("Bye".into());
+        crate::tests::tick().await;
+        assert!(store.changes.snapshots().is_empty());
+    }
+
+    #[tokio::test]
+    async fn applying_a_patch_only_notifies_changed_fields() {
+        _ = any_spawner::Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+
+        let store = Store::new(doc());
+        let mut changes = store.changes();
+        let title_runs = Arc::new(AtomicUsize::new(0));
+        let views_runs = Arc::new(AtomicUsize::new(0));
+
+        Effect::new_sync({
+            let title_runs = Arc::clone(&title_runs);
+            move |_| {
+                store.title().read();
+                title_runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let views_runs = Arc::clone(&views_runs);
+            move |_| {
+                store.meta().views().read();
+                views_runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        crate::tests::tick().await;
+
+        store
+            .apply_patch(&[PatchOperation::Replace {
+                path: "/meta/views".into(),
+                value: json!(10),
+            }])
+            .unwrap();
+        crate::tests::tick().await;
+
+        assert_eq!(store.meta().views().get(), 10);
+        assert_eq!(title_runs.load(Ordering::Relaxed), 1);
+        assert_eq!(views_runs.load(Ordering::Relaxed), 2);
+
+        // remote changes are not echoed back, but local ones still are
+        store.title().set("Bye".into());
+        let ops = changes.next().await.unwrap();
+        assert_eq!(
+            ops,
+            vec![PatchOperation::Replace {
+                path: "/title".into(),
+                value: json!("Bye"),
+            }]
+        );
+
+        assert!(store
+            .apply_patch(&[PatchOperation::Remove {
+                path: "/missing".into(),
+            }])
+            .is_err());
+        assert_eq!(store.title().get(), "Bye");
+    }
+}
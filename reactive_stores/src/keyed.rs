@@ -82,6 +82,69 @@ impl<K: Hash + Eq, V> KeyedAccess<K> for std::collections::HashMap<K, V> {
     }
 }
 
+impl<K: Hash + Eq, V> KeyedAccess<K> for indexmap::IndexMap<K, V> {
+    type Value = V;
+    fn keyed(&self, _index: usize, key: &K) -> &Self::Value {
+        self.get(key).expect("key does not exist")
+    }
+    fn keyed_mut(&mut self, _index: usize, key: &K) -> &mut Self::Value {
+        self.get_mut(key).expect("key does not exist")
+    }
+}
+
+/// A keyed collection whose entries are looked up, inserted and removed by their key, like a map.
+///
+/// This allows a [`KeyedSubfield`] for the collection to [`get`](KeyedSubfield::get),
+/// [`insert`](KeyedSubfield::insert) and [`remove`](KeyedSubfield::remove) individual entries,
+/// only notifying the entry that changed. The key returned by the field's key function should
+/// be the key of the entry in the map.
+pub trait KeyedMap<K>: KeyedAccess<K> {
+    /// Returns `true` if the collection contains an entry for the key.
+    fn contains_keyed(&self, key: &K) -> bool;
+    /// Inserts a value at the key, returning the previous value if there was one.
+    fn insert_keyed(
+        &mut self,
+        key: K,
+        value: Self::Value,
+    ) -> Option<Self::Value>;
+    /// Removes the entry for the key, returning its value if there was one.
+    fn remove_keyed(&mut self, key: &K) -> Option<Self::Value>;
+}
+impl<K: Ord, V> KeyedMap<K> for std::collections::BTreeMap<K, V> {
+    fn contains_keyed(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+    fn insert_keyed(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+    fn remove_keyed(&mut self, key: &K) -> Option<V> {
+        self.remove(key)
+    }
+}
+impl<K: Hash + Eq, V> KeyedMap<K> for std::collections::HashMap<K, V> {
+    fn contains_keyed(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+    fn insert_keyed(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+    fn remove_keyed(&mut self, key: &K) -> Option<V> {
+        self.remove(key)
+    }
+}
+impl<K: Hash + Eq, V> KeyedMap<K> for indexmap::IndexMap<K, V> {
+    fn contains_keyed(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+    fn insert_keyed(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+    fn remove_keyed(&mut self, key: &K) -> Option<V> {
+        // keeps the order of the remaining entries
+        self.shift_remove(key)
+    }
+}
+
 /// Provides access to a subfield that contains some kind of keyed collection.
 #[derive(Debug)]
 pub struct KeyedSubfield<Inner, Prev, K, T>
@@ -808,6 +871,98 @@ where
     }
 }
 
+impl<Inner, Prev, K, T> KeyedSubfield<Inner, Prev, K, T>
+where
+    Self: Clone,
+    for<'a> &'a T: IntoIterator,
+    Inner: StoreField<Value = Prev>,
+    Prev: 'static,
+    K: Debug + Send + Sync + PartialEq + Eq + Hash + 'static,
+{
+    /// Notifies anything that iterates over this collection that its structure (the set or
+    /// order of its keys) has changed.
+    ///
+    /// This only notifies `children` (not `this`) at the collection path, so that individual
+    /// keyed items, which track `this` on all ancestor paths, are not notified.
+    pub(crate) fn notify_structure_changed(&self) {
+        let mut path = self.path_unkeyed().into_iter().collect::<StorePath>();
+        self.get_trigger_unkeyed(path.clone()).children.notify();
+        while !path.is_empty() {
+            path.pop();
+            self.get_trigger_unkeyed(path.clone()).children.notify();
+        }
+    }
+}
+
+impl<Inner, Prev, K, T> KeyedSubfield<Inner, Prev, K, T>
+where
+    Self: Clone,
+    for<'a> &'a T: IntoIterator,
+    Inner: StoreField<Value = Prev>,
+    Prev: 'static,
+    K: Clone + Debug + Send + Sync + PartialEq + Eq + Hash + 'static,
+    T: KeyedMap<K> + 'static,
+    T::Value: Sized,
+{
+    /// Returns the entry at the key, if there is one.
+    ///
+    /// This subscribes to the entry at the key. If there is no entry, it instead subscribes to
+    /// the collection, so that it is notified when an entry is inserted.
+    pub fn get_key(&self, key: &K) -> Option<AtKeyed<Inner, Prev, K, T>> {
+        let exists = self
+            .reader()
+            .map(|map| map.contains_keyed(key))
+            .unwrap_or(false);
+        if exists {
+            let entry = self.at_key(key.clone());
+            entry.track_field();
+            Some(entry)
+        } else {
+            self.track_field();
+            None
+        }
+    }
+
+    /// Inserts a value at the key, returning the previous value if there was one.
+    ///
+    /// If there was already an entry at the key, only that entry is notified. Otherwise, only
+    /// the collection itself is notified, so that iterating over it picks up the new entry.
+    pub fn insert(&self, key: K, value: T::Value) -> Option<T::Value> {
+        let prev = {
+            let mut writer = self.writer()?;
+            writer.untrack();
+            writer.insert_keyed(key.clone(), value)
+        };
+        self.update_keys();
+        if prev.is_some() {
+            self.at_key(key).triggers_for_current_path().notify();
+        } else {
+            self.notify_structure_changed();
+        }
+        prev
+    }
+
+    /// Removes the entry at the key, returning its value if there was one.
+    ///
+    /// This notifies the removed entry and the collection, but not any of the other entries.
+    pub fn remove(&self, key: &K) -> Option<T::Value> {
+        // the path for this key can only be resolved while it is still in the collection
+        let triggers = self
+            .reader()
+            .filter(|map| map.contains_keyed(key))
+            .map(|_| self.at_key(key.clone()).triggers_for_current_path())?;
+        let removed = {
+            let mut writer = self.writer()?;
+            writer.untrack();
+            writer.remove_keyed(key)
+        };
+        self.update_keys();
+        triggers.notify();
+        self.notify_structure_changed();
+        removed
+    }
+}
+
 impl<Inner, Prev, K, T> IntoIterator for KeyedSubfield<Inner, Prev, K, T>
 where
     Self: Clone,
@@ -886,6 +1041,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{self as reactive_stores, tests::tick, AtKeyed, Store};
+    use indexmap::IndexMap;
     use reactive_graph::{
         effect::Effect,
         traits::{Get, GetUntracked, ReadUntracked, Set, Track, Write},
@@ -1338,6 +1494,145 @@ mod tests {
         assert!(missing.is_none(), "faulty key should return none.")
     }
 
+    #[derive(Debug, Store, Default, Patch)]
+    struct TodoIndexMap {
+        #[store(key: usize = |(key, _)| *key)]
+        todos: IndexMap<usize, Todo>,
+    }
+
+    #[tokio::test]
+    async fn map_entries_can_be_inserted_and_removed_by_key() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(TodoHashMap::test_data());
+        let todos = store.todos();
+
+        let iter_count = Arc::new(AtomicUsize::new(0));
+        let a_count = Arc::new(AtomicUsize::new(0));
+        let new_count = Arc::new(AtomicUsize::new(0));
+        let a_label = Arc::new(std::sync::Mutex::new(None));
+        let new_label = Arc::new(std::sync::Mutex::new(None));
+
+        Effect::new_sync({
+            let iter_count = Arc::clone(&iter_count);
+            move || {
+                for _ in todos {}
+                iter_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let a_count = Arc::clone(&a_count);
+            let a_label = Arc::clone(&a_label);
+            move || {
+                *a_label.lock().unwrap() =
+                    todos.get_key(&"A".to_string()).map(|a| a.label().get());
+                a_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let new_count = Arc::clone(&new_count);
+            let new_label = Arc::clone(&new_label);
+            move || {
+                *new_label.lock().unwrap() =
+                    todos.get_key(&"New".to_string()).map(|a| a.label().get());
+                new_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        tick().await;
+        assert_eq!(iter_count.load(Ordering::Relaxed), 1);
+        assert_eq!(a_count.load(Ordering::Relaxed), 1);
+        assert_eq!(new_count.load(Ordering::Relaxed), 1);
+        assert_eq!(a_label.lock().unwrap().as_deref(), Some("A"));
+        assert_eq!(*new_label.lock().unwrap(), None);
+
+        // replacing an existing entry only notifies that entry and the collection
+        let prev = todos.insert("B".to_string(), Todo::new(11, "B2"));
+        assert_eq!(prev, Some(Todo::new(11, "B")));
+        tick().await;
+        assert_eq!(iter_count.load(Ordering::Relaxed), 2);
+        assert_eq!(a_count.load(Ordering::Relaxed), 1);
+
+        // inserting a new entry notifies the collection
+        assert_eq!(todos.insert("New".to_string(), Todo::new(13, "New")), None);
+        tick().await;
+        assert_eq!(iter_count.load(Ordering::Relaxed), 3);
+        assert_eq!(a_count.load(Ordering::Relaxed), 1);
+        assert_eq!(new_label.lock().unwrap().as_deref(), Some("New"));
+
+        // removing an entry notifies it, but not its siblings
+        assert_eq!(todos.remove(&"A".to_string()), Some(Todo::new(10, "A")));
+        tick().await;
+        assert_eq!(iter_count.load(Ordering::Relaxed), 4);
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(*a_label.lock().unwrap(), None);
+        let new_runs = new_count.load(Ordering::Relaxed);
+
+        // removing a missing key does nothing
+        assert_eq!(todos.remove(&"A".to_string()), None);
+        tick().await;
+        assert_eq!(iter_count.load(Ordering::Relaxed), 4);
+        assert_eq!(new_count.load(Ordering::Relaxed), new_runs);
+    }
+
+    #[tokio::test]
+    async fn indexmap_keyed_fields_keep_order_and_patch_by_key() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(TodoIndexMap {
+            todos: [Todo::new(10, "A"), Todo::new(11, "B"), Todo::new(12, "C")]
+                .into_iter()
+                .map(|todo| (todo.id, todo))
+                .collect(),
+        });
+        let labels = || {
+            store
+                .todos()
+                .into_iter()
+                .map(|todo| todo.label().get_untracked())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(), ["A", "B", "C"]);
+
+        let b_count = Arc::new(AtomicUsize::new(0));
+        let c_count = Arc::new(AtomicUsize::new(0));
+        let b = store.todos().at_key(11);
+        let c = store.todos().at_key(12);
+        Effect::new_sync({
+            let b_count = Arc::clone(&b_count);
+            move || {
+                _ = b.try_get();
+                b_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let c_count = Arc::clone(&c_count);
+            move || {
+                c.track();
+                c_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        tick().await;
+
+        // patching moves and removes entries without notifying unchanged ones
+        store.todos().patch(
+            [Todo::new(12, "C"), Todo::new(10, "A2")]
+                .into_iter()
+                .map(|todo| (todo.id, todo))
+                .collect(),
+        );
+        tick().await;
+        assert_eq!(labels(), ["C", "A2"]);
+        assert_eq!(b_count.load(Ordering::Relaxed), 2);
+        assert_eq!(c_count.load(Ordering::Relaxed), 1);
+        assert_eq!(b.try_get(), None);
+
+        // removing keeps the order of the other entries
+        store.todos().insert(13, Todo::new(13, "D"));
+        store.todos().remove(&12);
+        assert_eq!(labels(), ["A2", "D"]);
+    }
+
     #[test]
     fn non_usize_keys_work_for_vec() {
         #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -22,6 +22,15 @@
 //! [`.patch()`](Patch::patch) method, which allows you to provide an entirely new value, but only
 //! notify fields that have changed.
 //!
+//! With the `json-patch` feature, [`ArcStore::changes`] returns a stream of the changes made to a
+//! store as [RFC 6902](https://datatracker.ietf.org/doc/html/rfc6902) JSON Patch operations, and
+//! [`ArcStore::apply_patch`] applies operations received from elsewhere, only notifying the
+//! fields they change. This can be used to keep a store in sync with a server or other clients.
+//!
+//! The [`Validate`](macro@Validate) macro declares validation rules for the fields of a struct,
+//! and [`FormState`] uses them (or rules built with [`Validators`]) to keep track of the errors,
+//! dirty state and touched state of each field of a store that backs a form.
+//!
 //! Updating a field will notify its parents and children, but not its siblings.
 //!
 //! Stores can therefore
@@ -155,6 +164,32 @@
 //! assert_eq!(store.vec_field().at_unkeyed(1).get(), 2);
 //! assert_eq!(store.vec_field().at_unkeyed(2).get(), 3);
 //! ```
+//! #### HashMap, BTreeMap and IndexMap
+//! Maps can be used as keyed fields by annotating them with a key function that returns the
+//! key of each entry. The [`KeyedSubfield`] for the map can then [`get_key`](KeyedSubfield::get_key),
+//! [`insert`](KeyedSubfield::insert) and [`remove`](KeyedSubfield::remove) entries by key, and
+//! only notifies the entry that changed (and anything that iterates over the map). For example:
+//! ```rust
+//! use reactive_stores::Store;
+//! use reactive_graph::traits::Get;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Store)]
+//! struct Inventory {
+//!     #[store(key: String = |(name, _)| name.clone())]
+//!     items: HashMap<String, u32>,
+//! }
+//!
+//! let store = Store::new(Inventory { items: HashMap::new() });
+//!
+//! store.items().insert("apples".to_string(), 3);
+//! assert_eq!(store.items().get_key(&"apples".to_string()).unwrap().get(), 3);
+//! assert_eq!(store.items().remove(&"apples".to_string()), Some(3));
+//! assert!(store.items().get_key(&"apples".to_string()).is_none());
+//!
+//! // the whole map can still be read with `Get`
+//! assert!(store.items().get().is_empty());
+//! ```
 //! #### Enum
 //! Enumerated types behave a bit differently as the [`Store`](macro@Store) macro builds underlying traits instead of alternate
 //! enumerated structures.  Each element in an `Enum` generates methods to access it in the store: a
@@ -201,6 +236,40 @@
 //!
 //! assert_eq!(tree.child().unwrap().deref_field().value().get(), 2);
 //! ```
+//! ### Computed fields
+//!
+//! Derived values can be declared on the type with `#[store(computed(name: Type = fun))]`. This
+//! adds a method that returns a [`Memo`](reactive_graph::computed::Memo) to the generated trait.
+//! `fun` receives a [`Field`] for the whole value, so the memo only tracks (and recomputes when)
+//! the fields it actually reads. The memo is created the first time it is accessed, and every
+//! later call on the same field of the same store returns it.
+//!
+//! ```rust
+//! # use reactive_graph::traits::{Get, Set};
+//! # use reactive_stores::{Field, Store};
+//! #[derive(Store)]
+//! #[store(computed(full_name: String = full_name))]
+//! struct Person {
+//!     first: String,
+//!     last: String,
+//!     age: u32,
+//! }
+//!
+//! fn full_name(person: Field<Person>) -> String {
+//!     format!("{} {}", person.first().get(), person.last().get())
+//! }
+//!
+//! let person = Store::new(Person {
+//!     first: "Ada".to_string(),
+//!     last: "Lovelace".to_string(),
+//!     age: 36,
+//! });
+//! let name = person.full_name();
+//! assert_eq!(name.get(), "Ada Lovelace");
+//!
+//! // `age` is not read by `full_name`, so this does not cause it to recompute
+//! person.age().set(37);
+//! ```
 //! ### Implementation Notes
 //!
 //! Every struct field can be understood as an index. For example, given the following definition
@@ -251,7 +320,7 @@ use reactive_graph::{
         UntrackableGuard, Write,
     },
 };
-pub use reactive_stores_macro::{Patch, Store};
+pub use reactive_stores_macro::{Patch, Store, Validate};
 use rustc_hash::FxHashMap;
 use std::{
     any::Any,
@@ -263,9 +332,12 @@ use std::{
 };
 
 mod arc_field;
+mod computed;
 mod deref;
 mod field;
 mod iter;
+#[cfg(feature = "json-patch")]
+mod json_patch;
 mod keyed;
 mod len;
 mod option;
@@ -277,11 +349,14 @@ mod serde;
 mod slotmap;
 mod store_field;
 mod subfield;
+mod validation;
 
 pub use arc_field::ArcField;
 pub use deref::*;
 pub use field::Field;
 pub use iter::*;
+#[cfg(feature = "json-patch")]
+pub use json_patch::{JsonPatchError, PatchOperation};
 pub use keyed::*;
 pub use len::Len;
 pub use option::*;
@@ -289,6 +364,14 @@ pub use patch::*;
 pub use path::{StorePath, StorePathSegment};
 pub use store_field::StoreField;
 pub use subfield::Subfield;
+pub use validation::{FormState, Validate, ValidationErrors, Validators};
+
+/// Items used by the code generated by the derive macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::computed::computed_field;
+    pub use reactive_graph::computed::Memo;
+}
 
 #[derive(Debug, Default)]
 struct TriggerMap(FxHashMap<StorePath, StoreFieldTrigger>);
@@ -436,9 +519,15 @@ pub struct KeyMap(
     Map<StorePath, Box<dyn Any + Send + Sync>>,
     /// Map index -> key
     Map<(StorePath, usize), StorePathSegment>,
+    /// Memos for computed fields
+    computed::ComputedMemos,
 );
 
 impl KeyMap {
+    pub(crate) fn computed(&self) -> &computed::ComputedMemos {
+        &self.2
+    }
+
     /// Transforms the keys related to the field identified by `path`.
     ///
     /// # Arguments
@@ -545,6 +634,8 @@ pub struct ArcStore<T> {
     pub(crate) value: Arc<RwLock<T>>,
     signals: Arc<RwLock<TriggerMap>>,
     keys: KeyMap,
+    #[cfg(feature = "json-patch")]
+    changes: json_patch::ChangeLog,
 }
 
 impl<T> ArcStore<T> {
@@ -556,6 +647,8 @@ impl<T> ArcStore<T> {
             value: Arc::new(RwLock::new(value)),
             signals: Default::default(),
             keys: Default::default(),
+            #[cfg(feature = "json-patch")]
+            changes: Default::default(),
         }
     }
 }
@@ -585,6 +678,8 @@ impl<T> Clone for ArcStore<T> {
             value: Arc::clone(&self.value),
             signals: Arc::clone(&self.signals),
             keys: self.keys.clone(),
+            #[cfg(feature = "json-patch")]
+            changes: self.changes.clone(),
         }
     }
 }
@@ -855,7 +950,7 @@ mod tests {
     use reactive_graph::{
         effect::Effect,
         owner::StoredValue,
-        traits::{Read, ReadUntracked, Set, Track, Update, Write},
+        traits::{Get, Read, ReadUntracked, Set, Track, Update, Write},
     };
     use std::sync::{
         atomic::{AtomicUsize, Ordering},
@@ -1204,6 +1299,385 @@ mod tests {
         assert_eq!(combined_count.load(Ordering::Relaxed), 3);
     }
 
+    #[derive(Debug, Clone, Store, Patch, PartialEq)]
+    enum Status {
+        Idle,
+        Loading { progress: u8, label: String },
+        Failed(String, u8),
+    }
+
+    #[derive(Debug, Clone, Store, Patch)]
+    struct Job {
+        status: Status,
+    }
+
+    #[tokio::test]
+    async fn patching_enum_with_same_variant_only_notifies_changed_field() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let variant_count = Arc::new(AtomicUsize::new(0));
+        let progress_count = Arc::new(AtomicUsize::new(0));
+        let label_count = Arc::new(AtomicUsize::new(0));
+
+        let store = Store::new(Job {
+            status: Status::Loading {
+                progress: 0,
+                label: "download".into(),
+            },
+        });
+
+        Effect::new_sync({
+            let variant_count = Arc::clone(&variant_count);
+            move |_| {
+                store.status().loading();
+                variant_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let progress_count = Arc::clone(&progress_count);
+            move |_| {
+                if let Some(progress) = store.status().loading_progress() {
+                    progress.track();
+                }
+                progress_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let label_count = Arc::clone(&label_count);
+            move |_| {
+                if let Some(label) = store.status().loading_label() {
+                    label.track();
+                }
+                label_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        tick().await;
+
+        store.patch(Job {
+            status: Status::Loading {
+                progress: 50,
+                label: "download".into(),
+            },
+        });
+        tick().await;
+        assert_eq!(variant_count.load(Ordering::Relaxed), 1);
+        assert_eq!(progress_count.load(Ordering::Relaxed), 2);
+        assert_eq!(label_count.load(Ordering::Relaxed), 1);
+
+        store.patch(Job {
+            status: Status::Loading {
+                progress: 50,
+                label: "download".into(),
+            },
+        });
+        tick().await;
+        assert_eq!(variant_count.load(Ordering::Relaxed), 1);
+        assert_eq!(progress_count.load(Ordering::Relaxed), 2);
+        assert_eq!(label_count.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            store.status().get(),
+            Status::Loading {
+                progress: 50,
+                label: "download".into(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn patching_enum_with_new_variant_notifies_discriminant() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let variant_count = Arc::new(AtomicUsize::new(0));
+        let message_count = Arc::new(AtomicUsize::new(0));
+
+        let store = Store::new(Job {
+            status: Status::Idle,
+        });
+
+        Effect::new_sync({
+            let variant_count = Arc::clone(&variant_count);
+            move |_| {
+                store.status().failed();
+                variant_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let message_count = Arc::clone(&message_count);
+            move |_| {
+                if let Some(message) = store.status().failed_0() {
+                    message.track();
+                }
+                message_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        tick().await;
+
+        store.patch(Job {
+            status: Status::Failed("timed out".into(), 1),
+        });
+        tick().await;
+        assert_eq!(variant_count.load(Ordering::Relaxed), 2);
+        assert_eq!(message_count.load(Ordering::Relaxed), 2);
+        assert!(store.status().failed());
+
+        // same variant: only the retry count changes
+        store.patch(Job {
+            status: Status::Failed("timed out".into(), 2),
+        });
+        tick().await;
+        assert_eq!(variant_count.load(Ordering::Relaxed), 2);
+        assert_eq!(message_count.load(Ordering::Relaxed), 2);
+        assert_eq!(store.status().failed_1().map(|n| n.get()), Some(2));
+
+        store.patch(Job {
+            status: Status::Idle,
+        });
+        tick().await;
+        assert_eq!(variant_count.load(Ordering::Relaxed), 3);
+        assert_eq!(message_count.load(Ordering::Relaxed), 3);
+        assert!(store.status().idle());
+    }
+
+    #[derive(Debug, Clone, Store)]
+    #[store(computed(full_name: String = full_name), computed(is_adult: bool = |person: crate::Field<Person>| person.age().get() >= 18))]
+    struct Person {
+        first: String,
+        last: String,
+        age: u32,
+    }
+
+    static FULL_NAME_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    fn full_name(person: crate::Field<Person>) -> String {
+        FULL_NAME_RUNS.fetch_add(1, Ordering::Relaxed);
+        format!("{} {}", person.first().get(), person.last().get())
+    }
+
+    #[tokio::test]
+    async fn computed_fields_only_recompute_when_read_fields_change() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(Person {
+            first: "Ada".into(),
+            last: "Byron".into(),
+            age: 17,
+        });
+        let name = store.full_name();
+        let is_adult = store.is_adult();
+
+        assert_eq!(name.get(), "Ada Byron");
+        assert!(!is_adult.get());
+        assert_eq!(FULL_NAME_RUNS.load(Ordering::Relaxed), 1);
+
+        // later calls share the same memo
+        assert_eq!(store.full_name().get(), "Ada Byron");
+        assert_eq!(FULL_NAME_RUNS.load(Ordering::Relaxed), 1);
+
+        store.age().set(36);
+        assert_eq!(name.get(), "Ada Byron");
+        assert!(is_adult.get());
+        assert_eq!(FULL_NAME_RUNS.load(Ordering::Relaxed), 1);
+
+        store.last().set("Lovelace".into());
+        assert_eq!(name.get(), "Ada Lovelace");
+        assert_eq!(FULL_NAME_RUNS.load(Ordering::Relaxed), 2);
+
+        // replacing the whole value notifies every field
+        store.set(Person {
+            first: "Grace".into(),
+            last: "Hopper".into(),
+            age: 85,
+        });
+        assert_eq!(name.get(), "Grace Hopper");
+        assert_eq!(FULL_NAME_RUNS.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn computed_fields_belong_to_the_store_owner() {
+        use reactive_graph::{owner::Owner, traits::IsDisposed};
+
+        let owner = Owner::new();
+        owner.set();
+        let store = Store::new(Person {
+            first: "Ada".into(),
+            last: "Lovelace".into(),
+            age: 36,
+        });
+
+        // the first access happens in a child that is then cleaned up
+        let child = owner.child();
+        let is_adult = child.with(|| store.is_adult());
+        child.cleanup();
+
+        assert!(!is_adult.is_disposed());
+        assert_eq!(store.is_adult(), is_adult);
+    }
+
+    fn required(value: &str) -> Result<(), &'static str> {
+        if value.trim().is_empty() {
+            Err("required")
+        } else {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Store, Patch, crate::Validate, Clone, Default)]
+    struct Order {
+        #[validate(required)]
+        customer: String,
+        #[validate(nested)]
+        lines: Vec<OrderLine>,
+    }
+
+    #[derive(Debug, Store, Patch, crate::Validate, Clone, Default)]
+    struct OrderLine {
+        #[validate(required)]
+        product: String,
+        #[validate(|n: &u32| if *n == 0 { Err("must order at least one") } else { Ok(()) })]
+        quantity: u32,
+    }
+
+    #[test]
+    fn validate_reports_errors_at_field_paths() {
+        use crate::{StorePath, Validate};
+
+        let order = Order {
+            customer: "".into(),
+            lines: vec![
+                OrderLine {
+                    product: "tea".into(),
+                    quantity: 2,
+                },
+                OrderLine {
+                    product: "".into(),
+                    quantity: 0,
+                },
+            ],
+        };
+        let errors = order.validate().unwrap_err();
+        let path = |segments: &[usize]| {
+            segments.iter().map(Into::into).collect::<StorePath>()
+        };
+        assert_eq!(errors.get(&path(&[0])), ["required"]);
+        assert!(errors.get(&path(&[1, 0, 0])).is_empty());
+        assert_eq!(errors.get(&path(&[1, 1, 0])), ["required"]);
+        assert_eq!(errors.get(&path(&[1, 1, 1])), ["must order at least one"]);
+        assert!(errors.contains(&path(&[1])));
+        assert!(!errors.contains(&path(&[1, 0])));
+        assert_eq!(
+            errors.to_string(),
+            "validation failed: required; required; must order at least one"
+        );
+    }
+
+    #[tokio::test]
+    async fn form_state_tracks_errors_dirty_and_touched_fields() {
+        use crate::{FormState, Validators};
+
+        _ = any_spawner::Executor::init_tokio();
+
+        let validators = Validators::<Order>::derived().rule(
+            |order| order.lines(),
+            |order: &Order| {
+                if order.lines.is_empty() {
+                    Err("add at least one line")
+                } else {
+                    Ok(())
+                }
+            },
+        );
+        let form = FormState::with_validators(Order::default(), validators);
+        let store = form.store();
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        Effect::new_sync({
+            let runs = Arc::clone(&runs);
+            move |_| {
+                form.is_valid_field(store.customer());
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        tick().await;
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+
+        assert!(!form.is_valid());
+        assert_eq!(form.errors_for(store.customer()), ["required"]);
+        assert_eq!(form.errors_for(store.lines()), ["add at least one line"]);
+        assert!(!form.is_dirty());
+
+        store.customer().set("Ada".into());
+        tick().await;
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+        assert!(form.is_valid_field(store.customer()));
+        assert!(form.is_dirty_field(store.customer()));
+        assert!(!form.is_dirty_field(store.lines()));
+
+        store.lines().write().push(OrderLine::default());
+        assert!(!form.is_valid_field(store.lines()));
+        assert!(form.is_valid_field(store.customer()));
+
+        // the errors changed, but not the ones for the customer field
+        tick().await;
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+        assert!(form.is_dirty_field(store.lines().at_unkeyed(0).product()));
+
+        assert!(!form.is_touched(store.customer()));
+        form.touch(store.customer());
+        assert!(form.is_touched(store.customer()));
+        assert!(!form.is_touched(store.lines()));
+        assert!(!form.validate());
+        assert!(form.is_touched(store.lines().at_unkeyed(0).quantity()));
+
+        form.reset();
+        assert!(!form.is_dirty());
+        assert!(!form.is_touched(store.customer()));
+        assert_eq!(store.customer().read_untracked().as_str(), "");
+    }
+
+    #[test]
+    fn validators_run_on_plain_values() {
+        use crate::Validators;
+
+        let validators = Validators::<OrderLine>::derived().field(
+            |line| line.quantity(),
+            |n: &u32| if *n > 10 { Err("too many") } else { Ok(()) },
+        );
+        let line = validators
+            .validate(OrderLine {
+                product: "tea".into(),
+                quantity: 3,
+            })
+            .unwrap();
+        assert_eq!(line.quantity, 3);
+        let errors = validators
+            .validate(OrderLine {
+                product: "tea".into(),
+                quantity: 30,
+            })
+            .unwrap_err();
+        assert_eq!(errors.to_string(), "validation failed: too many");
+    }
+
+    #[test]
+    fn validators_can_keep_the_store_alive() {
+        use crate::Validators;
+        use std::sync::Mutex;
+
+        let kept = Arc::new(Mutex::new(Vec::new()));
+        let validators = Validators::<OrderLine>::new().with({
+            let kept = Arc::clone(&kept);
+            move |root, _| kept.lock().unwrap().push(root.clone())
+        });
+        let line = validators
+            .validate(OrderLine {
+                product: "tea".into(),
+                quantity: 3,
+            })
+            .unwrap();
+        assert_eq!(line.quantity, 3);
+        assert_eq!(kept.lock().unwrap().len(), 1);
+    }
+
     // regression test for https://github.com/leptos-rs/leptos/issues/3523
     #[tokio::test]
     async fn notifying_all_descendants() {
@@ -1427,4 +1901,63 @@ mod tests {
         tick().await;
         assert_eq!(name_count.load(Ordering::Relaxed), 2);
     }
+
+    #[derive(Debug, Store)]
+    enum Upload {
+        Pending,
+        Sending { sent: u8, total: u8 },
+    }
+
+    #[derive(Debug, Store)]
+    struct Transfer {
+        upload: Upload,
+    }
+
+    #[tokio::test]
+    async fn enum_fields_are_notified_separately() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let variant_count = Arc::new(AtomicUsize::new(0));
+        let sent_count = Arc::new(AtomicUsize::new(0));
+
+        let store = Store::new(Transfer {
+            upload: Upload::Sending { sent: 0, total: 10 },
+        });
+
+        Effect::new_sync({
+            let variant_count = Arc::clone(&variant_count);
+            move |_| {
+                store.upload().sending();
+                variant_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let sent_count = Arc::clone(&sent_count);
+            move |_| {
+                if let Some(sent) = store.upload().sending_sent() {
+                    sent.track();
+                }
+                sent_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        tick().await;
+
+        // each field of a variant has its own path, and checking the variant does not track
+        // changes to the fields inside it
+        *store.upload().sending_total().unwrap().write() = 20;
+        tick().await;
+        assert_eq!(variant_count.load(Ordering::Relaxed), 1);
+        assert_eq!(sent_count.load(Ordering::Relaxed), 1);
+
+        *store.upload().sending_sent().unwrap().write() = 5;
+        tick().await;
+        assert_eq!(variant_count.load(Ordering::Relaxed), 1);
+        assert_eq!(sent_count.load(Ordering::Relaxed), 2);
+
+        *store.upload().write() = Upload::Pending;
+        tick().await;
+        assert_eq!(variant_count.load(Ordering::Relaxed), 2);
+        assert_eq!(sent_count.load(Ordering::Relaxed), 3);
+        assert!(store.upload().pending());
+    }
 }
@@ -85,18 +85,7 @@ where
         };
 
         if structure_changed {
-            // Only notify `children` (not `this`) at the collection path, so that
-            // individual keyed items — which track `this` on all ancestor paths —
-            // are not spuriously notified when only the collection order has changed.
-            let trigger = self.get_trigger_unkeyed(path.clone());
-            trigger.children.notify();
-
-            let mut ancestor_path = path;
-            while !ancestor_path.is_empty() {
-                ancestor_path.pop();
-                let inner = self.get_trigger_unkeyed(ancestor_path.clone());
-                inner.children.notify();
-            }
+            self.notify_structure_changed();
         }
 
         self.update_keys();
@@ -433,9 +422,13 @@ where
         }
 
         // if there are any old keys not included in the new keys, the map has changed
+        // the removed entries are notified, so that anything reading them by key can update
         for old_key in old_keyed.keys() {
             if !new_keyed.contains_key(old_key) {
                 has_changed = true;
+                if let Some(path) = path_at_key(old_key) {
+                    notify(&path);
+                }
             }
         }
 
@@ -523,9 +516,13 @@ where
         let mut new = BTreeMap::new();
 
         // if there are any old keys not included in the new keys, the map has changed
+        // the removed entries are notified, so that anything reading them by key can update
         for old_key in old_keyed.keys() {
             if !new_keyed.contains_key(old_key) {
                 has_changed = true;
+                if let Some(path) = path_at_key(old_key) {
+                    notify(&path);
+                }
             }
         }
 
@@ -574,6 +571,92 @@ where
     }
 }
 
+impl<K, V> PatchFieldKeyed<K> for IndexMap<K, V>
+where
+    V: PatchField,
+    K: Eq + Hash,
+{
+    fn patch_field_keyed(
+        &mut self,
+        mut new: Self,
+        notify: &mut dyn FnMut(&StorePath),
+        keys: Option<&KeyMap>,
+        key_fn: impl Fn(<&Self as IntoIterator>::Item) -> K,
+        path_at_key: impl Fn(&K) -> Option<StorePath>,
+    ) -> bool
+    where
+        K: Clone + Debug + Send + Sync + PartialEq + Eq + Hash + 'static,
+    {
+        let mut has_changed = false;
+
+        let mut old_keyed = HashMap::with_capacity(self.len());
+        let mut new_keyed = IndexMap::with_capacity(new.len());
+
+        // first, calculate keys and indices for all the old values
+        for (idx, item) in self.drain(..).enumerate() {
+            let key = key_fn((&item.0, &item.1));
+            old_keyed.insert(key, (idx, item));
+        }
+
+        // then, calculate keys for all the new values, keeping their order
+        for item in new.drain(..) {
+            let key = key_fn((&item.0, &item.1));
+            new_keyed.insert(key, item);
+        }
+
+        // if there are any old keys not included in the new keys, the map has changed
+        // the removed entries are notified, so that anything reading them by key can update
+        for old_key in old_keyed.keys() {
+            if !new_keyed.contains_key(old_key) {
+                has_changed = true;
+                if let Some(path) = path_at_key(old_key) {
+                    notify(&path);
+                }
+            }
+        }
+
+        // iterate over the new entries in order, rebuilding the `new` map (which we emptied with
+        // `drain` above)
+        //
+        // for each entry, either
+        // 1) push it directly into the `new` map again, or
+        // 2) take the old value and patch it
+        for (new_idx, (key, new_value)) in new_keyed.into_iter().enumerate() {
+            match old_keyed.remove(&key) {
+                None => {
+                    // not found in old map, map has changed and will trigger
+                    new.insert(new_value.0, new_value.1);
+                    has_changed = true;
+                }
+                Some((old_idx, mut old_value)) => {
+                    // if indices are different, the order of the map has changed
+                    if old_idx != new_idx {
+                        has_changed = true;
+                    }
+
+                    if let Some(path) = path_at_key(&key) {
+                        old_value.1.patch_field(
+                            new_value.1,
+                            &path,
+                            notify,
+                            keys,
+                        );
+                    } else {
+                        has_changed = true;
+                    }
+
+                    new.insert(new_value.0, old_value.1);
+                }
+            }
+        }
+
+        // update the value
+        *self = new;
+
+        has_changed
+    }
+}
+
 macro_rules! patch_tuple {
 	($($ty:ident),*) => {
 		impl<$($ty),*> PatchField for ($($ty,)*)
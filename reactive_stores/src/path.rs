@@ -63,6 +63,11 @@ impl StorePath {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    /// Returns `true` if `prefix` is this path or the path of one of its parents.
+    pub fn starts_with(&self, prefix: &StorePath) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
 }
 
 /// One segment of a [`StorePath`].
@@ -54,6 +54,23 @@ pub trait StoreField: Sized {
         trigger.children.track();
     }
 
+    /// Reactively tracks changes made directly to this field or to any of its ancestors, but
+    /// not changes made to its children.
+    ///
+    /// This is used to track which variant of an enum is active: replacing the enum with a
+    /// different variant notifies this field, while patching the fields inside the current
+    /// variant does not.
+    #[track_caller]
+    fn track_field_shallow(&self) {
+        let mut full_path = self.path().into_iter().collect::<StorePath>();
+        loop {
+            self.get_trigger(full_path.clone()).this.track();
+            if full_path.pop().is_none() {
+                break;
+            }
+        }
+    }
+
     /// Returns a read guard to access this field.
     #[track_caller]
     fn reader(&self) -> Option<Self::Reader>;
@@ -152,7 +169,7 @@ where
                     .unwrap_or_else(|| {
                         panic!(
                             "could not find key for index {:?} at {}",
-                            &(path.clone(), segment.0),
+                            (path.clone(), segment.0),
                             caller
                         )
                     });
@@ -0,0 +1,605 @@
+use crate::{ArcField, ArcStore, PatchField, Store, StoreField, StorePath};
+use reactive_graph::{
+    computed::Memo,
+    graph::untrack,
+    owner::{Owner, StoredValue, WeakOwner},
+    signal::RwSignal,
+    traits::{
+        Get, IsDisposed, Set, Update, UpdateValue, With, WithUntracked,
+        WithValue,
+    },
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    sync::Arc,
+};
+
+/// A type whose value can be checked against a set of rules.
+///
+/// This is usually implemented with the [`Validate`](macro@crate::Validate) derive macro, which
+/// runs the validators declared on each field with `#[validate(...)]`. Because it works on plain
+/// values, the same rules can be run on the client, against the value of a store, and on the
+/// server, against the arguments of a server function.
+pub trait Validate {
+    /// Checks the value, adding an error to `errors` for each rule that fails.
+    ///
+    /// `path` is the [`StorePath`] of this value within the store, and is used as the prefix for
+    /// the paths of any errors in its fields.
+    fn validate_at(&self, path: &StorePath, errors: &mut ValidationErrors);
+
+    /// Checks the value, returning all of the errors if any rule fails.
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        self.validate_at(&StorePath::new(), &mut errors);
+        errors.into_result()
+    }
+}
+
+impl<T> Validate for Option<T>
+where
+    T: Validate,
+{
+    fn validate_at(&self, path: &StorePath, errors: &mut ValidationErrors) {
+        if let Some(inner) = self {
+            let mut path = path.clone();
+            path.push(0);
+            inner.validate_at(&path, errors);
+        }
+    }
+}
+
+impl<T> Validate for Vec<T>
+where
+    T: Validate,
+{
+    fn validate_at(&self, path: &StorePath, errors: &mut ValidationErrors) {
+        let mut path = path.clone();
+        path.push(0);
+        for (idx, item) in self.iter().enumerate() {
+            path.replace_last(idx);
+            item.validate_at(&path, errors);
+        }
+    }
+}
+
+impl<T> Validate for Box<T>
+where
+    T: Validate + ?Sized,
+{
+    fn validate_at(&self, path: &StorePath, errors: &mut ValidationErrors) {
+        (**self).validate_at(path, errors);
+    }
+}
+
+/// The errors found when validating a value, indexed by the [`StorePath`] of the field they
+/// belong to.
+///
+/// Paths are unkeyed: the items of keyed collections are identified by their index.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationErrors(Vec<(StorePath, Vec<String>)>);
+
+impl ValidationErrors {
+    /// Creates an empty set of errors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if there are no errors.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Adds an error for the field at `path`.
+    pub fn insert(&mut self, path: StorePath, message: impl Into<String>) {
+        let message = message.into();
+        match self.0.iter_mut().find(|(existing, _)| *existing == path) {
+            Some((_, messages)) => messages.push(message),
+            None => self.0.push((path, vec![message])),
+        }
+    }
+
+    /// Returns the errors for the field at `path`, not including errors in its children.
+    pub fn get(&self, path: &StorePath) -> &[String] {
+        self.0
+            .iter()
+            .find(|(existing, _)| existing == path)
+            .map(|(_, messages)| messages.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if there are any errors for the field at `path` or any of its children.
+    pub fn contains(&self, path: &StorePath) -> bool {
+        self.0
+            .iter()
+            .any(|(existing, _)| existing.starts_with(path))
+    }
+
+    /// Iterates over the paths with errors and their error messages, in the order in which
+    /// they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (&StorePath, &[String])> {
+        self.0
+            .iter()
+            .map(|(path, messages)| (path, messages.as_slice()))
+    }
+
+    /// Moves all of the errors from `other` into this set.
+    pub fn extend(&mut self, other: ValidationErrors) {
+        for (path, messages) in other.0 {
+            for message in messages {
+                self.insert(path.clone(), message);
+            }
+        }
+    }
+
+    fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("validation failed")?;
+        let mut messages = self.0.iter().flat_map(|(_, messages)| messages);
+        if let Some(first) = messages.next() {
+            write!(f, ": {first}")?;
+        }
+        for message in messages {
+            write!(f, "; {message}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ValidationErrors {}
+
+type Rule<T> = Arc<dyn Fn(&ArcField<T>, &mut ValidationErrors) + Send + Sync>;
+
+/// A set of validation rules for the fields of a store, built up one rule at a time.
+///
+/// Each rule selects the field it applies to with the same accessors used to work with the
+/// store, so that its errors are reported at that field's path.
+///
+/// ```rust
+/// use reactive_stores::{Store, Validators};
+///
+/// #[derive(Store, Clone, Debug)]
+/// struct Signup {
+///     email: String,
+///     password: String,
+///     confirm: String,
+/// }
+///
+/// let validators = Validators::<Signup>::new()
+///     .field(
+///         |form| form.email(),
+///         |email: &String| {
+///             if email.contains('@') {
+///                 Ok(())
+///             } else {
+///                 Err("enter a valid email address")
+///             }
+///         },
+///     )
+///     .rule(
+///         |form| form.confirm(),
+///         |form: &Signup| {
+///             if form.password == form.confirm {
+///                 Ok(())
+///             } else {
+///                 Err("passwords do not match")
+///             }
+///         },
+///     );
+///
+/// let errors = validators
+///     .validate(Signup {
+///         email: "alice".to_string(),
+///         password: "secret".to_string(),
+///         confirm: "secret".to_string(),
+///     })
+///     .unwrap_err();
+/// assert_eq!(errors.to_string(), "validation failed: enter a valid email address");
+/// ```
+pub struct Validators<T>
+where
+    T: 'static,
+{
+    rules: Vec<Rule<T>>,
+}
+
+impl<T> Clone for Validators<T>
+where
+    T: 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            rules: self.rules.clone(),
+        }
+    }
+}
+
+impl<T> Default for Validators<T>
+where
+    T: 'static,
+{
+    fn default() -> Self {
+        Self { rules: Vec::new() }
+    }
+}
+
+impl<T> Validators<T>
+where
+    T: 'static,
+{
+    /// Creates an empty set of rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a set of rules that starts with the rules declared by the type's [`Validate`]
+    /// implementation.
+    pub fn derived() -> Self
+    where
+        T: Validate,
+    {
+        Self::new().with(|root, errors| {
+            let path = root.path_unkeyed().into_iter().collect();
+            root.track_field();
+            if let Some(value) = root.reader() {
+                value.validate_at(&path, errors);
+            }
+        })
+    }
+
+    /// Adds a rule that checks the value of a single field.
+    ///
+    /// `field` selects the field from the root of the store.
+    pub fn field<F, E>(
+        self,
+        field: impl Fn(ArcField<T>) -> F + Send + Sync + 'static,
+        validator: impl Fn(&F::Value) -> Result<(), E> + Send + Sync + 'static,
+    ) -> Self
+    where
+        F: StoreField,
+        E: ToString,
+    {
+        self.with(move |root, errors| {
+            let field = field(root.clone());
+            field.track_field();
+            let result = match field.reader() {
+                Some(value) => validator(&value),
+                None => return,
+            };
+            if let Err(e) = result {
+                let path = field.path_unkeyed().into_iter().collect();
+                errors.insert(path, e.to_string());
+            }
+        })
+    }
+
+    /// Adds a rule that checks the whole value, reporting its error at the path of a single
+    /// field. This can be used for rules that compare several fields.
+    ///
+    /// `field` selects the field from the root of the store.
+    pub fn rule<F, E>(
+        self,
+        field: impl Fn(ArcField<T>) -> F + Send + Sync + 'static,
+        validator: impl Fn(&T) -> Result<(), E> + Send + Sync + 'static,
+    ) -> Self
+    where
+        F: StoreField,
+        E: ToString,
+    {
+        self.with(move |root, errors| {
+            root.track_field();
+            let result = match root.reader() {
+                Some(value) => validator(&value),
+                None => return,
+            };
+            if let Err(e) = result {
+                let path =
+                    field(root.clone()).path_unkeyed().into_iter().collect();
+                errors.insert(path, e.to_string());
+            }
+        })
+    }
+
+    /// Adds a rule that receives the root field of the store and adds any errors directly.
+    pub fn with(
+        mut self,
+        rule: impl Fn(&ArcField<T>, &mut ValidationErrors) + Send + Sync + 'static,
+    ) -> Self {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    /// Runs every rule against the field, tracking the fields that each rule reads.
+    pub fn check(&self, root: &ArcField<T>) -> ValidationErrors {
+        let mut errors = ValidationErrors::new();
+        for rule in &self.rules {
+            rule(root, &mut errors);
+        }
+        errors
+    }
+
+    /// Runs every rule against a plain value, returning the value if all of them pass.
+    ///
+    /// This does not require a reactive owner, so it can be used to run the same rules on the
+    /// server, for example against the arguments of a server function.
+    ///
+    /// The rules run against a store holding the value, which they may keep alive, so the value
+    /// that is returned is a clone.
+    pub fn validate(&self, value: T) -> Result<T, ValidationErrors>
+    where
+        T: Clone + Send + Sync,
+    {
+        let store = ArcStore::new(value);
+        let errors = untrack(|| self.check(&ArcField::from(store.clone())));
+        errors.into_result()?;
+        Ok(store.with_untracked(T::clone))
+    }
+}
+
+/// The state of a form that edits the value of a [`Store`].
+///
+/// Alongside the store itself, this keeps a reactive tree of validation state, indexed by the
+/// [`StorePath`] of each field:
+/// - the errors found by running its [`Validators`], which are re-run whenever a field they
+///   read changes;
+/// - whether each field is *dirty*, i.e., whether its value differs from the initial value;
+/// - whether each field has been *touched*, which is usually set when an input loses focus and
+///   used to decide when to show its errors.
+///
+/// The methods that take a field accept any field of this form's store. Each field's errors and
+/// dirty state are memoized separately, so reading them only reruns when that field's state
+/// changes, not whenever any other part of the form is edited.
+///
+/// ```rust
+/// use reactive_graph::{owner::Owner, traits::Set};
+/// use reactive_stores::{FormState, Patch, Store, Validate};
+///
+/// fn non_empty(value: &str) -> Result<(), &'static str> {
+///     if value.is_empty() {
+///         Err("required")
+///     } else {
+///         Ok(())
+///     }
+/// }
+///
+/// #[derive(Store, Patch, Validate, Clone, Default)]
+/// struct Profile {
+///     #[validate(non_empty)]
+///     name: String,
+///     bio: String,
+/// }
+///
+/// # let owner = Owner::new(); owner.set();
+/// let form = FormState::new(Profile::default());
+/// let name = form.store().name();
+///
+/// assert!(!form.is_valid());
+/// assert_eq!(form.errors_for(name), vec!["required"]);
+/// assert!(!form.is_touched(name));
+///
+/// name.set("Alice".to_string());
+/// form.touch(name);
+/// assert!(form.is_valid());
+/// assert!(form.is_dirty_field(name));
+/// assert!(!form.is_dirty_field(form.store().bio()));
+/// assert!(form.is_touched(name));
+/// ```
+pub struct FormState<T> {
+    store: Store<T>,
+    initial: StoredValue<T>,
+    errors: Memo<ValidationErrors>,
+    dirty: Memo<FxHashSet<StorePath>>,
+    fields: StoredValue<FieldStates>,
+    touched: RwSignal<FxHashSet<StorePath>>,
+}
+
+/// The memos for the state of each field of a [`FormState`] that has been read.
+struct FieldStates {
+    /// The owner the form was created in, which the memos for each field belong to.
+    owner: Option<WeakOwner>,
+    fields: FxHashMap<StorePath, FieldState>,
+}
+
+#[derive(Clone, Copy)]
+struct FieldState {
+    errors: Memo<Vec<String>>,
+    valid: Memo<bool>,
+    dirty: Memo<bool>,
+}
+
+impl<T> Clone for FormState<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FormState<T> {}
+
+impl<T> FormState<T>
+where
+    T: Clone + PatchField + Send + Sync + 'static,
+{
+    /// Creates a form for the initial value, validated by its [`Validate`] implementation.
+    #[track_caller]
+    pub fn new(value: T) -> Self
+    where
+        T: Validate,
+    {
+        Self::with_validators(value, Validators::derived())
+    }
+
+    /// Creates a form for the initial value, validated by the given rules.
+    #[track_caller]
+    pub fn with_validators(value: T, validators: Validators<T>) -> Self {
+        let store = Store::new(value.clone());
+        let initial = StoredValue::new(value);
+        let errors = Memo::new({
+            let root = ArcField::from(store);
+            move |_| validators.check(&root)
+        });
+        // the paths that differ from the initial value are the ones that patching the initial
+        // value with the current value would notify
+        let dirty = Memo::new(move |_| {
+            let mut dirty = FxHashSet::default();
+            store.with(|current| {
+                initial.with_value(|initial| {
+                    initial.clone().patch_field(
+                        current.clone(),
+                        &StorePath::new(),
+                        &mut |path| {
+                            dirty.insert(path.clone());
+                        },
+                        None,
+                    );
+                })
+            });
+            dirty
+        });
+        Self {
+            store,
+            initial,
+            errors,
+            dirty,
+            fields: StoredValue::new(FieldStates {
+                owner: Owner::current().map(|owner| owner.downgrade()),
+                fields: FxHashMap::default(),
+            }),
+            touched: RwSignal::new(FxHashSet::default()),
+        }
+    }
+
+    /// Returns the memos for the field, creating them the first time it is read.
+    fn field_state(&self, field: &impl StoreField) -> FieldState {
+        let path = path_of(field);
+        let (existing, owner) = self.fields.with_value(|states| {
+            (
+                states
+                    .fields
+                    .get(&path)
+                    .copied()
+                    .filter(|state| !state.errors.is_disposed()),
+                states.owner.as_ref().and_then(WeakOwner::upgrade),
+            )
+        });
+        if let Some(state) = existing {
+            return state;
+        }
+
+        let (errors, dirty) = (self.errors, self.dirty);
+        let create = {
+            let path = path.clone();
+            move || FieldState {
+                errors: Memo::new({
+                    let path = path.clone();
+                    move |_| errors.with(|errors| errors.get(&path).to_vec())
+                }),
+                valid: Memo::new({
+                    let path = path.clone();
+                    move |_| errors.with(|errors| !errors.contains(&path))
+                }),
+                dirty: Memo::new(move |_| {
+                    dirty.with(|dirty| {
+                        dirty.iter().any(|dirty| {
+                            dirty.starts_with(&path) || path.starts_with(dirty)
+                        })
+                    })
+                }),
+            }
+        };
+        let state = match owner {
+            Some(owner) => owner.with(create),
+            None => create(),
+        };
+        self.fields.update_value(|states| {
+            states.fields.insert(path, state);
+        });
+        state
+    }
+
+    /// The store that holds the current value of the form.
+    pub fn store(&self) -> Store<T> {
+        self.store
+    }
+
+    /// All of the current validation errors.
+    pub fn errors(&self) -> ValidationErrors {
+        self.errors.with(Clone::clone)
+    }
+
+    /// The current validation errors for the field, not including errors in its children.
+    pub fn errors_for(&self, field: impl StoreField) -> Vec<String> {
+        self.field_state(&field).errors.get()
+    }
+
+    /// Returns `true` if the whole value passes validation.
+    pub fn is_valid(&self) -> bool {
+        self.errors.with(ValidationErrors::is_empty)
+    }
+
+    /// Returns `true` if the field and all of its children pass validation.
+    pub fn is_valid_field(&self, field: impl StoreField) -> bool {
+        self.field_state(&field).valid.get()
+    }
+
+    /// Returns `true` if any part of the value differs from the initial value.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.with(|dirty| !dirty.is_empty())
+    }
+
+    /// Returns `true` if the value of the field differs from its initial value.
+    pub fn is_dirty_field(&self, field: impl StoreField) -> bool {
+        self.field_state(&field).dirty.get()
+    }
+
+    /// Returns `true` if the field, or one of its parents, has been marked as touched.
+    pub fn is_touched(&self, field: impl StoreField) -> bool {
+        let path = path_of(&field);
+        self.touched.with(|touched| {
+            touched.iter().any(|touched| path.starts_with(touched))
+        })
+    }
+
+    /// Marks the field as touched.
+    pub fn touch(&self, field: impl StoreField) {
+        let path = path_of(&field);
+        self.touched.update(|touched| {
+            touched.insert(path);
+        });
+    }
+
+    /// Marks every field as touched.
+    pub fn touch_all(&self) {
+        self.touched.update(|touched| {
+            touched.insert(StorePath::new());
+        });
+    }
+
+    /// Marks every field as touched, so that all errors are shown, and returns whether the
+    /// value is valid.
+    ///
+    /// This is intended to be called before submitting the form.
+    pub fn validate(&self) -> bool {
+        self.touch_all();
+        self.errors.with_untracked(ValidationErrors::is_empty)
+    }
+
+    /// Resets the form to its initial value and clears the touched state of every field.
+    pub fn reset(&self) {
+        self.store.set(self.initial.with_value(Clone::clone));
+        self.touched.set(FxHashSet::default());
+    }
+}
+
+fn path_of(field: &impl StoreField) -> StorePath {
+    field.path_unkeyed().into_iter().collect()
+}
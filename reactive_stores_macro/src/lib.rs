@@ -1,13 +1,14 @@
 use convert_case::{Case, Casing};
 use proc_macro2::{Span, TokenStream};
 use proc_macro_error2::{abort, abort_call_site, proc_macro_error, OptionExt};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream, Parser},
     punctuated::Punctuated,
     token::Comma,
-    ExprClosure, Field, Fields, GenericParam, Generics, Ident, Index, Meta,
-    Result, Token, Type, TypeParam, Variant, Visibility, WhereClause,
+    Attribute, Expr, ExprClosure, Field, Fields, GenericParam, Generics, Ident,
+    Index, Meta, Result, Token, Type, TypeParam, Variant, Visibility,
+    WhereClause,
 };
 
 #[proc_macro_error]
@@ -26,6 +27,16 @@ pub fn derive_patch(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         .into()
 }
 
+#[proc_macro_error]
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    syn::parse_macro_input!(input as ValidateModel)
+        .into_token_stream()
+        .into()
+}
+
 /// Removes all constraints from generics arguments list.
 ///
 /// # Example
@@ -128,6 +139,7 @@ struct Model {
     name: Ident,
     generics: Generics,
     ty: ModelTy,
+    computed: Vec<ComputedField>,
 }
 
 enum ModelTy {
@@ -165,15 +177,107 @@ impl Parse for Model {
             }
         };
 
+        let computed = input
+            .attrs
+            .iter()
+            .filter(|attr| attr.meta.path().is_ident("store"))
+            .flat_map(|attr| match &attr.meta {
+                Meta::List(list) => {
+                    match Punctuated::<ContainerMode, Comma>::parse_terminated
+                        .parse2(list.tokens.clone())
+                    {
+                        Ok(modes) => modes,
+                        Err(e) => abort!(list, e),
+                    }
+                }
+                _ => abort!(
+                    attr.meta,
+                    "needs to be as `#[store(computed(name: Type = fun))]`"
+                ),
+            })
+            .flat_map(|mode| match mode {
+                ContainerMode::Computed(fields) => fields,
+            })
+            .collect();
+
         Ok(Self {
             vis: input.vis,
             generics: input.generics,
             name: input.ident,
             ty,
+            computed,
         })
     }
 }
 
+enum ContainerMode {
+    Computed(Vec<ComputedField>),
+}
+
+impl Parse for ContainerMode {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mode: Ident = input.parse()?;
+        if mode == "computed" {
+            let content;
+            syn::parenthesized!(content in input);
+            let fields =
+                Punctuated::<ComputedField, Comma>::parse_terminated(&content)?;
+            Ok(ContainerMode::Computed(fields.into_iter().collect()))
+        } else {
+            Err(syn::Error::new(
+                mode.span(),
+                "expected `computed(name: Type = fun)`",
+            ))
+        }
+    }
+}
+
+/// A memoized field declared with `#[store(computed(name: Type = fun))]`, where `fun` receives
+/// a `Field` for the whole value.
+struct ComputedField {
+    ident: Ident,
+    ty: Type,
+    fun: Expr,
+}
+
+impl Parse for ComputedField {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        let _col: Token![:] = input.parse()?;
+        let ty: Type = input.parse()?;
+        let _eq: Token![=] = input.parse()?;
+        let fun: Expr = input.parse()?;
+        Ok(ComputedField { ident, ty, fun })
+    }
+}
+
+impl ComputedField {
+    fn to_tokens(
+        &self,
+        include_body: bool,
+        library_path: &TokenStream,
+    ) -> TokenStream {
+        let ComputedField { ident, ty, fun } = self;
+        if include_body {
+            let name = ident.to_string();
+            quote! {
+                fn #ident(self) -> #library_path::__private::Memo<#ty>
+                where
+                    Self: Send + Sync + 'static,
+                {
+                    #library_path::__private::computed_field(self, #name, #fun)
+                }
+            }
+        } else {
+            quote! {
+                fn #ident(self) -> #library_path::__private::Memo<#ty>
+                where
+                    Self: Send + Sync + 'static;
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 enum SubfieldMode {
     Keyed(Box<ExprClosure>, Box<Type>),
@@ -205,6 +309,7 @@ impl ToTokens for Model {
             name,
             generics,
             ty,
+            computed,
         } = &self;
         let any_store_field = Ident::new("AnyStoreField", Span::call_site());
         let trait_name = Ident::new(&format!("{name}StoreFields"), name.span());
@@ -232,13 +337,18 @@ impl ToTokens for Model {
 
         // define an extension trait that matches this struct
         // and implement that trait for all StoreFields
-        let (trait_fields, read_fields): (Vec<_>, Vec<_>) = ty.to_field_data(
-            &library_path,
-            generics,
-            &clear_generics,
-            &any_store_field,
-            name,
-        );
+        let (mut trait_fields, mut read_fields): (Vec<_>, Vec<_>) = ty
+            .to_field_data(
+                &library_path,
+                generics,
+                &clear_generics,
+                &any_store_field,
+                name,
+            );
+        for field in computed {
+            trait_fields.push(field.to_tokens(false, &library_path));
+            read_fields.push(field.to_tokens(true, &library_path));
+        }
 
         // read access
         tokens.extend(quote! {
@@ -472,7 +582,7 @@ fn variant_to_tokens(
                     fn #ident(self) -> bool {
                         match #library_path::StoreField::reader(&self) {
                             Some(reader) => {
-                                #library_path::StoreField::track_field(&self);
+                                #library_path::StoreField::track_field_shallow(&self);
                                 matches!(&*reader, #name::#orig_ident)
                             },
                             None => false
@@ -494,7 +604,7 @@ fn variant_to_tokens(
                     fn #ident(self) -> bool {
                         match #library_path::StoreField::reader(&self) {
                             Some(reader) => {
-                                #library_path::StoreField::track_field(&self);
+                                #library_path::StoreField::track_field_shallow(&self);
                                 matches!(&*reader, #name::#orig_ident { .. })
                             },
                             None => false
@@ -510,7 +620,8 @@ fn variant_to_tokens(
             tokens.extend(fields
                 .named
                 .iter()
-                .map(|field| {
+                .enumerate()
+                .map(|(idx, field)| {
                     let field_ident = field.ident.as_ref().unwrap();
                     let field_ty = &field.ty;
                     let combined_ident = Ident::new(
@@ -522,7 +633,7 @@ fn variant_to_tokens(
                     if include_body {
                         quote! {
                             fn #combined_ident(self) -> Option<#library_path::Subfield<#any_store_field, #name #clear_generics, #field_ty>> {
-                                #library_path::StoreField::track_field(&self);
+                                #library_path::StoreField::track_field_shallow(&self);
                                 let reader = #library_path::StoreField::reader(&self);
                                 let matches = reader
                                     .map(|reader| matches!(&*reader, #name::#orig_ident { .. }))
@@ -530,7 +641,7 @@ fn variant_to_tokens(
                                 if matches {
                                     Some(#library_path::Subfield::new(
                                         self,
-                                        0.into(),
+                                        #idx.into(),
                                         |prev| {
                                             match prev {
                                                 #name::#orig_ident { #field_ident, .. } => Some(#field_ident),
@@ -569,7 +680,7 @@ fn variant_to_tokens(
                     fn #ident(self) -> bool {
                         match #library_path::StoreField::reader(&self) {
                             Some(reader) => {
-                                #library_path::StoreField::track_field(&self);
+                                #library_path::StoreField::track_field_shallow(&self);
                                 matches!(&*reader, #name::#orig_ident { .. })
                             },
                             None => false
@@ -605,7 +716,7 @@ fn variant_to_tokens(
                     if include_body {
                         quote! {
                             fn #combined_ident(self) -> Option<#library_path::Subfield<#any_store_field, #name #clear_generics, #field_ty>> {
-                                #library_path::StoreField::track_field(&self);
+                                #library_path::StoreField::track_field_shallow(&self);
                                 let reader = #library_path::StoreField::reader(&self);
                                 let matches = reader
                                     .map(|reader| matches!(&*reader, #name::#orig_ident(..)))
@@ -613,7 +724,7 @@ fn variant_to_tokens(
                                 if matches {
                                     Some(#library_path::Subfield::new(
                                         self,
-                                        0.into(),
+                                        #idx.into(),
                                         |prev| {
                                             match prev {
                                                 #name::#orig_ident(#(#ignore_before)* this, #(#ignore_after)*) => Some(this),
@@ -653,13 +764,8 @@ struct PatchModel {
 }
 
 enum PatchModelTy {
-    Struct {
-        fields: Vec<Field>,
-    },
-    #[allow(dead_code)]
-    Enum {
-        variants: Vec<Variant>,
-    },
+    Struct { fields: Vec<Field> },
+    Enum { variants: Vec<Variant> },
 }
 
 impl Parse for PatchModel {
@@ -682,17 +788,12 @@ impl Parse for PatchModel {
 
                 PatchModelTy::Struct { fields }
             }
-            syn::Data::Enum(_e) => {
-                abort_call_site!("only structs can be used with `Patch`");
-
-                // TODO: support enums later on
-                // PatchModelTy::Enum {
-                //     variants: e.variants.into_iter().collect(),
-                // }
-            }
+            syn::Data::Enum(e) => PatchModelTy::Enum {
+                variants: e.variants.into_iter().collect(),
+            },
             _ => {
                 abort_call_site!(
-                    "only structs and enums can be used with `Store`"
+                    "only structs and enums can be used with `Patch`"
                 );
             }
         };
@@ -710,129 +811,90 @@ impl ToTokens for PatchModel {
         let library_path = quote! { reactive_stores };
         let PatchModel { name, generics, ty } = &self;
 
-        let fields = match ty {
+        let body = match ty {
             PatchModelTy::Struct { fields } => {
-                fields.iter().enumerate().map(|(idx, field)| {
-                    let Field {
-                        attrs, ident, ..
-                    } = &field;
-                    let locator = match &ident {
+                let fields = fields.iter().enumerate().map(|(idx, field)| {
+                    let locator = match &field.ident {
                         Some(ident) => Either::Left(ident),
                         None => Either::Right(Index::from(idx)),
                     };
-                    let closure = attrs
-                        .iter()
-                        .find_map(|attr| {
-                            attr.meta.path().is_ident("patch").then(
-                                || match &attr.meta {
-                                    Meta::List(list) => {
-                                        match Punctuated::<
-                                                ExprClosure,
-                                                Comma,
-                                            >::parse_terminated
-                                                .parse2(list.tokens.clone())
-                                            {
-                                                Ok(closures) => {
-                                                    let closure = closures.iter().next().cloned().expect_or_abort("should have ONE closure");
-                                                    if closure.inputs.len() != 2 {
-                                                        abort!(closure.inputs, "patch closure should have TWO params as in #[patch(|this, new| ...)]");
-                                                    }
-                                                    closure
-                                                },
-                                                Err(e) => abort!(list, e),
-                                            }
-                                    }
-                                    _ => abort!(attr.meta, "needs to be as `#[patch(|this, new| ...)]`"),
-                                },
+                    patch_field_to_tokens(
+                        &library_path,
+                        idx,
+                        &field.attrs,
+                        quote! { self.#locator },
+                        quote! { new.#locator },
+                    )
+                });
+                quote! {
+                    let mut new_path = path.clone();
+                    new_path.push(0);
+                    #(#fields)*
+                }
+            }
+            PatchModelTy::Enum { variants } => {
+                // if the variant is unchanged, each of its fields is patched individually, using
+                // the same path segments that the `Store` derive uses for enum fields
+                // if the variant has changed, the whole value is replaced and notified
+                let arms = variants.iter().map(|variant| {
+                    let ident = &variant.ident;
+                    let this_idents = (0..variant.fields.len())
+                        .map(|idx| format_ident!("__this_{idx}"))
+                        .collect::<Vec<_>>();
+                    let new_idents = (0..variant.fields.len())
+                        .map(|idx| format_ident!("__new_{idx}"))
+                        .collect::<Vec<_>>();
+                    let (this_pat, new_pat) = match &variant.fields {
+                        Fields::Unit => {
+                            (quote! { Self::#ident }, quote! { Self::#ident })
+                        }
+                        Fields::Named(fields) => {
+                            let field_idents = fields
+                                .named
+                                .iter()
+                                .map(|field| field.ident.as_ref().unwrap())
+                                .collect::<Vec<_>>();
+                            (
+                                quote! { Self::#ident { #(#field_idents: #this_idents),* } },
+                                quote! { Self::#ident { #(#field_idents: #new_idents),* } },
                             )
-                        });
-                    let keyed = attrs
-                        .iter()
-                        .find_map(|attr| {
-                            attr.meta.path().is_ident("store").then(|| {
-                                match &attr.meta {
-                                    Meta::List(list) => {
-                                        let subfields = match Punctuated::<
-                                                SubfieldMode,
-                                                Comma,
-                                            >::parse_terminated
-                                                .parse2(list.tokens.clone())
-                                            {
-                                                Ok(modes) => Some(
-                                                    modes
-                                                        .iter()
-                                                        .cloned()
-                                                        .collect::<Vec<_>>(),
-                                                ),
-                                                Err(e) => abort!(list, e),
-                                            }.unwrap_or_default();
-                                            subfields.into_iter().find_map(|subfield| match subfield {
-                                                SubfieldMode::Keyed(closure, _ty) => Some(closure),
-                                                SubfieldMode::Skip => None,
-                                            })
-                                    }
-                                    _ => None,
-                                }
-                            })
-                        }).flatten();
-
-                    if let Some(closure) = closure {
-                        let params = closure.inputs;
-                        let body = closure.body;
-                        quote! {
-                            if new.#locator != self.#locator {
-                                _ = {
-                                    let (#params) = (&mut self.#locator, new.#locator);
-                                    #body
-                                };
-                                notify(&new_path);
-                            }
-                            new_path.replace_last(#idx + 1);
                         }
-                    } else if let Some(closure) = keyed {
-                        quote! {
-                            let structure_changed = #library_path::PatchFieldKeyed::patch_field_keyed(
-                                &mut self.#locator,
-                                new.#locator,
-                                notify,
-                                keys,
-                                #closure,
-                                |key| {
-                                    let keys = keys.as_ref()?;
-                                    let segment = keys
-                                        .with_field_keys(
-                                            path.clone(),
-                                            |keys| (keys.get(key), vec![]),
-                                            || vec![],
-                                        )
-                                        .flatten()
-                                        .map(|(_, idx)| idx)?;
-                                    let mut path = path.clone();
-                                    path.push(segment);
-                                    Some(path)
-                                }
-                            );
-                            if structure_changed {
-                                notify(&new_path);
-                            }
-                            new_path.replace_last(#idx + 1);
+                        Fields::Unnamed(_) => (
+                            quote! { Self::#ident(#(#this_idents),*) },
+                            quote! { Self::#ident(#(#new_idents),*) },
+                        ),
+                    };
+                    let fields = variant.fields.iter().enumerate().map(
+                        |(idx, field)| {
+                            let this = &this_idents[idx];
+                            let new = &new_idents[idx];
+                            patch_field_to_tokens(
+                                &library_path,
+                                idx,
+                                &field.attrs,
+                                quote! { (*#this) },
+                                quote! { #new },
+                            )
+                        },
+                    );
+                    quote! {
+                        (#this_pat, #new_pat) => {
+                            let mut new_path = path.clone();
+                            new_path.push(0);
+                            #(#fields)*
                         }
-                    } else {
-                        quote! {
-                            #library_path::PatchField::patch_field(
-                                &mut self.#locator,
-                                new.#locator,
-                                &new_path,
-                                notify,
-                                keys
-                            );
-                            new_path.replace_last(#idx + 1);
+                    }
+                });
+                quote! {
+                    match (&mut *self, new) {
+                        #(#arms)*
+                        #[allow(unreachable_patterns)]
+                        (this, new) => {
+                            *this = new;
+                            notify(path);
                         }
                     }
-                }).collect::<Vec<_>>()
-            }
-            PatchModelTy::Enum { variants: _ } => {
-                unreachable!("not implemented currently")
+                }
             }
         };
 
@@ -853,15 +915,139 @@ impl ToTokens for PatchModel {
                     notify: &mut dyn FnMut(&#library_path::StorePath),
                     keys: Option<&#library_path::KeyMap>,
                 ) {
-                    let mut new_path = path.clone();
-                    new_path.push(0);
-                    #(#fields)*
+                    #body
                 }
             }
         });
     }
 }
 
+/// Generates the code that patches a single field, where `this` is a place expression for the
+/// current value and `new` is the value it is being patched with.
+///
+/// Expects `new_path` to hold the path of this field, and advances it to the next field.
+fn patch_field_to_tokens(
+    library_path: &proc_macro2::TokenStream,
+    idx: usize,
+    attrs: &[Attribute],
+    this: proc_macro2::TokenStream,
+    new: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let closure = attrs.iter().find_map(|attr| {
+        attr.meta.path().is_ident("patch").then(|| match &attr.meta {
+            Meta::List(list) => {
+                match Punctuated::<ExprClosure, Comma>::parse_terminated
+                    .parse2(list.tokens.clone())
+                {
+                    Ok(closures) => {
+                        let closure = closures
+                            .iter()
+                            .next()
+                            .cloned()
+                            .expect_or_abort("should have ONE closure");
+                        if closure.inputs.len() != 2 {
+                            abort!(closure.inputs, "patch closure should have TWO params as in #[patch(|this, new| ...)]");
+                        }
+                        closure
+                    }
+                    Err(e) => abort!(list, e),
+                }
+            }
+            _ => abort!(
+                attr.meta,
+                "needs to be as `#[patch(|this, new| ...)]`"
+            ),
+        })
+    });
+    let keyed = attrs
+        .iter()
+        .find_map(|attr| {
+            attr.meta
+                .path()
+                .is_ident("store")
+                .then(|| match &attr.meta {
+                    Meta::List(list) => {
+                        let subfields = match Punctuated::<
+                                SubfieldMode,
+                                Comma,
+                            >::parse_terminated
+                                .parse2(list.tokens.clone())
+                            {
+                                Ok(modes) => Some(
+                                    modes.iter().cloned().collect::<Vec<_>>(),
+                                ),
+                                Err(e) => abort!(list, e),
+                            }
+                            .unwrap_or_default();
+                        subfields.into_iter().find_map(
+                            |subfield| match subfield {
+                                SubfieldMode::Keyed(closure, _ty) => {
+                                    Some(closure)
+                                }
+                                SubfieldMode::Skip => None,
+                            },
+                        )
+                    }
+                    _ => None,
+                })
+        })
+        .flatten();
+
+    if let Some(closure) = closure {
+        let params = closure.inputs;
+        let body = closure.body;
+        quote! {
+            if #new != #this {
+                _ = {
+                    let (#params) = (&mut #this, #new);
+                    #body
+                };
+                notify(&new_path);
+            }
+            new_path.replace_last(#idx + 1);
+        }
+    } else if let Some(closure) = keyed {
+        quote! {
+            let structure_changed = #library_path::PatchFieldKeyed::patch_field_keyed(
+                &mut #this,
+                #new,
+                notify,
+                keys,
+                #closure,
+                |key| {
+                    let keys = keys.as_ref()?;
+                    let segment = keys
+                        .with_field_keys(
+                            path.clone(),
+                            |keys| (keys.get(key), vec![]),
+                            || vec![],
+                        )
+                        .flatten()
+                        .map(|(_, idx)| idx)?;
+                    let mut path = path.clone();
+                    path.push(segment);
+                    Some(path)
+                }
+            );
+            if structure_changed {
+                notify(&new_path);
+            }
+            new_path.replace_last(#idx + 1);
+        }
+    } else {
+        quote! {
+            #library_path::PatchField::patch_field(
+                &mut #this,
+                #new,
+                &new_path,
+                notify,
+                keys
+            );
+            new_path.replace_last(#idx + 1);
+        }
+    }
+}
+
 enum Either<A, B> {
     Left(A),
     Right(B),
@@ -875,3 +1061,134 @@ impl<A: ToTokens, B: ToTokens> ToTokens for Either<A, B> {
         }
     }
 }
+
+struct ValidateModel {
+    pub name: Ident,
+    pub generics: Generics,
+    pub fields: Vec<Field>,
+}
+
+impl Parse for ValidateModel {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let input = syn::DeriveInput::parse(input)?;
+
+        let fields = match input.data {
+            syn::Data::Struct(s) => match s.fields {
+                syn::Fields::Unit => {
+                    abort!(s.semi_token, "unit structs are not supported");
+                }
+                syn::Fields::Named(fields) => {
+                    fields.named.into_iter().collect::<Vec<_>>()
+                }
+                syn::Fields::Unnamed(fields) => {
+                    fields.unnamed.into_iter().collect::<Vec<_>>()
+                }
+            },
+            _ => {
+                abort_call_site!("only structs can be used with `Validate`");
+            }
+        };
+
+        Ok(Self {
+            name: input.ident,
+            generics: input.generics,
+            fields,
+        })
+    }
+}
+
+#[derive(Clone)]
+enum ValidateMode {
+    Nested,
+    With(Box<Expr>),
+}
+
+impl Parse for ValidateMode {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let expr: Expr = input.parse()?;
+        match &expr {
+            Expr::Path(path) if path.path.is_ident("nested") => {
+                Ok(ValidateMode::Nested)
+            }
+            _ => Ok(ValidateMode::With(Box::new(expr))),
+        }
+    }
+}
+
+impl ToTokens for ValidateModel {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let library_path = quote! { reactive_stores };
+        let ValidateModel {
+            name,
+            generics,
+            fields,
+        } = &self;
+
+        let fields = fields.iter().enumerate().map(|(idx, field)| {
+            let locator = match &field.ident {
+                Some(ident) => Either::Left(ident),
+                None => Either::Right(Index::from(idx)),
+            };
+            let modes = field
+                .attrs
+                .iter()
+                .filter(|attr| attr.meta.path().is_ident("validate"))
+                .flat_map(|attr| match &attr.meta {
+                    Meta::List(list) => {
+                        match Punctuated::<ValidateMode, Comma>::parse_terminated
+                            .parse2(list.tokens.clone())
+                        {
+                            Ok(modes) => modes.into_iter().collect::<Vec<_>>(),
+                            Err(e) => abort!(list, e),
+                        }
+                    }
+                    _ => abort!(
+                        attr.meta,
+                        "needs to be as `#[validate(<validator>, ...)]`"
+                    ),
+                })
+                .map(|mode| match mode {
+                    ValidateMode::Nested => quote! {
+                        #library_path::Validate::validate_at(
+                            &self.#locator,
+                            &field_path,
+                            errors,
+                        );
+                    },
+                    ValidateMode::With(validator) => quote! {
+                        if let Err(e) = (#validator)(&self.#locator) {
+                            errors.insert(
+                                field_path.clone(),
+                                ::std::string::ToString::to_string(&e),
+                            );
+                        }
+                    },
+                });
+            quote! {
+                #(#modes)*
+                field_path.replace_last(#idx + 1);
+            }
+        });
+
+        let clear_generics = remove_constraint_from_generics(generics);
+        let params = clear_generics.params;
+        let where_clause = &generics.where_clause;
+
+        tokens.extend(quote! {
+            #[automatically_derived]
+            impl #generics #library_path::Validate for #name <#params>
+               #where_clause
+            {
+                fn validate_at(
+                    &self,
+                    path: &#library_path::StorePath,
+                    errors: &mut #library_path::ValidationErrors,
+                ) {
+                    let mut field_path = path.clone();
+                    field_path.push(0);
+                    #(#fields)*
+                }
+            }
+        });
+    }
+}
@@ -18,7 +18,8 @@ use std::{future::Future, sync::Arc};
 /// # Security
 /// Be very careful when using this method. Always remember to
 /// sanitize the input to avoid a cross-site scripting (XSS)
-/// vulnerability.
+/// vulnerability. With the `sanitize` feature, `safe_inner_html`
+/// does this for you.
 #[inline(always)]
 pub fn inner_html<T>(value: T) -> InnerHtml<T>
 where
@@ -1,10 +1,14 @@
+#[cfg(not(feature = "hydration_recovery"))]
+use crate::hydration::failed_to_cast_element;
+#[cfg(feature = "hydration_recovery")]
+use crate::hydration::recover_from_element_mismatch;
 #[cfg(any(debug_assertions, leptos_debuginfo))]
 use crate::hydration::set_currently_hydrating;
 #[cfg(erase_components)]
 use crate::view::any_view::AnyView;
 use crate::{
     html::attribute::Attribute,
-    hydration::{failed_to_cast_element, Cursor},
+    hydration::{is_expected_element, Cursor},
     renderer::{CastFrom, Rndr},
     ssr::StreamBuilder,
     view::{
@@ -22,6 +26,8 @@ mod custom;
 mod element_ext;
 mod elements;
 mod inner_html;
+#[cfg(feature = "sanitize")]
+mod safe_inner_html;
 use super::attribute::{
     any_attribute::AnyAttribute, escape_attr, NextAttribute,
 };
@@ -29,6 +35,8 @@ pub use custom::*;
 pub use element_ext::*;
 pub use elements::*;
 pub use inner_html::*;
+#[cfg(feature = "sanitize")]
+pub use safe_inner_html::*;
 #[cfg(any(debug_assertions, leptos_debuginfo))]
 use std::panic::Location;
 
@@ -525,7 +533,7 @@ where
             tag_name: &str,
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             defined_at: &'static std::panic::Location<'static>,
-        ) -> crate::renderer::types::Element {
+        ) -> Option<crate::renderer::types::Element> {
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             {
                 set_currently_hydrating(Some(defined_at));
@@ -537,18 +545,37 @@ where
             } else if curr_position != Position::Current {
                 cursor.sibling();
             }
-            crate::renderer::types::Element::cast_from(cursor.current())
-                .unwrap_or_else(|| {
-                    failed_to_cast_element(tag_name, cursor.current())
-                })
+            match crate::renderer::types::Element::cast_from(cursor.current()) {
+                Some(el) if is_expected_element(cursor, &el, tag_name) => {
+                    Some(el)
+                }
+                #[cfg(feature = "hydration_recovery")]
+                _ => {
+                    recover_from_element_mismatch(tag_name, cursor);
+                    None
+                }
+                #[cfg(not(feature = "hydration_recovery"))]
+                _ => failed_to_cast_element(tag_name, cursor),
+            }
         }
-        let el = inner_1(
+        let Some(el) = inner_1(
             cursor,
             position,
             E::TAG,
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             self.defined_at,
-        );
+        ) else {
+            // the node did not match this element, so render it on the client instead
+            #[cfg(feature = "hydration_recovery")]
+            {
+                let state = self.build();
+                cursor.replace_mismatched(state.el.as_ref());
+                position.set(Position::NextChild);
+                return state;
+            }
+            #[cfg(not(feature = "hydration_recovery"))]
+            unreachable!()
+        };
 
         let attrs = self.attributes.hydrate::<FROM_SERVER>(&el);
 
@@ -596,7 +623,7 @@ where
             tag_name: &str,
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             defined_at: &'static std::panic::Location<'static>,
-        ) -> crate::renderer::types::Element {
+        ) -> Option<crate::renderer::types::Element> {
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             {
                 set_currently_hydrating(Some(defined_at));
@@ -608,18 +635,37 @@ where
             } else if curr_position != Position::Current {
                 cursor.sibling();
             }
-            crate::renderer::types::Element::cast_from(cursor.current())
-                .unwrap_or_else(|| {
-                    failed_to_cast_element(tag_name, cursor.current())
-                })
+            match crate::renderer::types::Element::cast_from(cursor.current()) {
+                Some(el) if is_expected_element(cursor, &el, tag_name) => {
+                    Some(el)
+                }
+                #[cfg(feature = "hydration_recovery")]
+                _ => {
+                    recover_from_element_mismatch(tag_name, cursor);
+                    None
+                }
+                #[cfg(not(feature = "hydration_recovery"))]
+                _ => failed_to_cast_element(tag_name, cursor),
+            }
         }
-        let el = inner_1(
+        let Some(el) = inner_1(
             cursor,
             position,
             E::TAG,
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             self.defined_at,
-        );
+        ) else {
+            // the node did not match this element, so render it on the client instead
+            #[cfg(feature = "hydration_recovery")]
+            {
+                let state = self.build();
+                cursor.replace_mismatched(state.el.as_ref());
+                position.set(Position::NextChild);
+                return state;
+            }
+            #[cfg(not(feature = "hydration_recovery"))]
+            unreachable!()
+        };
 
         let attrs = self.attributes.hydrate::<true>(&el);
 
@@ -745,9 +791,7 @@ impl<At, Ch> Mountable for ElementState<At, Ch> {
             element: &crate::renderer::types::Element,
             child: &mut dyn Mountable,
         ) -> bool {
-            if let Some(parent) = Rndr::get_parent(element)
-                .and_then(crate::renderer::types::Element::cast_from)
-            {
+            if let Some(parent) = Rndr::get_parent_element(element) {
                 child.mount(&parent, Some(element));
                 true
             } else {
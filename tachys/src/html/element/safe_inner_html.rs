@@ -0,0 +1,310 @@
+use super::{
+    inner_html, ElementWithChildren, HtmlElement, InnerHtml, InnerHtmlValue,
+};
+use crate::{
+    html::attribute::Attribute, renderer::Rndr, view::add_attr::AddAnyAttr,
+};
+use js_sys::{Function, Object, Reflect};
+use std::{
+    fmt::Debug,
+    sync::{Arc, LazyLock},
+};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+/// The name of the [Trusted Types](https://developer.mozilla.org/en-US/docs/Web/API/Trusted_Types_API)
+/// policy used to set sanitized HTML in browsers that support Trusted Types.
+///
+/// If your `Content-Security-Policy` restricts Trusted Types policies, it should allow this one.
+pub const TRUSTED_TYPES_POLICY_NAME: &str = "leptos-sanitize";
+
+/// An allowlist of the tags, attributes, and URL schemes that are kept when sanitizing HTML.
+///
+/// The default policy is [`ammonia`]'s default, which allows the markup that is typically produced
+/// from Markdown, and removes scripts, event handlers, styles, and `javascript:` URLs.
+#[derive(Clone)]
+pub struct SanitizePolicy(Arc<ammonia::Builder<'static>>);
+
+impl SanitizePolicy {
+    /// Creates a policy from an [`ammonia::Builder`], which can be used to configure which tags,
+    /// attributes, and URL schemes are allowed.
+    pub fn new(builder: ammonia::Builder<'static>) -> Self {
+        Self(Arc::new(builder))
+    }
+
+    /// Sanitizes untrusted HTML.
+    pub fn clean(&self, html: &str) -> String {
+        self.0.clean(html).to_string()
+    }
+
+    /// Sanitizes untrusted HTML and sets it as the inner HTML of an element.
+    pub(crate) fn set_inner_html(
+        &self,
+        el: &crate::renderer::types::Element,
+        html: &str,
+    ) {
+        set_sanitized_inner_html(el, &self.clean(html));
+    }
+
+    /// Updates the inner HTML of an element, if the untrusted HTML has changed.
+    pub(crate) fn rebuild<T>(
+        &self,
+        value: T,
+        mut state: (crate::renderer::types::Element, T),
+    ) -> (crate::renderer::types::Element, T)
+    where
+        T: SanitizeInput,
+    {
+        if value != state.1 {
+            self.set_inner_html(&state.0, value.as_ref());
+            state.1 = value;
+        }
+        state
+    }
+}
+
+impl Default for SanitizePolicy {
+    fn default() -> Self {
+        static DEFAULT: LazyLock<SanitizePolicy> =
+            LazyLock::new(|| SanitizePolicy::new(ammonia::Builder::default()));
+        DEFAULT.clone()
+    }
+}
+
+impl Debug for SanitizePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SanitizePolicy").finish_non_exhaustive()
+    }
+}
+
+/// Untrusted HTML that can be [`Sanitized`].
+pub trait SanitizeInput: AsRef<str> + PartialEq + Send {}
+
+impl SanitizeInput for String {}
+
+impl SanitizeInput for Arc<str> {}
+
+impl SanitizeInput for &str {}
+
+/// Untrusted HTML, which is sanitized with a [`SanitizePolicy`] before it is used as the inner
+/// HTML of an element.
+///
+/// The same sanitization is applied when rendering on the server and in the browser.
+#[derive(Debug, Clone)]
+pub struct Sanitized<T> {
+    pub(crate) value: T,
+    pub(crate) policy: SanitizePolicy,
+}
+
+impl<T> Sanitized<T> {
+    /// Wraps untrusted HTML, which will be sanitized with the default policy.
+    pub fn new(value: T) -> Self {
+        Self::with_policy(value, SanitizePolicy::default())
+    }
+
+    /// Wraps untrusted HTML, which will be sanitized with the given policy.
+    pub fn with_policy(value: T, policy: SanitizePolicy) -> Self {
+        Self { value, policy }
+    }
+}
+
+/// Returns an [`Attribute`] that sanitizes untrusted HTML and sets it as the inner HTML of an
+/// element.
+///
+/// No children should be given to this element, as this HTML will be used instead.
+#[inline(always)]
+pub fn safe_inner_html<T>(value: T) -> InnerHtml<Sanitized<T>>
+where
+    Sanitized<T>: InnerHtmlValue,
+{
+    inner_html(Sanitized::new(value))
+}
+
+/// Sets the inner HTML of an element to sanitized, untrusted HTML.
+pub trait SafeInnerHtmlAttribute<T>
+where
+    Sanitized<T>: InnerHtmlValue,
+    Self: Sized + AddAnyAttr,
+{
+    /// Sanitizes untrusted HTML with the default [`SanitizePolicy`], and sets it as the inner
+    /// HTML of this element.
+    ///
+    /// No children should be given to this element, as this HTML will be used instead.
+    fn safe_inner_html(
+        self,
+        value: T,
+    ) -> <Self as AddAnyAttr>::Output<InnerHtml<Sanitized<T>>> {
+        self.add_any_attr(safe_inner_html(value))
+    }
+
+    /// Sanitizes untrusted HTML with the given [`SanitizePolicy`], and sets it as the inner HTML
+    /// of this element.
+    ///
+    /// No children should be given to this element, as this HTML will be used instead.
+    fn safe_inner_html_with(
+        self,
+        policy: SanitizePolicy,
+        value: T,
+    ) -> <Self as AddAnyAttr>::Output<InnerHtml<Sanitized<T>>> {
+        self.add_any_attr(inner_html(Sanitized::with_policy(value, policy)))
+    }
+}
+
+impl<T, E, At> SafeInnerHtmlAttribute<T> for HtmlElement<E, At, ()>
+where
+    Self: AddAnyAttr,
+    E: ElementWithChildren,
+    At: Attribute,
+    Sanitized<T>: InnerHtmlValue,
+{
+}
+
+macro_rules! sanitized_inner_html {
+    ($ty:ty, $($lt:lifetime)?) => {
+        impl<$($lt)?> InnerHtmlValue for Sanitized<$ty> {
+            type AsyncOutput = Self;
+            type State = (crate::renderer::types::Element, $ty);
+            type Cloneable = Sanitized<Arc<str>>;
+            type CloneableOwned = Sanitized<Arc<str>>;
+
+            fn html_len(&self) -> usize {
+                self.value.len()
+            }
+
+            fn to_html(self, buf: &mut String) {
+                buf.push_str(&self.policy.clean(&self.value));
+            }
+
+            fn to_template(_buf: &mut String) {}
+
+            fn hydrate<const FROM_SERVER: bool>(
+                self,
+                el: &crate::renderer::types::Element,
+            ) -> Self::State {
+                if !FROM_SERVER {
+                    self.policy.set_inner_html(el, &self.value);
+                }
+                (el.clone(), self.value)
+            }
+
+            fn build(
+                self,
+                el: &crate::renderer::types::Element,
+            ) -> Self::State {
+                self.policy.set_inner_html(el, &self.value);
+                (el.clone(), self.value)
+            }
+
+            fn rebuild(self, state: &mut Self::State) {
+                if self.value != state.1 {
+                    self.policy.set_inner_html(&state.0, &self.value);
+                    state.1 = self.value;
+                }
+            }
+
+            fn into_cloneable(self) -> Self::Cloneable {
+                Sanitized {
+                    value: Arc::from(self.value),
+                    policy: self.policy,
+                }
+            }
+
+            fn into_cloneable_owned(self) -> Self::CloneableOwned {
+                Sanitized {
+                    value: Arc::from(self.value),
+                    policy: self.policy,
+                }
+            }
+
+            fn dry_resolve(&mut self) {}
+
+            async fn resolve(self) -> Self::AsyncOutput {
+                self
+            }
+        }
+    };
+}
+
+sanitized_inner_html!(String,);
+sanitized_inner_html!(Arc<str>,);
+sanitized_inner_html!(&'a str, 'a);
+
+thread_local! {
+    static TRUSTED_TYPES_POLICY: Option<JsValue> = trusted_types_policy();
+}
+
+/// Creates a Trusted Types policy, if the browser supports them. Its `createHTML` passes HTML
+/// through unchanged, as it is only given HTML that has already been sanitized.
+fn trusted_types_policy() -> Option<JsValue> {
+    let trusted_types = Reflect::get(&js_sys::global(), &"trustedTypes".into())
+        .ok()
+        .filter(|trusted_types| !trusted_types.is_undefined())?;
+    let create_policy = Reflect::get(&trusted_types, &"createPolicy".into())
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()?;
+    let options = Object::new();
+    let create_html =
+        Closure::<dyn Fn(String) -> String>::new(|html| html).into_js_value();
+    Reflect::set(&options, &"createHTML".into(), &create_html).ok()?;
+    create_policy
+        .call2(
+            &trusted_types,
+            &JsValue::from_str(TRUSTED_TYPES_POLICY_NAME),
+            &options,
+        )
+        .ok()
+}
+
+/// Sets sanitized HTML as the inner HTML of an element, as `TrustedHTML` if the browser supports
+/// Trusted Types.
+fn set_sanitized_inner_html(el: &crate::renderer::types::Element, html: &str) {
+    let trusted = TRUSTED_TYPES_POLICY.with(|policy| {
+        let policy = policy.as_ref()?;
+        Reflect::get(policy, &"createHTML".into())
+            .ok()?
+            .dyn_into::<Function>()
+            .ok()?
+            .call1(policy, &JsValue::from_str(html))
+            .ok()
+    });
+    match trusted {
+        Some(trusted) => {
+            _ = Reflect::set(el, &"innerHTML".into(), &trusted);
+        }
+        None => Rndr::set_inner_html(el, html),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SanitizePolicy, Sanitized};
+    use crate::html::element::InnerHtmlValue;
+
+    #[test]
+    fn removes_scripts_and_event_handlers() {
+        let mut html = String::new();
+        Sanitized::new(
+            "<p onclick=\"steal()\">Hello, <b>world</b>!</p>\
+             <script>steal()</script><a href=\"javascript:steal()\">link</a>",
+        )
+        .to_html(&mut html);
+        assert_eq!(
+            html,
+            "<p>Hello, <b>world</b>!</p><a rel=\"noopener noreferrer\">link</a>"
+        );
+    }
+
+    #[test]
+    fn applies_custom_policy() {
+        let mut builder = ammonia::Builder::empty();
+        builder.add_tags(["em"]);
+        let policy = SanitizePolicy::new(builder);
+        let mut html = String::new();
+        Sanitized::with_policy(
+            String::from("<p><em>only</em> emphasis</p>"),
+            policy,
+        )
+        .to_html(&mut html);
+        assert_eq!(html, "<em>only</em> emphasis");
+    }
+}
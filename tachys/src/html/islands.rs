@@ -403,10 +403,7 @@ where
             let el =
                 crate::renderer::types::Element::cast_from(cursor.current())
                     .unwrap_or_else(|| {
-                        failed_to_cast_element(
-                            "leptos-children",
-                            cursor.current(),
-                        )
+                        failed_to_cast_element("leptos-children", cursor)
                     });
             let cb = wasm_bindgen::closure::Closure::wrap(
                 on_hydrate as Box<dyn Fn()>,
@@ -44,6 +44,8 @@ pub mod islands;
 pub mod node_ref;
 /// Types for DOM properties.
 pub mod property;
+/// Types for rendering into a shadow root.
+pub mod shadow_root;
 /// Types for the `style` attribute and individual style manipulation.
 pub mod style;
 
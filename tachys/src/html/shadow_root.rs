@@ -0,0 +1,274 @@
+use super::attribute::{any_attribute::AnyAttribute, Attribute};
+use crate::{
+    hydration::Cursor,
+    renderer::{types, Rndr},
+    ssr::StreamBuilder,
+    view::{
+        add_attr::AddAnyAttr, Mountable, Position, PositionState, Render,
+        RenderHtml,
+    },
+};
+
+/// Whether the contents of a shadow root can be accessed from outside it, through the
+/// `shadowRoot` property of its host element.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShadowRootMode {
+    /// The shadow root can be accessed through `element.shadowRoot`.
+    #[default]
+    Open,
+    /// The shadow root cannot be accessed from outside it.
+    Closed,
+}
+
+impl ShadowRootMode {
+    /// The value of the mode as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShadowRootMode::Open => "open",
+            ShadowRootMode::Closed => "closed",
+        }
+    }
+}
+
+/// Renders its children into a shadow root, attached to the element that contains it.
+///
+/// When rendered on the server, this creates a
+/// [declarative shadow root](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/template#shadowrootmode)
+/// with a `<template shadowrootmode>`, which the browser attaches to the parent element when
+/// it parses the HTML. When hydrating, the children are hydrated from inside that shadow root.
+///
+/// A closed shadow root cannot be found again after it has been created by the browser, so
+/// the children of a closed shadow root are rendered again on the client, rather than hydrated.
+///
+/// Declarative shadow roots are only created while parsing the initial HTML document, so a
+/// shadow root inside content that is streamed in later (for example, out-of-order streaming of
+/// a `Suspense`) is also rendered again on the client.
+///
+/// If the parent element cannot have a shadow root, the children are mounted directly into it
+/// instead, and a warning is logged to the console.
+pub struct ShadowRoot<View> {
+    mode: ShadowRootMode,
+    view: View,
+}
+
+/// Creates a [`ShadowRoot`] with the given mode and children.
+pub fn shadow_root<View>(mode: ShadowRootMode, view: View) -> ShadowRoot<View> {
+    ShadowRoot { mode, view }
+}
+
+/// Retained view state for a [`ShadowRoot`].
+pub struct ShadowRootState<State> {
+    mode: ShadowRootMode,
+    // the host and the shadow root attached to it, once it has been mounted, or the host twice
+    // if a shadow root could not be attached to it
+    root: Option<(types::Element, types::Element)>,
+    state: State,
+}
+
+impl<View> ShadowRoot<View> {
+    fn open_tag(&self, buf: &mut String) {
+        buf.push_str("<template shadowrootmode=\"");
+        buf.push_str(self.mode.as_str());
+        buf.push_str("\">");
+    }
+
+    fn close_tag(buf: &mut String) {
+        buf.push_str("</template>");
+    }
+}
+
+impl<View> Render for ShadowRoot<View>
+where
+    View: Render,
+{
+    type State = ShadowRootState<View::State>;
+
+    fn build(self) -> Self::State {
+        ShadowRootState {
+            mode: self.mode,
+            root: None,
+            state: self.view.build(),
+        }
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        self.view.rebuild(&mut state.state);
+    }
+}
+
+impl<State> ShadowRootState<State> {
+    /// Whether the children were mounted directly into the host, because a shadow root could not
+    /// be attached to it.
+    fn is_in_place(&self) -> bool {
+        matches!(&self.root, Some((host, root)) if host == root)
+    }
+}
+
+impl<State> Mountable for ShadowRootState<State>
+where
+    State: Mountable,
+{
+    fn unmount(&mut self) {
+        self.state.unmount();
+    }
+
+    fn mount(&mut self, parent: &types::Element, marker: Option<&types::Node>) {
+        let root = match &self.root {
+            Some((host, root)) if host == parent => root.clone(),
+            _ => {
+                let root = Rndr::attach_shadow(parent, self.mode)
+                    .unwrap_or_else(|| parent.clone());
+                self.root = Some((parent.clone(), root.clone()));
+                root
+            }
+        };
+        if self.is_in_place() {
+            self.state.mount(&root, marker);
+        } else {
+            self.state.mount(&root, None);
+        }
+    }
+
+    fn insert_before_this(&self, child: &mut dyn Mountable) -> bool {
+        // the shadow root is not one of the children of its host, but children mounted in
+        // its place are
+        self.is_in_place() && self.state.insert_before_this(child)
+    }
+
+    fn elements(&self) -> Vec<types::Element> {
+        self.state.elements()
+    }
+}
+
+impl<View> AddAnyAttr for ShadowRoot<View>
+where
+    View: RenderHtml,
+{
+    type Output<SomeNewAttr: Attribute> =
+        ShadowRoot<<View as AddAnyAttr>::Output<SomeNewAttr>>;
+
+    fn add_any_attr<NewAttr: Attribute>(
+        self,
+        attr: NewAttr,
+    ) -> Self::Output<NewAttr>
+    where
+        Self::Output<NewAttr>: RenderHtml,
+    {
+        ShadowRoot {
+            mode: self.mode,
+            view: self.view.add_any_attr(attr),
+        }
+    }
+}
+
+impl<View> RenderHtml for ShadowRoot<View>
+where
+    View: RenderHtml,
+{
+    type AsyncOutput = ShadowRoot<View::AsyncOutput>;
+    type Owned = ShadowRoot<View::Owned>;
+
+    const MIN_LENGTH: usize = "<template shadowrootmode=\"open\">".len()
+        + "</template>".len()
+        + View::MIN_LENGTH;
+
+    fn dry_resolve(&mut self) {
+        self.view.dry_resolve()
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        ShadowRoot {
+            mode: self.mode,
+            view: self.view.resolve().await,
+        }
+    }
+
+    fn to_html_with_buf(
+        self,
+        buf: &mut String,
+        position: &mut Position,
+        escape: bool,
+        mark_branches: bool,
+        extra_attrs: Vec<AnyAttribute>,
+    ) {
+        // the template is not one of the children of its host once it has been parsed,
+        // so the position of the following children is unchanged
+        let mut inner_position = Position::FirstChild;
+        self.open_tag(buf);
+        self.view.to_html_with_buf(
+            buf,
+            &mut inner_position,
+            escape,
+            mark_branches,
+            extra_attrs,
+        );
+        Self::close_tag(buf);
+        _ = position;
+    }
+
+    fn to_html_async_with_buf<const OUT_OF_ORDER: bool>(
+        self,
+        buf: &mut StreamBuilder,
+        position: &mut Position,
+        escape: bool,
+        mark_branches: bool,
+        extra_attrs: Vec<AnyAttribute>,
+    ) where
+        Self: Sized,
+    {
+        let mut inner_position = Position::FirstChild;
+        let mut tag = String::new();
+        self.open_tag(&mut tag);
+        buf.push_sync(&tag);
+
+        self.view.to_html_async_with_buf::<OUT_OF_ORDER>(
+            buf,
+            &mut inner_position,
+            escape,
+            mark_branches,
+            extra_attrs,
+        );
+
+        tag.clear();
+        Self::close_tag(&mut tag);
+        buf.push_sync(&tag);
+        _ = position;
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor,
+        position: &PositionState,
+    ) -> Self::State {
+        let ShadowRoot { mode, view } = self;
+        let host = cursor
+            .current_parent(position)
+            .expect("a shadow root should be the child of an element");
+
+        match Rndr::shadow_root(&host) {
+            Some(root) => {
+                let state = cursor.hydrate_within(&root, position, || {
+                    view.hydrate::<FROM_SERVER>(cursor, position)
+                });
+                ShadowRootState {
+                    mode,
+                    root: Some((host, root)),
+                    state,
+                }
+            }
+            // there is no shadow root to hydrate, so render it from scratch
+            None => {
+                let mut state = Render::build(ShadowRoot { mode, view });
+                state.mount(&host, None);
+                state
+            }
+        }
+    }
+
+    fn into_owned(self) -> Self::Owned {
+        ShadowRoot {
+            mode: self.mode,
+            view: self.view.into_owned(),
+        }
+    }
+}
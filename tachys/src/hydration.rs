@@ -1,11 +1,20 @@
+use crate::{
+    html::attribute::{any_attribute::AnyAttribute, Attribute},
+    ssr::StreamBuilder,
+    view::{add_attr::AddAnyAttr, Render, RenderHtml},
+};
 use crate::{
     renderer::{CastFrom, Rndr},
     view::{Position, PositionState},
 };
 #[cfg(any(debug_assertions, leptos_debuginfo))]
 use std::cell::Cell;
-use std::{cell::RefCell, panic::Location, rc::Rc};
-use web_sys::{Comment, Element, Node, Text};
+use std::{cell::RefCell, fmt, panic::Location, rc::Rc};
+#[cfg(any(debug_assertions, leptos_debuginfo))]
+use wasm_bindgen::JsCast;
+#[cfg(any(debug_assertions, leptos_debuginfo))]
+use web_sys::Node;
+use web_sys::{Comment, Element, Text};
 
 #[cfg(feature = "mark_branches")]
 const COMMENT_NODE: u16 = 8;
@@ -16,14 +25,34 @@ const COMMENT_NODE: u16 = 8;
 /// implements [`RenderHtml`](crate::view::RenderHtml) knows how to advance the cursor to access
 /// the nodes it needs.
 #[derive(Debug)]
-pub struct Cursor(Rc<RefCell<crate::renderer::types::Node>>);
+pub struct Cursor(
+    Rc<RefCell<crate::renderer::types::Node>>,
+    Rc<RefCell<Option<Missing>>>,
+);
 
 impl Clone for Cursor {
     fn clone(&self) -> Self {
-        Self(Rc::clone(&self.0))
+        Self(Rc::clone(&self.0), Rc::clone(&self.1))
     }
 }
 
+/// Where the cursor looked for a node that did not exist, the last time it tried to move.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    not(any(
+        debug_assertions,
+        leptos_debuginfo,
+        feature = "hydration_recovery"
+    )),
+    allow(dead_code)
+)]
+enum Missing {
+    /// The element at which the cursor is located has no children.
+    FirstChildOf(crate::renderer::types::Node),
+    /// The node at which the cursor is located has no next sibling.
+    After(crate::renderer::types::Node),
+}
+
 impl Cursor
 where
     crate::renderer::types::Element: AsRef<crate::renderer::types::Node>,
@@ -34,7 +63,7 @@ where
             crate::renderer::types::Node,
         >>::as_ref(&root)
         .clone();
-        Self(Rc::new(RefCell::new(root)))
+        Self(Rc::new(RefCell::new(root)), Default::default())
     }
 
     /// Returns the node at which the cursor is currently located.
@@ -49,6 +78,9 @@ where
         let mut inner = self.0.borrow_mut();
         if let Some(node) = Rndr::first_child(&inner) {
             *inner = node;
+            *self.1.borrow_mut() = None;
+        } else {
+            *self.1.borrow_mut() = Some(Missing::FirstChildOf(inner.clone()));
         }
 
         #[cfg(feature = "mark_branches")]
@@ -78,6 +110,9 @@ where
         let mut inner = self.0.borrow_mut();
         if let Some(node) = Rndr::next_sibling(&inner) {
             *inner = node;
+            *self.1.borrow_mut() = None;
+        } else {
+            *self.1.borrow_mut() = Some(Missing::After(inner.clone()));
         }
 
         #[cfg(feature = "mark_branches")]
@@ -107,11 +142,51 @@ where
         if let Some(node) = Rndr::get_parent(&inner) {
             *inner = node;
         }
+        *self.1.borrow_mut() = None;
     }
 
     /// Sets the cursor to some node.
     pub fn set(&self, node: crate::renderer::types::Node) {
         *self.0.borrow_mut() = node;
+        *self.1.borrow_mut() = None;
+    }
+
+    /// Returns the element whose children are currently being hydrated.
+    ///
+    /// This is the node at which the cursor is located if it is about to move to its first
+    /// child, or the parent of that node otherwise.
+    pub fn current_parent(
+        &self,
+        position: &PositionState,
+    ) -> Option<crate::renderer::types::Element> {
+        let current = self.current();
+        if position.get() == Position::FirstChild {
+            Rndr::cast_parent(current)
+        } else {
+            Rndr::get_parent_element(&current)
+        }
+    }
+
+    /// Hydrates the children of `parent`, which is not one of the nodes the cursor will walk
+    /// over (for example, a shadow root), by calling `hydrate` with the cursor located at
+    /// `parent`.
+    ///
+    /// The cursor and position are restored afterwards, so hydration continues from where it
+    /// was before.
+    pub fn hydrate_within<T>(
+        &self,
+        parent: &crate::renderer::types::Element,
+        position: &PositionState,
+        hydrate: impl FnOnce() -> T,
+    ) -> T {
+        let prev_node = self.current();
+        let prev_position = position.get();
+        self.set(parent.clone().into());
+        position.set(Position::FirstChild);
+        let value = hydrate();
+        self.set(prev_node);
+        position.set(prev_position);
+        value
     }
 
     /// Advances to the next placeholder node and returns it
@@ -123,8 +198,8 @@ where
         //Rndr::log_node(&self.current());
         self.advance_to_placeholder(position);
         let marker = self.current();
-        crate::renderer::types::Placeholder::cast_from(marker.clone())
-            .unwrap_or_else(|| failed_to_cast_marker_node(marker))
+        crate::renderer::types::Placeholder::cast_from(marker)
+            .unwrap_or_else(|| failed_to_cast_marker_node(self))
     }
 
     /// Advances to the next placeholder node.
@@ -136,11 +211,46 @@ where
         }
         position.set(Position::NextChild);
     }
+
+    /// Replaces the node at which the cursor is located, which does not match the view being
+    /// hydrated, with `node`, and moves the cursor to it.
+    ///
+    /// If the cursor did not find a node the last time it tried to move, `node` is inserted
+    /// where that node was expected instead.
+    #[cfg(feature = "hydration_recovery")]
+    pub(crate) fn replace_mismatched(
+        &self,
+        node: &crate::renderer::types::Node,
+    ) {
+        let missing = self.1.borrow_mut().take();
+        match missing {
+            Some(Missing::FirstChildOf(parent)) => {
+                if let Some(parent) = Rndr::cast_parent(parent) {
+                    Rndr::insert_node(&parent, node, None);
+                }
+            }
+            Some(Missing::After(prev)) => {
+                if let Some(parent) = Rndr::get_parent_element(&prev) {
+                    let anchor = Rndr::next_sibling(&prev);
+                    Rndr::insert_node(&parent, node, anchor.as_ref());
+                }
+            }
+            None => {
+                let current = self.current();
+                if let Some(parent) = Rndr::get_parent_element(&current) {
+                    Rndr::insert_node(&parent, node, Some(&current));
+                    Rndr::remove(&current);
+                }
+            }
+        }
+        self.set(node.clone());
+    }
 }
 
 #[cfg(any(debug_assertions, leptos_debuginfo))]
 thread_local! {
     static CURRENTLY_HYDRATING: Cell<Option<&'static Location<'static>>> = const { Cell::new(None) };
+    static COMPONENT_STACK: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
 }
 
 pub(crate) fn set_currently_hydrating(
@@ -156,31 +266,106 @@ pub(crate) fn set_currently_hydrating(
     }
 }
 
-pub(crate) fn failed_to_cast_element(tag_name: &str, node: Node) -> Element {
+/// Whether `el`, the node at which the cursor is located, is an element with the tag that
+/// hydration expects to find.
+///
+/// This is only checked in debug mode, or when recovering from hydration errors. Without
+/// recovery, a mismatch is only reported as a warning, and hydration continues with `el`.
+#[inline(always)]
+pub(crate) fn is_expected_element(
+    cursor: &Cursor,
+    el: &Element,
+    tag_name: &str,
+) -> bool {
+    #[cfg(any(
+        debug_assertions,
+        leptos_debuginfo,
+        feature = "hydration_recovery"
+    ))]
+    {
+        // if the cursor could not move, it is still located at the previous node
+        let expected = cursor.1.borrow().is_none()
+            // custom elements have no static tag
+            && (tag_name.is_empty()
+                || el.tag_name().eq_ignore_ascii_case(tag_name));
+        #[cfg(not(feature = "hydration_recovery"))]
+        if !expected {
+            report_mismatch(
+                cursor,
+                format_args!("an HTML <{tag_name}> element"),
+                Outcome::Continue,
+            );
+            return true;
+        }
+        expected
+    }
+    #[cfg(not(any(
+        debug_assertions,
+        leptos_debuginfo,
+        feature = "hydration_recovery"
+    )))]
+    {
+        _ = (cursor, el, tag_name);
+        true
+    }
+}
+
+pub(crate) fn failed_to_cast_element(tag_name: &str, cursor: &Cursor) -> ! {
+    unrecoverable_mismatch(cursor, format_args!("an HTML <{tag_name}> element"))
+}
+
+/// Reports that the node at which the cursor is located is not the `<{tag_name}>` element
+/// that hydration expects, before it is rendered on the client instead.
+#[cfg(feature = "hydration_recovery")]
+pub(crate) fn recover_from_element_mismatch(tag_name: &str, cursor: &Cursor) {
+    report_mismatch(
+        cursor,
+        format_args!("an HTML <{tag_name}> element"),
+        Outcome::Recover,
+    );
+}
+
+pub(crate) fn failed_to_cast_marker_node(cursor: &Cursor) -> Comment {
+    #[cfg(feature = "hydration_recovery")]
+    {
+        report_mismatch(
+            cursor,
+            format_args!("a marker node"),
+            Outcome::Recover,
+        );
+        let marker = Rndr::create_placeholder();
+        cursor.replace_mismatched(&marker);
+        marker
+    }
+    #[cfg(not(feature = "hydration_recovery"))]
+    {
+        unrecoverable_mismatch(cursor, format_args!("a marker node"))
+    }
+}
+
+pub(crate) fn failed_to_cast_text_node(cursor: &Cursor, text: &str) -> Text {
+    #[cfg(feature = "hydration_recovery")]
+    {
+        report_mismatch(cursor, format_args!("a text node"), Outcome::Recover);
+        let node = Rndr::create_text_node(text);
+        cursor.replace_mismatched(&node);
+        node
+    }
+    #[cfg(not(feature = "hydration_recovery"))]
+    {
+        _ = text;
+        unrecoverable_mismatch(cursor, format_args!("a text node"))
+    }
+}
+
+fn unrecoverable_mismatch(cursor: &Cursor, expected: fmt::Arguments) -> ! {
+    report_mismatch(cursor, expected, Outcome::Panic);
     #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
     {
-        _ = node;
         unreachable!();
     }
     #[cfg(any(debug_assertions, leptos_debuginfo))]
     {
-        let hydrating = CURRENTLY_HYDRATING
-            .take()
-            .map(|n| n.to_string())
-            .unwrap_or_else(|| "{unknown}".to_string());
-        web_sys::console::error_3(
-            &wasm_bindgen::JsValue::from_str(&format!(
-                "A hydration error occurred while trying to hydrate an \
-                 element defined at {hydrating}.\n\nThe framework expected an \
-                 HTML <{tag_name}> element, but found this instead: ",
-            )),
-            &node,
-            &wasm_bindgen::JsValue::from_str(
-                "\n\nThe hydration mismatch may have occurred slightly \
-                 earlier, but this is the first time the framework found a \
-                 node of an unexpected type.",
-            ),
-        );
         panic!(
             "Unrecoverable hydration error. Please read the error message \
              directly above this for more details."
@@ -188,11 +373,32 @@ pub(crate) fn failed_to_cast_element(tag_name: &str, node: Node) -> Element {
     }
 }
 
-pub(crate) fn failed_to_cast_marker_node(node: Node) -> Comment {
+/// What hydration does after it has reported a mismatch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// which outcomes are possible depends on the `hydration_recovery` feature
+#[allow(dead_code)]
+enum Outcome {
+    /// Hydration cannot continue, and panics.
+    Panic,
+    /// The mismatched node is replaced by rendering the view on the client.
+    Recover,
+    /// Hydration continues with the mismatched node.
+    Continue,
+}
+
+/// Logs a description of the node at which the cursor is located, which is not the node
+/// described by `expected`, along with where it is in the DOM and which components were being
+/// hydrated.
+///
+/// This only logs anything in debug mode.
+fn report_mismatch(
+    cursor: &Cursor,
+    expected: fmt::Arguments,
+    outcome: Outcome,
+) {
     #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
     {
-        _ = node;
-        unreachable!();
+        _ = (cursor, expected, outcome);
     }
     #[cfg(any(debug_assertions, leptos_debuginfo))]
     {
@@ -200,54 +406,358 @@ pub(crate) fn failed_to_cast_marker_node(node: Node) -> Comment {
             .take()
             .map(|n| n.to_string())
             .unwrap_or_else(|| "{unknown}".to_string());
-        web_sys::console::error_3(
-            &wasm_bindgen::JsValue::from_str(&format!(
-                "A hydration error occurred while trying to hydrate an \
-                 element defined at {hydrating}.\n\nThe framework expected a \
-                 marker node, but found this instead: ",
-            )),
-            &node,
-            &wasm_bindgen::JsValue::from_str(
-                "\n\nThe hydration mismatch may have occurred slightly \
-                 earlier, but this is the first time the framework found a \
-                 node of an unexpected type.",
+        let node = cursor.current();
+        let (found, path) = match &*cursor.1.borrow() {
+            Some(Missing::FirstChildOf(parent)) => (
+                "nothing: the element has no children".to_string(),
+                node_path(parent),
+            ),
+            Some(Missing::After(prev)) => (
+                "nothing: there are no more nodes after this one".to_string(),
+                node_path(prev),
             ),
+            None => (describe_node(&node), node_path(&node)),
+        };
+
+        let mut message = format!(
+            "A hydration error occurred while trying to hydrate an element \
+             defined at {hydrating}.\n\nExpected: {expected}\nFound: \
+             {found}\nDOM path: {path}\n"
         );
-        panic!(
-            "Unrecoverable hydration error. Please read the error message \
-             directly above this for more details."
+        COMPONENT_STACK.with_borrow(|components| {
+            if !components.is_empty() {
+                message.push_str("Component stack: ");
+                message.push_str(&components.join(" > "));
+                message.push('\n');
+            }
+        });
+        message.push_str("\nThe node: ");
+
+        let mut note = String::from(
+            "\n\nThe hydration mismatch may have occurred slightly earlier, \
+             but this is the first time the framework found a node of an \
+             unexpected type. Mismatches are often caused by invalid HTML \
+             that the browser corrects while parsing it (like a <div> inside \
+             a <p>), by rendering something different on the server and the \
+             client, or by a browser extension that modifies the page.",
+        );
+        let log = match outcome {
+            Outcome::Panic => web_sys::console::error_3,
+            Outcome::Recover => {
+                note.push_str(
+                    "\n\nBecause hydration recovery is enabled, this node \
+                     will be replaced by rendering the view on the client.",
+                );
+                web_sys::console::warn_3
+            }
+            Outcome::Continue => {
+                note.push_str(
+                    "\n\nHydration will continue with this node. Enable the \
+                     `hydration_recovery` feature to render the view on the \
+                     client instead.",
+                );
+                web_sys::console::warn_3
+            }
+        };
+        log(
+            &wasm_bindgen::JsValue::from_str(&message),
+            &node,
+            &wasm_bindgen::JsValue::from_str(&note),
         );
     }
 }
 
-pub(crate) fn failed_to_cast_text_node(node: Node) -> Text {
-    #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
-    {
-        _ = node;
-        unreachable!();
+/// Describes a node in an error message.
+#[cfg(any(debug_assertions, leptos_debuginfo))]
+fn describe_node(node: &Node) -> String {
+    let truncated = || truncate_text(node.text_content().unwrap_or_default());
+    match node.node_type() {
+        Node::ELEMENT_NODE => format!(
+            "a <{}> element",
+            node.unchecked_ref::<Element>()
+                .tag_name()
+                .to_ascii_lowercase()
+        ),
+        Node::TEXT_NODE => format!("a text node {:?}", truncated()),
+        Node::COMMENT_NODE => format!("a comment <!--{}-->", truncated()),
+        _ => node.node_name(),
     }
+}
+
+/// Returns a CSS selector-like path from the document to `node`, like
+/// `html > body > main:nth-child(2) > #text`.
+#[cfg(any(debug_assertions, leptos_debuginfo))]
+fn node_path(node: &Node) -> String {
+    let mut segments = Vec::new();
+    let mut node = node.clone();
+    loop {
+        if node.node_type() == Node::DOCUMENT_NODE {
+            break;
+        }
+        let parent = if let Some(el) = node.dyn_ref::<Element>() {
+            segments.push(element_path_segment(
+                el.tag_name().to_ascii_lowercase(),
+                &el.id(),
+                || {
+                    let mut index = 1;
+                    let mut sibling = el.previous_element_sibling();
+                    while let Some(prev) = sibling {
+                        index += 1;
+                        sibling = prev.previous_element_sibling();
+                    }
+                    index
+                },
+            ));
+            node.parent_node()
+        } else if let Some(root) = node.dyn_ref::<web_sys::ShadowRoot>() {
+            segments.push("#shadow-root".to_string());
+            Some(root.host().into())
+        } else {
+            segments.push(node.node_name());
+            node.parent_node()
+        };
+        match parent {
+            Some(parent) => node = parent,
+            None => break,
+        }
+    }
+    segments.reverse();
+    segments.join(" > ")
+}
+
+/// Shortens the text of a node, so that it fits in an error message.
+#[cfg(any(debug_assertions, leptos_debuginfo))]
+fn truncate_text(text: String) -> String {
+    const MAX_TEXT: usize = 40;
+
+    match text.char_indices().nth(MAX_TEXT) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+/// Returns the segment of a [`node_path`] for an element with the given lowercase tag name and
+/// ID. Elements without an ID are identified by their position among their siblings, which is
+/// only computed if it is needed.
+#[cfg(any(debug_assertions, leptos_debuginfo))]
+fn element_path_segment(
+    tag: String,
+    id: &str,
+    index: impl FnOnce() -> usize,
+) -> String {
+    if !id.is_empty() {
+        format!("{tag}#{id}")
+    } else if matches!(tag.as_str(), "html" | "head" | "body") {
+        tag
+    } else {
+        format!("{tag}:nth-child({})", index())
+    }
+}
+
+/// A view rendered by a component, which records the name of the component while it is
+/// hydrated, so that hydration errors can report which components were being hydrated.
+///
+/// The name is only recorded in debug mode, or with `--cfg leptos_debuginfo`. Otherwise, this
+/// only holds the view.
+#[derive(Debug, Clone)]
+pub struct ComponentView<T> {
     #[cfg(any(debug_assertions, leptos_debuginfo))]
+    name: &'static str,
+    view: T,
+}
+
+impl<T> ComponentView<T> {
+    /// Wraps the view rendered by the component with the given name.
+    pub fn new(name: &'static str, view: T) -> Self {
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        let _ = name;
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            name,
+            view,
+        }
+    }
+
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> ComponentView<U> {
+        ComponentView {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            name: self.name,
+            view: f(self.view),
+        }
+    }
+
+    fn hydrating<U>(self, f: impl FnOnce(T) -> U) -> U {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            COMPONENT_STACK.with_borrow_mut(|stack| stack.push(self.name));
+            let value = f(self.view);
+            COMPONENT_STACK.with_borrow_mut(|stack| stack.pop());
+            value
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            f(self.view)
+        }
+    }
+}
+
+impl<T> Render for ComponentView<T>
+where
+    T: Render,
+{
+    type State = T::State;
+
+    fn build(self) -> Self::State {
+        self.view.build()
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        self.view.rebuild(state);
+    }
+}
+
+impl<T> AddAnyAttr for ComponentView<T>
+where
+    T: AddAnyAttr,
+{
+    type Output<SomeNewAttr: Attribute> = ComponentView<T::Output<SomeNewAttr>>;
+
+    fn add_any_attr<NewAttr: Attribute>(
+        self,
+        attr: NewAttr,
+    ) -> Self::Output<NewAttr>
+    where
+        Self::Output<NewAttr>: RenderHtml,
     {
-        let hydrating = CURRENTLY_HYDRATING
-            .take()
-            .map(|n| n.to_string())
-            .unwrap_or_else(|| "{unknown}".to_string());
-        web_sys::console::error_3(
-            &wasm_bindgen::JsValue::from_str(&format!(
-                "A hydration error occurred while trying to hydrate an \
-                 element defined at {hydrating}.\n\nThe framework expected a \
-                 text node, but found this instead: ",
-            )),
-            &node,
-            &wasm_bindgen::JsValue::from_str(
-                "\n\nThe hydration mismatch may have occurred slightly \
-                 earlier, but this is the first time the framework found a \
-                 node of an unexpected type.",
-            ),
+        self.map(|view| view.add_any_attr(attr))
+    }
+}
+
+impl<T> RenderHtml for ComponentView<T>
+where
+    T: RenderHtml,
+{
+    type AsyncOutput = ComponentView<T::AsyncOutput>;
+    type Owned = ComponentView<T::Owned>;
+
+    const MIN_LENGTH: usize = T::MIN_LENGTH;
+    const EXISTS: bool = T::EXISTS;
+
+    fn dry_resolve(&mut self) {
+        self.view.dry_resolve();
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        let view = self.view.resolve().await;
+        ComponentView {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            name: self.name,
+            view,
+        }
+    }
+
+    fn html_len(&self) -> usize {
+        self.view.html_len()
+    }
+
+    fn to_html_with_buf(
+        self,
+        buf: &mut String,
+        position: &mut Position,
+        escape: bool,
+        mark_branches: bool,
+        extra_attrs: Vec<AnyAttribute>,
+    ) {
+        self.view.to_html_with_buf(
+            buf,
+            position,
+            escape,
+            mark_branches,
+            extra_attrs,
         );
-        panic!(
-            "Unrecoverable hydration error. Please read the error message \
-             directly above this for more details."
+    }
+
+    fn to_html_async_with_buf<const OUT_OF_ORDER: bool>(
+        self,
+        buf: &mut StreamBuilder,
+        position: &mut Position,
+        escape: bool,
+        mark_branches: bool,
+        extra_attrs: Vec<AnyAttribute>,
+    ) where
+        Self: Sized,
+    {
+        self.view.to_html_async_with_buf::<OUT_OF_ORDER>(
+            buf,
+            position,
+            escape,
+            mark_branches,
+            extra_attrs,
+        );
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor,
+        position: &PositionState,
+    ) -> Self::State {
+        self.hydrating(|view| view.hydrate::<FROM_SERVER>(cursor, position))
+    }
+
+    async fn hydrate_async(
+        self,
+        cursor: &Cursor,
+        position: &PositionState,
+    ) -> Self::State {
+        // the component stack is not tracked across await points
+        self.view.hydrate_async(cursor, position).await
+    }
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(RenderHtml::into_owned)
+    }
+}
+
+#[cfg(all(test, any(debug_assertions, leptos_debuginfo)))]
+mod tests {
+    use super::{element_path_segment, truncate_text};
+
+    #[test]
+    fn short_text_is_not_truncated() {
+        assert_eq!(truncate_text(String::new()), "");
+        let text = "a".repeat(40);
+        assert_eq!(truncate_text(text.clone()), text);
+    }
+
+    #[test]
+    fn long_text_is_truncated_on_a_char_boundary() {
+        let text = "é".repeat(50);
+        assert_eq!(truncate_text(text), format!("{}…", "é".repeat(40)));
+    }
+
+    #[test]
+    fn elements_with_an_id_are_identified_by_it() {
+        let segment = element_path_segment("main".into(), "app", || {
+            panic!("the index is not needed")
+        });
+        assert_eq!(segment, "main#app");
+    }
+
+    #[test]
+    fn document_elements_are_identified_by_their_tag() {
+        for tag in ["html", "head", "body"] {
+            let segment = element_path_segment(tag.into(), "", || {
+                panic!("the index is not needed")
+            });
+            assert_eq!(segment, tag);
+        }
+    }
+
+    #[test]
+    fn other_elements_are_identified_by_their_position() {
+        assert_eq!(
+            element_path_segment("li".into(), "", || 3),
+            "li:nth-child(3)"
         );
     }
 }
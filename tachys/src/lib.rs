@@ -17,6 +17,8 @@
 
 /// Commonly-used traits.
 pub mod prelude {
+    #[cfg(feature = "sanitize")]
+    pub use crate::html::element::SafeInnerHtmlAttribute;
     pub use crate::{
         html::{
             attribute::{
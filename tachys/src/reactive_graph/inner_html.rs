@@ -1,6 +1,8 @@
 use super::{ReactiveFunction, SharedReactiveFunction};
 use crate::html::element::InnerHtmlValue;
 use reactive_graph::effect::RenderEffect;
+#[cfg(feature = "sanitize")]
+use std::sync::Arc;
 
 impl<F, V> InnerHtmlValue for F
 where
@@ -86,6 +88,103 @@ where
     }
 }
 
+#[cfg(feature = "sanitize")]
+impl<F, V> InnerHtmlValue for crate::html::element::Sanitized<F>
+where
+    F: ReactiveFunction<Output = V>,
+    V: crate::html::element::SanitizeInput + 'static,
+{
+    type AsyncOutput = crate::html::element::Sanitized<Arc<str>>;
+    type State = RenderEffect<(crate::renderer::types::Element, V)>;
+    type Cloneable = crate::html::element::Sanitized<SharedReactiveFunction<V>>;
+    type CloneableOwned =
+        crate::html::element::Sanitized<SharedReactiveFunction<V>>;
+
+    fn html_len(&self) -> usize {
+        0
+    }
+
+    fn to_html(mut self, buf: &mut String) {
+        let value = self.value.invoke();
+        buf.push_str(&self.policy.clean(value.as_ref()));
+    }
+
+    fn to_template(_buf: &mut String) {}
+
+    fn hydrate<const FROM_SERVER: bool>(
+        mut self,
+        el: &crate::renderer::types::Element,
+    ) -> Self::State {
+        let el = el.to_owned();
+        RenderEffect::new(move |prev| {
+            let value = self.value.invoke();
+            match prev {
+                Some(state) => self.policy.rebuild(value, state),
+                None => {
+                    if !FROM_SERVER {
+                        self.policy.set_inner_html(&el, value.as_ref());
+                    }
+                    (el.clone(), value)
+                }
+            }
+        })
+    }
+
+    fn build(mut self, el: &crate::renderer::types::Element) -> Self::State {
+        let el = el.to_owned();
+        RenderEffect::new(move |prev| {
+            let value = self.value.invoke();
+            match prev {
+                Some(state) => self.policy.rebuild(value, state),
+                None => {
+                    self.policy.set_inner_html(&el, value.as_ref());
+                    (el.clone(), value)
+                }
+            }
+        })
+    }
+
+    fn rebuild(mut self, state: &mut Self::State) {
+        let prev_value = state.take_value();
+        *state = RenderEffect::new_with_value(
+            move |prev| {
+                let value = self.value.invoke();
+                if let Some(state) = prev {
+                    self.policy.rebuild(value, state)
+                } else {
+                    unreachable!()
+                }
+            },
+            prev_value,
+        );
+    }
+
+    fn into_cloneable(self) -> Self::Cloneable {
+        crate::html::element::Sanitized {
+            value: self.value.into_shared(),
+            policy: self.policy,
+        }
+    }
+
+    fn into_cloneable_owned(self) -> Self::CloneableOwned {
+        crate::html::element::Sanitized {
+            value: self.value.into_shared(),
+            policy: self.policy,
+        }
+    }
+
+    fn dry_resolve(&mut self) {
+        self.value.invoke();
+    }
+
+    async fn resolve(mut self) -> Self::AsyncOutput {
+        crate::html::element::Sanitized {
+            value: Arc::from(self.value.invoke().as_ref()),
+            policy: self.policy,
+        }
+    }
+}
+
 macro_rules! inner_html_reactive {
     ($name:ident, <$($gen:ident),*>, $v:ty, $( $where_clause:tt )*) =>
     {
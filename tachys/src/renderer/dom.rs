@@ -158,6 +158,70 @@ impl Dom {
         node.parent_node()
     }
 
+    /// Casts a node to an [`Element`] that other nodes can be mounted into.
+    ///
+    /// This is either an element or a shadow root, which supports the same methods for
+    /// inserting and removing children.
+    pub fn cast_parent(node: Node) -> Option<Element> {
+        if node.has_type::<web_sys::ShadowRoot>() {
+            Some(node.unchecked_into())
+        } else {
+            node.dyn_into().ok()
+        }
+    }
+
+    /// Returns the shadow root attached to `host`, if it is open.
+    ///
+    /// The shadow root is returned as an [`Element`] that other nodes can be mounted into.
+    pub fn shadow_root(host: &Element) -> Option<Element> {
+        host.shadow_root().map(JsCast::unchecked_into)
+    }
+
+    /// Attaches a shadow root with the given mode to `host`, and returns it as an [`Element`]
+    /// that other nodes can be mounted into.
+    ///
+    /// If `host` already has an open shadow root, or has a closed shadow root that was created
+    /// declaratively, returns that shadow root instead.
+    ///
+    /// Returns `None`, and warns in the console, if `host` cannot have a shadow root, either
+    /// because of its tag name or because the browser does not support shadow roots.
+    pub fn attach_shadow(
+        host: &Element,
+        mode: crate::html::shadow_root::ShadowRootMode,
+    ) -> Option<Element> {
+        if let Some(root) = Self::shadow_root(host) {
+            return Some(root);
+        }
+        let mode = match mode {
+            crate::html::shadow_root::ShadowRootMode::Open => {
+                web_sys::ShadowRootMode::Open
+            }
+            crate::html::shadow_root::ShadowRootMode::Closed => {
+                web_sys::ShadowRootMode::Closed
+            }
+        };
+        match host.attach_shadow(&web_sys::ShadowRootInit::new(mode)) {
+            Ok(root) => Some(root.unchecked_into()),
+            Err(err) => {
+                web_sys::console::warn_3(
+                    &JsValue::from_str(
+                        "[WARNING] Could not attach a shadow root, so its \
+                         children will be rendered directly into the host \
+                         element instead. The host element: ",
+                    ),
+                    host,
+                    &err,
+                );
+                None
+            }
+        }
+    }
+
+    /// Returns the parent of a node, if it is an element or a shadow root.
+    pub fn get_parent_element(node: &Node) -> Option<Element> {
+        Self::get_parent(node).and_then(Self::cast_parent)
+    }
+
     pub fn first_child(node: &Node) -> Option<Node> {
         #[cfg(debug_assertions)]
         {
@@ -225,7 +289,7 @@ impl Dom {
     where
         M: Mountable,
     {
-        let parent = Element::cast_from(
+        let parent = Self::cast_parent(
             Self::get_parent(before).expect("could not find parent element"),
         )
         .expect("placeholder parent should be Element");
@@ -240,9 +304,7 @@ impl Dom {
     where
         M: Mountable,
     {
-        if let Some(parent) =
-            Self::get_parent(before).and_then(Element::cast_from)
-        {
+        if let Some(parent) = Self::get_parent_element(before) {
             new_child.mount(&parent, Some(before));
             true
         } else {
@@ -623,7 +685,7 @@ impl Mountable for Node {
     }
 
     fn insert_before_this(&self, child: &mut dyn Mountable) -> bool {
-        let parent = Dom::get_parent(self).and_then(Element::cast_from);
+        let parent = Dom::get_parent_element(self);
         if let Some(parent) = parent {
             child.mount(&parent, Some(self));
             return true;
@@ -650,8 +712,7 @@ impl Mountable for Text {
     }
 
     fn insert_before_this(&self, child: &mut dyn Mountable) -> bool {
-        let parent =
-            Dom::get_parent(self.as_ref()).and_then(Element::cast_from);
+        let parent = Dom::get_parent_element(self.as_ref());
         if let Some(parent) = parent {
             child.mount(&parent, Some(self));
             return true;
@@ -678,8 +739,7 @@ impl Mountable for Comment {
     }
 
     fn insert_before_this(&self, child: &mut dyn Mountable) -> bool {
-        let parent =
-            Dom::get_parent(self.as_ref()).and_then(Element::cast_from);
+        let parent = Dom::get_parent_element(self.as_ref());
         if let Some(parent) = parent {
             child.mount(&parent, Some(self));
             return true;
@@ -702,8 +762,7 @@ impl Mountable for Element {
     }
 
     fn insert_before_this(&self, child: &mut dyn Mountable) -> bool {
-        let parent =
-            Dom::get_parent(self.as_ref()).and_then(Element::cast_from);
+        let parent = Dom::get_parent_element(self.as_ref());
         if let Some(parent) = parent {
             child.mount(&parent, Some(self));
             return true;
@@ -29,6 +29,9 @@ pub mod types {
     };
 }
 
+// These renderers are not compiled: they implement `Renderer` for their own node types, but
+// views only mount into `Rndr`, so there is currently no way to render a component into the mock
+// DOM (for example, to test it natively). See [`Rndr`].
 /* #[cfg(feature = "testing")]
 /// A renderer based on a mock DOM.
 pub mod mock_dom;
@@ -38,9 +41,12 @@ pub mod sledgehammer; */
 
 /// Implements the instructions necessary to render an interface on some platform.
 ///
-/// By default, this is implemented for the Document Object Model (DOM) in a Web
-/// browser, but implementing this trait for some other platform allows you to use
-/// the library to render any tree-based UI.
+/// This is implemented for the Document Object Model (DOM) in a Web browser.
+///
+/// ### Note
+/// Implementing this trait for some other platform (like a terminal) does not currently allow
+/// the library's views to be rendered there: view types are built on [`Rndr`] and its
+/// [`types`] directly, and [`Mountable`] mounts into a DOM element. See [`Rndr`] for why.
 pub trait Renderer: Send + Sized + Debug + 'static {
     /// The basic type of node in the view tree.
     type Node: Mountable + Clone + 'static;
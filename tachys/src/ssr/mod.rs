@@ -41,6 +41,25 @@ impl StreamBuilder {
         }
     }
 
+    /// Creates a new stream that renders into an existing buffer, which can be reused across
+    /// requests with [`into_buffer`](Self::into_buffer). Any existing contents are cleared.
+    pub fn with_buffer(mut buf: String, id: Option<Vec<u16>>) -> Self {
+        buf.clear();
+        Self {
+            id,
+            sync_buf: buf,
+            ..Default::default()
+        }
+    }
+
+    /// Consumes the stream and returns its (cleared) synchronous buffer, so that its allocation
+    /// can be reused for another request.
+    pub fn into_buffer(mut self) -> String {
+        let mut buf = mem::take(&mut self.sync_buf);
+        buf.clear();
+        buf
+    }
+
     /// Reserves additional space in the synchronous buffer.
     pub fn reserve(&mut self, additional: usize) {
         self.sync_buf.reserve(additional);
@@ -92,17 +111,64 @@ impl StreamBuilder {
 
     /// Completes the stream.
     pub fn finish(mut self) -> Self {
-        let sync_buf_remaining = mem::take(&mut self.sync_buf);
-        if sync_buf_remaining.is_empty() {
+        // if there are no other chunks, the synchronous buffer is sent as it is
+        if self.sync_buf.is_empty() || self.chunks.is_empty() {
             return self;
-        } else if let Some(StreamChunk::Sync(buf)) = self.chunks.back_mut() {
+        }
+        let mut sync_buf_remaining = mem::take(&mut self.sync_buf);
+        if let Some(StreamChunk::Sync(buf)) = self.chunks.back_mut() {
             buf.push_str(&sync_buf_remaining);
+            // keep the buffer's allocation, so it can be reused
+            sync_buf_remaining.clear();
+            self.sync_buf = sync_buf_remaining;
         } else {
             self.chunks.push_back(StreamChunk::Sync(sync_buf_remaining));
         }
         self
     }
 
+    /// Waits for the next chunk of HTML and writes it into `buf`, returning `false` once the
+    /// stream has finished.
+    ///
+    /// Unlike reading the stream as a [`Stream`] of `String`s, this does not allocate a new
+    /// `String` for each chunk: chunks are rendered into the stream's own buffer, which is reused.
+    #[cfg(feature = "bytes")]
+    pub async fn next_chunk_into(
+        &mut self,
+        buf: &mut impl bytes::BufMut,
+    ) -> bool {
+        let ready =
+            std::future::poll_fn(|cx| Pin::new(&mut *self).poll_chunk(cx))
+                .await;
+        if ready {
+            buf.put_slice(self.sync_buf.as_bytes());
+            self.sync_buf.clear();
+        }
+        ready
+    }
+
+    /// Writes the whole stream into `writer`.
+    ///
+    /// Each chunk is written as soon as it is ready, and the stream is not polled for the next
+    /// chunk until `writer` has accepted the previous one, so a slow client applies backpressure
+    /// to rendering. Chunks are rendered into the stream's own buffer, which is reused for every
+    /// chunk rather than allocating a new `String` for each.
+    #[cfg(feature = "tokio")]
+    pub async fn write_to<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: tokio::io::AsyncWrite + Unpin + ?Sized,
+    {
+        use tokio::io::AsyncWriteExt;
+
+        while std::future::poll_fn(|cx| Pin::new(&mut *self).poll_chunk(cx))
+            .await
+        {
+            writer.write_all(self.sync_buf.as_bytes()).await?;
+            self.sync_buf.clear();
+        }
+        writer.flush().await
+    }
+
     // Out-of-Order Streaming
     /// Pushes a fallback for out-of-order streaming.
     pub fn push_fallback<View>(
@@ -352,13 +418,15 @@ impl Debug for StreamChunk {
     }
 }
 
-impl Stream for StreamBuilder {
-    type Item = String;
-
-    fn poll_next(
+impl StreamBuilder {
+    /// Polls the stream until the synchronous buffer holds the next chunk of HTML that is ready
+    /// to be sent, returning `false` once the stream has finished.
+    ///
+    /// The caller is responsible for emptying the buffer after each chunk.
+    fn poll_chunk(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
-    ) -> Poll<Option<Self::Item>> {
+    ) -> Poll<bool> {
         let mut this = self.as_mut();
         let pending = this.pending.take();
         if let Some(mut pending) = pending {
@@ -371,7 +439,7 @@ impl Stream for StreamBuilder {
                     for chunk in chunks.into_iter().rev() {
                         this.chunks.push_front(chunk);
                     }
-                    self.poll_next(cx)
+                    self.poll_chunk(cx)
                 }
             }
         } else {
@@ -380,9 +448,9 @@ impl Stream for StreamBuilder {
                 None => {
                     if this.pending_ooo.is_empty() {
                         if this.sync_buf.is_empty() {
-                            Poll::Ready(None)
+                            Poll::Ready(false)
                         } else {
-                            Poll::Ready(Some(mem::take(&mut this.sync_buf)))
+                            Poll::Ready(true)
                         }
                     } else {
                         // check if *any* pending out-of-order chunk is ready
@@ -461,12 +529,17 @@ impl Stream for StreamBuilder {
                         if this.sync_buf.is_empty() {
                             Poll::Pending
                         } else {
-                            Poll::Ready(Some(mem::take(&mut this.sync_buf)))
+                            Poll::Ready(true)
                         }
                     }
                 }
-                Some(StreamChunk::Sync(value)) => {
-                    this.sync_buf.push_str(&value);
+                Some(StreamChunk::Sync(mut value)) => {
+                    if this.sync_buf.is_empty() {
+                        // take the chunk's allocation rather than copying it
+                        mem::swap(&mut this.sync_buf, &mut value);
+                    } else {
+                        this.sync_buf.push_str(&value);
+                    }
                     loop {
                         match this.chunks.pop_front() {
                             None => break,
@@ -487,22 +560,22 @@ impl Stream for StreamBuilder {
                         }
                     }
 
-                    this.poll_next(cx)
+                    this.poll_chunk(cx)
                 }
                 Some(StreamChunk::Async { chunks, .. }) => {
                     this.pending = Some(chunks);
                     if this.sync_buf.is_empty() {
-                        self.poll_next(cx)
+                        self.poll_chunk(cx)
                     } else {
-                        Poll::Ready(Some(mem::take(&mut this.sync_buf)))
+                        Poll::Ready(true)
                     }
                 }
                 Some(StreamChunk::OutOfOrder { chunks, .. }) => {
                     this.pending_ooo.push_back(chunks);
                     if this.sync_buf.is_empty() {
-                        self.poll_next(cx)
+                        self.poll_chunk(cx)
                     } else {
-                        Poll::Ready(Some(mem::take(&mut this.sync_buf)))
+                        Poll::Ready(true)
                     }
                 }
             }
@@ -510,6 +583,53 @@ impl Stream for StreamBuilder {
     }
 }
 
+impl Stream for StreamBuilder {
+    type Item = String;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.as_mut()
+            .poll_chunk(cx)
+            .map(|ready| ready.then(|| mem::take(&mut self.sync_buf)))
+    }
+}
+
+#[cfg(all(test, feature = "ssr", feature = "tokio"))]
+mod write_tests {
+    use crate::{
+        html::element::{em, main, p, ElementChild},
+        view::RenderHtml,
+    };
+
+    #[tokio::test]
+    async fn writes_stream_into_async_write() {
+        let el = main().child(p().child(("Hello, ", em().child("world"), "!")));
+        let mut stream =
+            el.to_html_stream_in_order_with_buffer(String::with_capacity(64));
+        let mut out = Vec::new();
+        stream.write_to(&mut out).await.unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<main><p>Hello, <em>world</em>!</p></main>"
+        );
+
+        let buf = stream.into_buffer();
+        assert!(buf.is_empty());
+        assert!(buf.capacity() >= 64);
+    }
+
+    #[cfg(feature = "bytes")]
+    #[tokio::test]
+    async fn writes_chunks_into_buf_mut() {
+        let mut stream = main().child("Hello").to_html_stream_out_of_order();
+        let mut out = bytes::BytesMut::new();
+        while stream.next_chunk_into(&mut out).await {}
+        assert_eq!(&out[..], b"<main>Hello</main>");
+    }
+}
+
 /*
 #[cfg(test)]
 mod tests {
@@ -5,13 +5,18 @@ use super::{
 use crate::{
     html::attribute::{any_attribute::AnyAttribute, Attribute},
     hydration::Cursor,
-    renderer::{CastFrom, Rndr},
+    renderer::Rndr,
     ssr::StreamBuilder,
 };
 use drain_filter_polyfill::VecExt as VecDrainFilterExt;
 use indexmap::IndexSet;
-use rustc_hash::FxHasher;
-use std::hash::{BuildHasherDefault, Hash};
+use rustc_hash::{FxHashSet, FxHasher};
+use std::{
+    cell::RefCell,
+    hash::{BuildHasherDefault, Hash},
+    rc::Rc,
+    sync::Arc,
+};
 
 type FxIndexSet<T> = IndexSet<T, BuildHasherDefault<FxHasher>>;
 
@@ -51,11 +56,74 @@ where
             .collect::<Vec<_>>(),
         key_fn,
         view_fn,
+        animation: NoAnimation,
+    }
+}
+
+/// Animates the items of a keyed list as they enter, leave, or move within it.
+///
+/// This is only used when an existing list is updated, not when it is first rendered or hydrated.
+pub trait ListAnimation: Send + Sync {
+    /// Called with the elements of an item after it has been added to the list.
+    fn enter(&self, elements: &[crate::renderer::types::Element]);
+
+    /// Called with the elements of an item that has been removed from the list. The elements
+    /// remain in the DOM until `done` is called.
+    fn leave(
+        &self,
+        elements: &[crate::renderer::types::Element],
+        done: Box<dyn FnOnce()>,
+    );
+
+    /// Called with the elements of every item that stays in the list, before the DOM is updated.
+    ///
+    /// Any of these items may change position, whether because it was moved or because items
+    /// before it were added or removed.
+    fn before_move(&self, elements: &[crate::renderer::types::Element]);
+
+    /// Called with the same elements as [`before_move`](Self::before_move), in the same order,
+    /// after the DOM has been updated.
+    fn after_move(&self, elements: &[crate::renderer::types::Element]);
+}
+
+/// Whether a [`Keyed`] list animates its items, as set by [`Keyed::with_animation`].
+///
+/// `S` is the view state of each item. Only animated lists keep removed items mounted until
+/// their leave animation has finished, so only they require it to be `'static`.
+pub trait KeyedAnimation<S> {
+    /// Returns the animation, and a function that boxes removed items so that they can stay
+    /// mounted while they leave, or `None` if the list is not animated.
+    fn animation(&self) -> Option<(&dyn ListAnimation, KeepLeaving<S>)>;
+}
+
+/// Boxes a removed item so that it can stay mounted until its leave animation has finished.
+pub type KeepLeaving<S> = fn(S) -> Box<dyn Mountable>;
+
+/// A [`Keyed`] list that is not animated.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoAnimation;
+
+impl<S> KeyedAnimation<S> for NoAnimation {
+    fn animation(&self) -> Option<(&dyn ListAnimation, KeepLeaving<S>)> {
+        None
+    }
+}
+
+/// A [`Keyed`] list that is animated with a [`ListAnimation`].
+#[derive(Clone)]
+pub struct Animated(Arc<dyn ListAnimation>);
+
+impl<S> KeyedAnimation<S> for Animated
+where
+    S: Mountable + 'static,
+{
+    fn animation(&self) -> Option<(&dyn ListAnimation, KeepLeaving<S>)> {
+        Some((&*self.0, |item| Box::new(item)))
     }
 }
 
 /// A keyed list of views.
-pub struct Keyed<T, I, K, KF, VF, VFS, V>
+pub struct Keyed<T, I, K, KF, VF, VFS, V, A = NoAnimation>
 where
     I: IntoIterator<Item = T>,
     K: Eq + Hash + 'static,
@@ -68,6 +136,35 @@ where
     ssr_items: Vec<(String, V)>,
     key_fn: KF,
     view_fn: VF,
+    animation: A,
+}
+
+impl<T, I, K, KF, VF, VFS, V> Keyed<T, I, K, KF, VF, VFS, V>
+where
+    I: IntoIterator<Item = T>,
+    K: Eq + Hash + 'static,
+    KF: Fn(&T) -> K,
+    VF: Fn(usize, T) -> (VFS, V),
+    VFS: Fn(usize),
+{
+    /// Animates items as they are added to, removed from, or moved within the list when it is
+    /// updated.
+    ///
+    /// Removed items stay mounted until their leave animation has finished, so the view state
+    /// of each item must be `'static`.
+    pub fn with_animation(
+        self,
+        animation: Arc<dyn ListAnimation>,
+    ) -> Keyed<T, I, K, KF, VF, VFS, V, Animated> {
+        Keyed {
+            items: self.items,
+            #[cfg(feature = "ssr")]
+            ssr_items: self.ssr_items,
+            key_fn: self.key_fn,
+            view_fn: self.view_fn,
+            animation: Animated(animation),
+        }
+    }
 }
 
 /// By default, keys used in for keyed iteration do not need to be serializable.
@@ -114,9 +211,15 @@ where
     marker: crate::renderer::types::Placeholder,
     hashed_items: IndexSet<K, BuildHasherDefault<FxHasher>>,
     rendered_items: Vec<Option<(VFS, V::State)>>,
+    /// Removed items whose leave animation has not finished yet.
+    leaving: Leaving,
 }
 
-impl<T, I, K, KF, VF, VFS, V> Render for Keyed<T, I, K, KF, VF, VFS, V>
+/// Removed items whose leave animation has not finished yet, each with a token that identifies
+/// it to the `done` callback of its animation.
+type Leaving = Rc<RefCell<Vec<(Rc<()>, Box<dyn Mountable>)>>>;
+
+impl<T, I, K, KF, VF, VFS, V, A> Render for Keyed<T, I, K, KF, VF, VFS, V, A>
 where
     I: IntoIterator<Item = T>,
     K: Eq + Hash + SerializableKey + 'static,
@@ -124,6 +227,7 @@ where
     V: Render,
     VF: Fn(usize, T) -> (VFS, V),
     VFS: Fn(usize),
+    A: KeyedAnimation<V::State>,
 {
     type State = KeyedState<K, VFS, V>;
 
@@ -143,6 +247,7 @@ where
             marker: Rndr::create_placeholder(),
             hashed_items,
             rendered_items,
+            leaving: Default::default(),
         }
     }
 
@@ -152,6 +257,7 @@ where
             marker,
             hashed_items,
             ref mut rendered_items,
+            leaving,
         } = state;
         let new_items = self.items.into_iter().flatten();
         let (capacity, _) = new_items.size_hint();
@@ -173,73 +279,91 @@ where
             rendered_items,
             &self.view_fn,
             items,
+            self.animation.animation(),
+            leaving,
         );
 
         *hashed_items = new_hashed_items;
     }
 }
 
-impl<T, I, K, KF, VF, VFS, V> AddAnyAttr for Keyed<T, I, K, KF, VF, VFS, V>
-where
-    I: IntoIterator<Item = T> + Send + 'static,
-    K: Eq + Hash + SerializableKey + 'static,
-    KF: Fn(&T) -> K + Send + 'static,
-    V: RenderHtml,
-    V: 'static,
-    VF: Fn(usize, T) -> (VFS, V) + Send + 'static,
-    VFS: Fn(usize) + 'static,
-    T: 'static,
-{
-    type Output<SomeNewAttr: Attribute> = Keyed<
-        T,
-        I,
-        K,
-        KF,
-        Box<
-            dyn Fn(
-                    usize,
-                    T,
-                ) -> (
-                    VFS,
-                    <V as AddAnyAttr>::Output<SomeNewAttr::CloneableOwned>,
-                ) + Send,
-        >,
-        VFS,
-        V::Output<SomeNewAttr::CloneableOwned>,
-    >;
-
-    fn add_any_attr<NewAttr: Attribute>(
-        self,
-        attr: NewAttr,
-    ) -> Self::Output<NewAttr>
-    where
-        Self::Output<NewAttr>: RenderHtml,
-    {
-        let Keyed {
-            items,
-            #[cfg(feature = "ssr")]
-            ssr_items,
-            key_fn,
-            view_fn,
-        } = self;
-        let attr = attr.into_cloneable_owned();
-        Keyed {
-            items,
-            key_fn,
-            #[cfg(feature = "ssr")]
-            ssr_items: ssr_items
-                .into_iter()
-                .map(|(k, v)| (k, v.add_any_attr(attr.clone())))
-                .collect(),
-            view_fn: Box::new(move |index, item| {
-                let (index, view) = view_fn(index, item);
-                (index, view.add_any_attr(attr.clone()))
-            }),
+// an animated list can only be rendered if its items' view states are `'static`, which is not
+// known for every attribute that could be added, so each kind of list has its own impl
+macro_rules! keyed_add_any_attr {
+    ($animation:ty) => {
+        impl<T, I, K, KF, VF, VFS, V> AddAnyAttr
+            for Keyed<T, I, K, KF, VF, VFS, V, $animation>
+        where
+            I: IntoIterator<Item = T> + Send + 'static,
+            K: Eq + Hash + SerializableKey + 'static,
+            KF: Fn(&T) -> K + Send + 'static,
+            V: RenderHtml,
+            V: 'static,
+            VF: Fn(usize, T) -> (VFS, V) + Send + 'static,
+            VFS: Fn(usize) + 'static,
+            T: 'static,
+        {
+            type Output<SomeNewAttr: Attribute> = Keyed<
+                T,
+                I,
+                K,
+                KF,
+                Box<
+                    dyn Fn(
+                            usize,
+                            T,
+                        ) -> (
+                            VFS,
+                            <V as AddAnyAttr>::Output<
+                                SomeNewAttr::CloneableOwned,
+                            >,
+                        ) + Send,
+                >,
+                VFS,
+                V::Output<SomeNewAttr::CloneableOwned>,
+                $animation,
+            >;
+
+            fn add_any_attr<NewAttr: Attribute>(
+                self,
+                attr: NewAttr,
+            ) -> Self::Output<NewAttr>
+            where
+                Self::Output<NewAttr>: RenderHtml,
+            {
+                let Keyed {
+                    items,
+                    #[cfg(feature = "ssr")]
+                    ssr_items,
+                    key_fn,
+                    view_fn,
+                    animation,
+                } = self;
+                let attr = attr.into_cloneable_owned();
+                Keyed {
+                    items,
+                    key_fn,
+                    #[cfg(feature = "ssr")]
+                    ssr_items: ssr_items
+                        .into_iter()
+                        .map(|(k, v)| (k, v.add_any_attr(attr.clone())))
+                        .collect(),
+                    view_fn: Box::new(move |index, item| {
+                        let (index, view) = view_fn(index, item);
+                        (index, view.add_any_attr(attr.clone()))
+                    }),
+                    animation,
+                }
+            }
         }
-    }
+    };
 }
 
-impl<T, I, K, KF, VF, VFS, V> RenderHtml for Keyed<T, I, K, KF, VF, VFS, V>
+keyed_add_any_attr!(NoAnimation);
+keyed_add_any_attr!(Animated);
+
+impl<T, I, K, KF, VF, VFS, V, A> RenderHtml
+    for Keyed<T, I, K, KF, VF, VFS, V, A>
 where
     I: IntoIterator<Item = T> + Send + 'static,
     K: Eq + Hash + SerializableKey + 'static,
@@ -248,6 +372,8 @@ where
     VF: Fn(usize, T) -> (VFS, V) + Send + 'static,
     VFS: Fn(usize) + 'static,
     T: 'static,
+    A: KeyedAnimation<V::State> + Send + 'static,
+    Self: AddAnyAttr,
 {
     type AsyncOutput = Vec<V::AsyncOutput>; // TODO
     type Owned = Self;
@@ -374,7 +500,7 @@ where
             Rndr::get_parent(&current)
                 .expect("first child of keyed list has no parent")
         };
-        let parent = crate::renderer::types::Element::cast_from(parent)
+        let parent = Rndr::cast_parent(parent)
             .expect("parent of keyed list should be an element");
 
         // build list
@@ -397,6 +523,7 @@ where
             marker,
             hashed_items,
             rendered_items,
+            leaving: Default::default(),
         }
     }
 
@@ -413,7 +540,7 @@ where
             Rndr::get_parent(&current)
                 .expect("first child of keyed list has no parent")
         };
-        let parent = crate::renderer::types::Element::cast_from(parent)
+        let parent = Rndr::cast_parent(parent)
             .expect("parent of keyed list should be an element");
 
         // build list
@@ -436,6 +563,7 @@ where
             marker,
             hashed_items,
             rendered_items,
+            leaving: Default::default(),
         }
     }
 
@@ -466,6 +594,10 @@ where
         for (_, item) in self.rendered_items.iter_mut().flatten() {
             item.unmount();
         }
+        let leaving = std::mem::take(&mut *self.leaving.borrow_mut());
+        for (_, mut item) in leaving {
+            item.unmount();
+        }
         self.marker.unmount();
     }
 
@@ -665,6 +797,7 @@ enum DiffOpAddMode {
     Append,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn apply_diff<T, VFS, V>(
     parent: Option<&crate::renderer::types::Element>,
     marker: &crate::renderer::types::Placeholder,
@@ -672,10 +805,40 @@ fn apply_diff<T, VFS, V>(
     children: &mut Vec<Option<(VFS, V::State)>>,
     view_fn: &dyn Fn(usize, T) -> (VFS, V),
     mut items: Vec<Option<T>>,
+    animation: Option<(&dyn ListAnimation, KeepLeaving<V::State>)>,
+    leaving: &Leaving,
 ) where
     VFS: Fn(usize),
     V: Render,
 {
+    let (animation, keep_leaving) = animation.unzip();
+    // every item that stays in the list may change position, so all of them are measured
+    // before the DOM is updated
+    let moving = animation
+        .filter(|_| !diff.clear)
+        .map(|animation| {
+            let removed = diff
+                .removed
+                .iter()
+                .map(|remove| remove.at)
+                .collect::<FxHashSet<_>>();
+            let elements = children
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !removed.contains(index))
+                .filter_map(|(_, child)| child.as_ref())
+                .flat_map(|(_, state)| state.elements())
+                .collect::<Vec<_>>();
+            if !elements.is_empty() {
+                animation.before_move(&elements);
+            }
+            elements
+        })
+        .unwrap_or_default();
+    let entering = animation
+        .map(|_| diff.added.iter().map(|add| add.at).collect::<Vec<_>>())
+        .unwrap_or_default();
+
     // The order of cmds needs to be:
     // 1. Clear
     // 2. Removals
@@ -685,8 +848,8 @@ fn apply_diff<T, VFS, V>(
     // 6. Additions
     // 7. Removes holes
     if diff.clear {
-        for (_, mut child) in children.drain(0..).flatten() {
-            child.unmount();
+        for (_, child) in children.drain(0..).flatten() {
+            remove_item(child, animation.zip(keep_leaving), leaving);
         }
 
         if diff.added.is_empty() {
@@ -695,9 +858,9 @@ fn apply_diff<T, VFS, V>(
     }
 
     for DiffOpRemove { at } in &diff.removed {
-        let (_, mut item_to_remove) = children[*at].take().unwrap();
+        let (_, item_to_remove) = children[*at].take().unwrap();
 
-        item_to_remove.unmount();
+        remove_item(item_to_remove, animation.zip(keep_leaving), leaving);
     }
 
     let (move_cmds, add_cmds) = unpack_moves(&diff);
@@ -775,6 +938,60 @@ fn apply_diff<T, VFS, V>(
 
     #[allow(unstable_name_collisions)]
     children.drain_filter(|c| c.is_none());
+
+    if let Some(animation) = animation {
+        if !moving.is_empty() {
+            animation.after_move(&moving);
+        }
+        for at in entering {
+            if let Some((_, state)) = &children[at] {
+                animation.enter(&state.elements());
+            }
+        }
+    }
+}
+
+/// Unmounts a removed item, or hands it to the list's animation and keeps it mounted until its
+/// leave animation has finished.
+fn remove_item<S>(
+    mut item: S,
+    animation: Option<(&dyn ListAnimation, KeepLeaving<S>)>,
+    leaving: &Leaving,
+) where
+    S: Mountable,
+{
+    let Some((animation, keep_leaving)) = animation else {
+        item.unmount();
+        return;
+    };
+    let elements = item.elements();
+    let token = Rc::new(());
+    leaving
+        .borrow_mut()
+        .push((Rc::clone(&token), keep_leaving(item)));
+    animation.leave(
+        &elements,
+        Box::new({
+            // if the list has been dropped, its leaving items have been dropped with it
+            let leaving = Rc::downgrade(leaving);
+            move || {
+                let Some(leaving) = leaving.upgrade() else {
+                    return;
+                };
+                let item = {
+                    let mut leaving = leaving.borrow_mut();
+                    leaving
+                        .iter()
+                        .position(|(other, _)| Rc::ptr_eq(other, &token))
+                        .map(|index| leaving.remove(index).1)
+                };
+                // dropping the item also disposes of its reactive owner
+                if let Some(mut item) = item {
+                    item.unmount();
+                }
+            }
+        }),
+    );
 }
 
 fn unpack_moves(diff: &Diff) -> (Vec<DiffOpMove>, Vec<DiffOpAdd>) {
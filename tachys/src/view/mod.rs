@@ -181,6 +181,29 @@ where
         builder.finish()
     }
 
+    /// Renders a view to an in-order stream of HTML, rendering into an existing buffer.
+    ///
+    /// The buffer can be recovered with [`StreamBuilder::into_buffer`] once the stream has been
+    /// consumed, and reused for another request.
+    fn to_html_stream_in_order_with_buffer(
+        self,
+        mut buf: String,
+    ) -> StreamBuilder
+    where
+        Self: Sized,
+    {
+        buf.reserve(self.html_len());
+        let mut builder = StreamBuilder::with_buffer(buf, None);
+        self.to_html_async_with_buf::<false>(
+            &mut builder,
+            &mut Position::FirstChild,
+            true,
+            false,
+            vec![],
+        );
+        builder.finish()
+    }
+
     /// Renders a view to an in-order stream of HTML with branch markers. This can be used to support libraries that diff
     /// HTML pages against one another, by marking sections of the view that branch to different
     /// types with marker comments.
@@ -218,6 +241,30 @@ where
         builder.finish()
     }
 
+    /// Renders a view to an out-of-order stream of HTML, rendering into an existing buffer.
+    ///
+    /// The buffer can be recovered with [`StreamBuilder::into_buffer`] once the stream has been
+    /// consumed, and reused for another request.
+    fn to_html_stream_out_of_order_with_buffer(
+        self,
+        mut buf: String,
+    ) -> StreamBuilder
+    where
+        Self: Sized,
+    {
+        buf.reserve(self.html_len());
+        let mut builder = StreamBuilder::with_buffer(buf, Some(vec![0]));
+
+        self.to_html_async_with_buf::<true>(
+            &mut builder,
+            &mut Position::FirstChild,
+            true,
+            false,
+            vec![],
+        );
+        builder.finish()
+    }
+
     /// Renders a view to an out-of-order stream of HTML with branch markers. This can be used to support libraries that diff
     /// HTML pages against one another, by marking sections of the view that branch to different
     /// types with marker comments.
@@ -221,9 +221,9 @@ impl<const V: &'static str> RenderHtml for Static<V> {
         }
 
         let node = cursor.current();
-        let node = crate::renderer::types::Text::cast_from(node.clone())
+        let node = crate::renderer::types::Text::cast_from(node)
             .unwrap_or_else(|| {
-                crate::hydration::failed_to_cast_text_node(node)
+                crate::hydration::failed_to_cast_text_node(cursor, V)
             });
 
         position.set(Position::NextChildAfterText);
@@ -93,9 +93,9 @@ impl RenderHtml for &str {
         }
 
         let node = cursor.current();
-        let node = crate::renderer::types::Text::cast_from(node.clone())
+        let node = crate::renderer::types::Text::cast_from(node)
             .unwrap_or_else(|| {
-                crate::hydration::failed_to_cast_text_node(node)
+                crate::hydration::failed_to_cast_text_node(cursor, self)
             });
 
         if !FROM_SERVER {